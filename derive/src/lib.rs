@@ -0,0 +1,301 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    bracketed, parse_macro_input, punctuated::Punctuated, Data, DeriveInput, Error, Fields,
+    LitChar, LitStr, Result, Token,
+};
+
+/// Derives the `ChatCommand` trait, along with a `TryFrom<Args>` implementation
+/// that parses the command's arguments into the fields of the struct.
+///
+/// The command is described with a `#[command(name = "...", about = "...")]`
+/// attribute, which may also list alternative names with `aliases = ["..."]`,
+/// and the permission level required to run it with `permission = Moderator`,
+/// and each field may be annotated with an `#[arg(...)]` attribute
+/// describing how it is parsed:
+///
+/// - no attribute: a required positional argument.
+/// - `#[arg(optional)]`: an optional positional argument, for `Option<T>` fields.
+/// - `#[arg(rest)]`: the remainder of the arguments as written, for free text.
+/// - `#[arg(flag)]`: a boolean `--flag`, for `bool` fields.
+/// - `#[arg(option)]`: an `--option value`, for `Option<T>` fields.
+///
+/// Flags and options may also set `short = 'x'` to accept `-x`, and any field
+/// may set `name = "..."` to override the name used in usage and errors.
+///
+/// ```ignore
+/// #[derive(Debug, PartialEq, ChatCommand)]
+/// #[command(name = "whisper", aliases = ["msg", "w"], about = "Send a private message to a user.")]
+/// pub struct Whisper {
+///     username: String,
+///     #[arg(rest)]
+///     message: String,
+/// }
+/// ```
+#[proc_macro_derive(ChatCommand, attributes(command, arg))]
+pub fn derive_chat_command(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(PartialEq)]
+enum ArgKind {
+    Positional,
+    Optional,
+    Rest,
+    Flag,
+    Option,
+}
+
+struct CommandAttrs {
+    name: String,
+    about: String,
+    aliases: Vec<String>,
+    permission: Ident,
+}
+
+struct Arg {
+    ident: Ident,
+    name: String,
+    kind: ArgKind,
+    short: Option<char>,
+}
+
+impl Arg {
+    /// The name of the argument when written as an option, e.g. `--dry-run`.
+    fn long(&self) -> String {
+        self.name.replace('_', "-")
+    }
+
+    fn usage(&self) -> String {
+        let short = self
+            .short
+            .map(|short| format!("|-{short}"))
+            .unwrap_or_default();
+
+        match self.kind {
+            ArgKind::Positional => format!("<{}>", self.name),
+            ArgKind::Optional => format!("[{}]", self.name),
+            ArgKind::Rest => format!("<{}...>", self.name),
+            ArgKind::Flag => format!("[--{}{}]", self.long(), short),
+            ArgKind::Option => format!("[--{}{} <{}>]", self.long(), short, self.name),
+        }
+    }
+
+    fn parse(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let name = &self.name;
+        let long = self.long();
+        let short = match self.short {
+            Some(short) => quote!(::core::option::Option::Some(#short)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        match self.kind {
+            ArgKind::Positional => quote!(let #ident = args.pop(#name)?;),
+            ArgKind::Optional => quote!(let #ident = args.pop_optional(#name)?;),
+            ArgKind::Rest => quote!(let #ident = args.rest(#name)?;),
+            ArgKind::Flag => quote!(let #ident = args.flag(#long, #short);),
+            ArgKind::Option => quote!(let #ident = args.option(#long, #short)?;),
+        }
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let CommandAttrs {
+        name,
+        about,
+        aliases,
+        permission,
+    } = parse_command_attrs(&input)?;
+    let args = parse_args(&input)?;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Options may appear anywhere, so they're removed before the positional
+    // arguments, which are then taken in the order they're declared.
+    let is_option = |arg: &&Arg| matches!(arg.kind, ArgKind::Flag | ArgKind::Option);
+    let options = args.iter().filter(is_option);
+    let positionals = args.iter().filter(|arg| !is_option(arg));
+
+    let usage = std::iter::once(format!("/{name}"))
+        .chain(positionals.clone().map(Arg::usage))
+        .chain(options.clone().map(Arg::usage))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    let parse = options.chain(positionals).map(Arg::parse);
+    let idents = args.iter().map(|arg| &arg.ident);
+
+    // Free text consumes every remaining argument, so there's nothing left over.
+    let finish = match args.iter().any(|arg| arg.kind == ArgKind::Rest) {
+        true => quote!(),
+        false => quote!(args.finish()?;),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::realtime_chat::traits::ChatCommand for #ident #ty_generics #where_clause {
+            const NAME: &'static str = #name;
+            const USAGE: &'static str = #usage;
+            const ABOUT: &'static str = #about;
+            const ALIASES: &'static [&'static str] = &[#(#aliases),*];
+            const PERMISSION: ::realtime_chat::domain::Permission =
+                ::realtime_chat::domain::Permission::#permission;
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<::realtime_chat::args::Args> for #ident #ty_generics #where_clause {
+            type Error = ::realtime_chat::errors::CommandError;
+
+            #[allow(unused_mut)]
+            fn try_from(mut args: ::realtime_chat::args::Args) -> ::core::result::Result<Self, Self::Error> {
+                #(#parse)*
+                #finish
+
+                ::core::result::Result::Ok(Self { #(#idents),* })
+            }
+        }
+    })
+}
+
+fn parse_command_attrs(input: &DeriveInput) -> Result<CommandAttrs> {
+    let mut name = None;
+    let mut about = String::new();
+    let mut aliases = vec![];
+    let mut permission = Ident::new("User", Span::call_site());
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("command"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("about") {
+                about = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("aliases") {
+                let value = meta.value()?;
+                let content;
+                bracketed!(content in value);
+
+                let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                aliases.extend(names.iter().map(LitStr::value));
+            } else if meta.path.is_ident("permission") {
+                permission = meta.value()?.parse()?;
+            } else {
+                return Err(meta.error("unknown command attribute"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    let name = name.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            r#"missing #[command(name = "...")] attribute"#,
+        )
+    })?;
+
+    Ok(CommandAttrs {
+        name,
+        about,
+        aliases,
+        permission,
+    })
+}
+
+fn parse_args(input: &DeriveInput) -> Result<Vec<Arg>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => vec![],
+            Fields::Unnamed(_) => {
+                return Err(Error::new_spanned(input, "commands must have named fields"))
+            }
+        },
+        _ => return Err(Error::new_spanned(input, "commands must be structs")),
+    };
+
+    let mut args: Vec<Arg> = vec![];
+
+    for field in fields {
+        // Named fields always have an identifier.
+        let ident = field.ident.clone().unwrap();
+
+        let mut arg = Arg {
+            name: ident.to_string(),
+            ident,
+            kind: ArgKind::Positional,
+            short: None,
+        };
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("arg"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let kind = match meta.path.get_ident().map(Ident::to_string).as_deref() {
+                    Some("optional") => ArgKind::Optional,
+                    Some("rest") => ArgKind::Rest,
+                    Some("flag") => ArgKind::Flag,
+                    Some("option") => ArgKind::Option,
+                    Some("name") => {
+                        arg.name = meta.value()?.parse::<LitStr>()?.value();
+                        return Ok(());
+                    }
+                    Some("short") => {
+                        arg.short = Some(meta.value()?.parse::<LitChar>()?.value());
+                        return Ok(());
+                    }
+                    _ => return Err(meta.error("unknown arg attribute")),
+                };
+
+                arg.kind = kind;
+
+                Ok(())
+            })?;
+        }
+
+        if arg.short.is_some() && !matches!(arg.kind, ArgKind::Flag | ArgKind::Option) {
+            return Err(Error::new_spanned(
+                field,
+                "only flags and options can have a short name",
+            ));
+        }
+
+        // Positional arguments can't follow free text, and required positional
+        // arguments can't follow optional ones.
+        let previous = args.iter().rev().find(|arg| {
+            matches!(
+                arg.kind,
+                ArgKind::Positional | ArgKind::Optional | ArgKind::Rest
+            )
+        });
+
+        let is_misplaced = matches!(
+            (previous.map(|arg| &arg.kind), &arg.kind),
+            (
+                Some(ArgKind::Rest),
+                ArgKind::Positional | ArgKind::Optional | ArgKind::Rest
+            ) | (Some(ArgKind::Optional), ArgKind::Positional)
+        );
+
+        if is_misplaced {
+            return Err(Error::new_spanned(
+                field,
+                "required arguments must come before optional arguments and free text",
+            ));
+        }
+
+        args.push(arg);
+    }
+
+    Ok(args)
+}
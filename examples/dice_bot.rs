@@ -0,0 +1,126 @@
+//! A server with a dice rolling bot registered as a plugin. Run it with
+//! `cargo run --example dice_bot`, then connect with the client and `/roll 2d6`.
+
+use async_trait::async_trait;
+use rand::Rng;
+use realtime_chat::{
+    args::{Args, FromArg},
+    commands::CommandInfo,
+    domain::{Permission, PluginContext, PluginEvent, Username},
+    errors::CommandError,
+    server::ChatServer,
+    traits::Plugin,
+};
+
+// Limits that keep a single roll to a reasonably sized message.
+const MAX_DICE: u32 = 20;
+const MAX_SIDES: u32 = 1000;
+
+/// A number of dice with the same number of sides, written as `2d6`.
+struct Dice {
+    count: u32,
+    sides: u32,
+}
+
+impl FromArg for Dice {
+    fn from_arg(value: &str) -> Result<Self, String> {
+        let invalid = || format!("{value} is not a valid roll (e.g. d20, 2d6)");
+
+        let (count, sides) = value.split_once('d').ok_or_else(invalid)?;
+        let count = match count {
+            "" => 1,
+            count => count.parse().map_err(|_| invalid())?,
+        };
+        let sides = sides.parse().map_err(|_| invalid())?;
+
+        if !(1..=MAX_DICE).contains(&count) || !(2..=MAX_SIDES).contains(&sides) {
+            return Err(format!(
+                "rolls are limited to {MAX_DICE} dice with 2 to {MAX_SIDES} sides"
+            ));
+        }
+
+        Ok(Self { count, sides })
+    }
+}
+
+struct DiceBot;
+
+#[async_trait]
+impl Plugin for DiceBot {
+    fn name(&self) -> &str {
+        "dice"
+    }
+
+    fn commands(&self) -> Vec<CommandInfo> {
+        vec![CommandInfo {
+            name: "roll",
+            usage: "/roll [dice]",
+            about: "Roll some dice, e.g. 2d6. Rolls a single d6 by default.",
+            aliases: &["dice"],
+            permission: Permission::User,
+        }]
+    }
+
+    async fn on_command(
+        &self,
+        ctx: &PluginContext,
+        sender: &Username,
+        _name: &str,
+        mut args: Args,
+    ) -> Result<(), CommandError> {
+        let dice = args
+            .pop_optional("dice")?
+            .unwrap_or(Dice { count: 1, sides: 6 });
+        args.finish()?;
+
+        let rolls: Vec<u32> = {
+            let mut rng = rand::thread_rng();
+
+            (0..dice.count)
+                .map(|_| rng.gen_range(1..=dice.sides))
+                .collect()
+        };
+
+        let total: u32 = rolls.iter().sum();
+        let rolls: Vec<String> = rolls.iter().map(u32::to_string).collect();
+
+        ctx.say(&format!(
+            "{} rolled {}d{}: {} = {}",
+            sender,
+            dice.count,
+            dice.sides,
+            rolls.join(" + "),
+            total
+        ))
+        .await;
+
+        Ok(())
+    }
+
+    async fn on_event(&self, ctx: &PluginContext, event: &PluginEvent) {
+        if let PluginEvent::Join(username) = event {
+            ctx.say_to(username, "Feeling lucky? Try /roll 2d6").await;
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt().compact().init();
+
+    let server = ChatServer::builder()
+        .bind("127.0.0.1:8080")
+        .plugin(DiceBot)
+        .start()
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        });
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::error!("Failed to listen for Ctrl-C: {}", e);
+    }
+
+    server.shutdown().await;
+}
@@ -0,0 +1,761 @@
+use crate::{
+    domain::{Emoji, Mention},
+    errors::CommandError,
+};
+use std::{collections::VecDeque, ops::Range, time::Duration};
+
+/// A single argument from a command, with any quotes and escapes resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub value: String,
+    /// Whether the token is an option, such as `--force` or `-f`. Quoted
+    /// tokens, negative numbers and tokens following `--` are never options.
+    pub is_option: bool,
+    // Whether the token is the `--` that ends the options. It's only kept by
+    // `Args`, so that free text read with `rest` may start with `--`.
+    is_end_of_options: bool,
+    // Byte range of the token in the original input, including any whitespace
+    // that follows it.
+    span: Range<usize>,
+}
+
+/// Splits a command into tokens, delimited by any amount of whitespace.
+///
+/// Arguments containing whitespace may be wrapped in double or single quotes,
+/// and a backslash escapes the following character, except within single
+/// quotes. For example, `whisper "some user" it\'s me` is split into
+/// `whisper`, `some user`, `it's` and `me`.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, CommandError> {
+    match tokenize_prefix(input) {
+        (tokens, None) => Ok(tokens),
+        (_, Some(_)) => Err(CommandError::UnterminatedQuote),
+    }
+}
+
+/// Splits as much of a command into tokens as possible, stopping at the first
+/// quote that isn't terminated. Returns the tokens, along with the byte offset
+/// of the token containing the unterminated quote, if there is one.
+pub fn tokenize_prefix(input: &str) -> (Vec<Token>, Option<usize>) {
+    let (mut tokens, unterminated) = tokenize_with_markers(input);
+    tokens.retain(|token| !token.is_end_of_options);
+
+    (tokens, unterminated)
+}
+
+/// Like `tokenize_prefix`, but keeps the `--` that ends the options.
+fn tokenize_with_markers(input: &str) -> (Vec<Token>, Option<usize>) {
+    let mut tokens: Vec<Token> = vec![];
+    let mut chars = input.char_indices().peekable();
+    let mut end_of_options = false;
+
+    loop {
+        // Skip the whitespace between tokens.
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        let Some(&(start, _)) = chars.peek() else {
+            break;
+        };
+
+        let mut value = String::new();
+        let mut quoted = false;
+
+        while let Some((_, c)) = chars.next_if(|(_, c)| !c.is_whitespace()) {
+            match c {
+                '"' | '\'' => {
+                    quoted = true;
+
+                    loop {
+                        match chars.next() {
+                            Some((_, q)) if q == c => break,
+                            Some((_, '\\')) if c == '"' => {
+                                value.push(chars.next().map_or('\\', |(_, c)| c))
+                            }
+                            Some((_, q)) => value.push(q),
+                            None => {
+                                set_span_ends(&mut tokens, start);
+                                return (tokens, Some(start));
+                            }
+                        }
+                    }
+                }
+                // A trailing backslash has nothing to escape, so it's kept as is.
+                '\\' => value.push(chars.next().map_or('\\', |(_, c)| c)),
+                c => value.push(c),
+            }
+        }
+
+        let is_end_of_options = !quoted && !end_of_options && value == "--";
+        let is_option = !quoted
+            && !end_of_options
+            && !is_end_of_options
+            && value.len() > 1
+            && value.starts_with('-')
+            && value.parse::<f64>().is_err();
+
+        end_of_options |= is_end_of_options;
+
+        tokens.push(Token {
+            value,
+            is_option,
+            is_end_of_options,
+            span: start..start,
+        });
+    }
+
+    set_span_ends(&mut tokens, input.len());
+
+    (tokens, None)
+}
+
+/// Extends the span of each token up to the start of the next, and the last
+/// up to the provided end.
+fn set_span_ends(tokens: &mut [Token], end: usize) {
+    let starts: Vec<usize> = tokens
+        .iter()
+        .skip(1)
+        .map(|token| token.span.start)
+        .collect();
+
+    for (token, end) in tokens.iter_mut().zip(starts.into_iter().chain([end])) {
+        token.span.end = end;
+    }
+}
+
+/// Quotes a value, if needed, so that `tokenize` reads it back as a single
+/// positional argument with the same value.
+pub fn quote(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.starts_with('-')
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\'));
+
+    if !needs_quotes {
+        return value.to_owned();
+    }
+
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A type that can be parsed from a command argument. On failure, returns
+/// the reason the argument is invalid.
+pub trait FromArg: Sized {
+    fn from_arg(value: &str) -> Result<Self, String>;
+}
+
+impl FromArg for String {
+    fn from_arg(value: &str) -> Result<Self, String> {
+        Ok(value.to_owned())
+    }
+}
+
+macro_rules! impl_from_arg_for_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl FromArg for $ty {
+                fn from_arg(value: &str) -> Result<Self, String> {
+                    value.parse().map_err(|_| format!("{value} is not a valid number"))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_arg_for_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl FromArg for bool {
+    /// Parses a switch, written as `on` or `off`.
+    fn from_arg(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "on" => Ok(true),
+            "off" => Ok(false),
+            _ => Err(format!("{value} is not on or off")),
+        }
+    }
+}
+
+impl FromArg for Duration {
+    /// Parses a duration made up of whole numbers followed by a unit, for
+    /// example `30s`, `5m` or `1h30m`. A number without a unit is in seconds.
+    fn from_arg(value: &str) -> Result<Self, String> {
+        let invalid = || format!("{value} is not a valid duration (e.g. 30s, 5m, 1h30m)");
+
+        if value.is_empty() {
+            return Err(invalid());
+        }
+
+        if let Ok(seconds) = value.parse() {
+            return Ok(Duration::from_secs(seconds));
+        }
+
+        let mut seconds: u64 = 0;
+        let mut digits = String::new();
+
+        for c in value.chars() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+
+            let unit = match c {
+                's' => 1,
+                'm' => 60,
+                'h' => 60 * 60,
+                'd' => 60 * 60 * 24,
+                _ => return Err(invalid()),
+            };
+
+            let amount: u64 = digits.parse().map_err(|_| invalid())?;
+            seconds = amount
+                .checked_mul(unit)
+                .and_then(|amount| seconds.checked_add(amount))
+                .ok_or_else(invalid)?;
+
+            digits.clear();
+        }
+
+        // Every number must be followed by a unit.
+        if !digits.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Duration::from_secs(seconds))
+    }
+}
+
+impl FromArg for Mention {
+    /// Parses a username, written as `nickname` or `nickname!tripcode`,
+    /// optionally prefixed with an `@`.
+    fn from_arg(value: &str) -> Result<Self, String> {
+        let username = value.strip_prefix('@').unwrap_or(value);
+
+        Mention::parse(username).ok_or_else(|| format!("{value} is not a valid username"))
+    }
+}
+
+impl FromArg for Emoji {
+    /// Parses an emoji, or a shortcode such as `:thumbsup:`.
+    fn from_arg(value: &str) -> Result<Self, String> {
+        Emoji::resolve(value).ok_or_else(|| format!("{value} is not a known emoji"))
+    }
+}
+
+/// The arguments passed to a command.
+///
+/// Commands should retrieve their options first, followed by their positional
+/// arguments, and finally call `finish` to reject anything left over.
+///
+/// Quotes only need to be terminated where they're read as arguments, so free
+/// text read with `rest` may contain apostrophes, e.g. `/me it's raining`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    input: String,
+    tokens: VecDeque<Token>,
+    // Byte offset of a quote that isn't terminated. The input from here on
+    // can only be read as free text.
+    unterminated: Option<usize>,
+}
+
+impl From<&str> for Args {
+    fn from(input: &str) -> Self {
+        let (tokens, unterminated) = tokenize_with_markers(input);
+
+        Self {
+            input: input.to_owned(),
+            tokens: tokens.into(),
+            unterminated,
+        }
+    }
+}
+
+impl Args {
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty() && self.unterminated.is_none()
+    }
+
+    /// Removes a boolean option, written as `--long` or `-s`, returning whether
+    /// it was present. Short options may be combined, e.g. `-fv`.
+    pub fn flag(&mut self, long: &str, short: Option<char>) -> bool {
+        let mut found = false;
+
+        self.tokens.retain_mut(|token| {
+            if !token.is_option {
+                return true;
+            }
+
+            if token.value.strip_prefix("--") == Some(long) {
+                found = true;
+                return false;
+            }
+
+            let Some(short) = short.filter(|_| !token.value.starts_with("--")) else {
+                return true;
+            };
+
+            if token.value.contains(short) {
+                found = true;
+                token.value = token.value.replacen(short, "", 1);
+            }
+
+            // Keep any other combined short options.
+            token.value != "-"
+        });
+
+        found
+    }
+
+    /// Removes an option with a value, written as `--long value`, `--long=value`
+    /// or `-s value`, and parses its value.
+    pub fn option<T: FromArg>(
+        &mut self,
+        long: &str,
+        short: Option<char>,
+    ) -> Result<Option<T>, CommandError> {
+        let short = short.map(|short| format!("-{short}"));
+
+        for (index, token) in self.tokens.iter().enumerate() {
+            if !token.is_option {
+                continue;
+            }
+
+            let name = token.value.strip_prefix("--");
+
+            if let Some(value) = name.and_then(|name| name.strip_prefix(long)?.strip_prefix('=')) {
+                let value = parse_arg(long, value)?;
+                self.tokens.remove(index);
+
+                return Ok(Some(value));
+            }
+
+            if name == Some(long) || short.as_ref() == Some(&token.value) {
+                // The value may follow a `--`, when it looks like an option.
+                let next = self
+                    .tokens
+                    .iter()
+                    .skip(index + 1)
+                    .position(|token| !token.is_end_of_options)
+                    .map(|offset| index + 1 + offset);
+
+                let (next, value) = match next.map(|next| (next, &self.tokens[next])) {
+                    Some((next, token)) if !token.is_option => {
+                        (next, parse_arg(long, &token.value)?)
+                    }
+                    _ => return Err(CommandError::MissingArgument(long.into())),
+                };
+
+                self.tokens.remove(next);
+                self.tokens.remove(index);
+
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Removes the next positional argument and parses it. Returns a
+    /// `CommandError::MissingArgument` error variant if there are none left.
+    pub fn pop<T: FromArg>(&mut self, name: &str) -> Result<T, CommandError> {
+        self.pop_optional(name)?
+            .ok_or_else(|| CommandError::MissingArgument(name.into()))
+    }
+
+    /// Removes the next positional argument, if any, and parses it.
+    pub fn pop_optional<T: FromArg>(&mut self, name: &str) -> Result<Option<T>, CommandError> {
+        let Some(index) = self
+            .tokens
+            .iter()
+            .position(|token| !token.is_option && !token.is_end_of_options)
+        else {
+            return match self.unterminated {
+                Some(_) => Err(CommandError::UnterminatedQuote),
+                None => Ok(None),
+            };
+        };
+
+        let value = parse_arg(name, &self.tokens[index].value)?;
+        self.tokens.remove(index);
+
+        // The `--` has done its job once an argument following it is read, so
+        // it's no longer part of the free text read with `rest`.
+        if let Some(marker) = self.tokens.iter().position(|token| token.is_end_of_options) {
+            if marker < index {
+                self.tokens.remove(marker);
+            }
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Removes the remainder of the arguments, returning them exactly as they
+    /// were written, with quotes and escapes left intact. This is intended for
+    /// free text, such as the message in a whisper, so quotes don't need to
+    /// be terminated. Options that have already been removed aren't included.
+    pub fn rest(&mut self, name: &str) -> Result<String, CommandError> {
+        if self.is_empty() {
+            return Err(CommandError::MissingArgument(name.into()));
+        }
+
+        let mut rest: String = self
+            .tokens
+            .drain(..)
+            .map(|token| &self.input[token.span])
+            .collect();
+
+        if let Some(start) = self.unterminated.take() {
+            rest.push_str(&self.input[start..]);
+        }
+
+        Ok(rest.trim_end().to_owned())
+    }
+
+    /// Consumes the remaining arguments, including options, as plain values
+    /// in the order they were written, leaving out any `--`. Anything after a quote that isn't
+    /// terminated is kept as a single value, exactly as it was written.
+    pub fn into_values(self) -> Vec<String> {
+        let tail = self
+            .unterminated
+            .map(|start| self.input[start..].trim_end().to_owned());
+
+        self.tokens
+            .into_iter()
+            .filter(|token| !token.is_end_of_options)
+            .map(|token| token.value)
+            .chain(tail)
+            .collect()
+    }
+
+    /// Ensures that every argument has been consumed.
+    pub fn finish(self) -> Result<(), CommandError> {
+        let mut tokens = self.tokens.iter().filter(|token| !token.is_end_of_options);
+
+        match tokens.next() {
+            Some(token) if token.is_option => Err(CommandError::UnknownOption(token.value.clone())),
+            Some(_) => Err(CommandError::TooManyArguments),
+            None if self.unterminated.is_some() => Err(CommandError::UnterminatedQuote),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_arg<T: FromArg>(name: &str, value: &str) -> Result<T, CommandError> {
+    T::from_arg(value).map_err(|reason| CommandError::InvalidArgument(name.into(), reason))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+    use proptest::prelude::*;
+
+    fn values(input: &str) -> Vec<String> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.value)
+            .collect()
+    }
+
+    #[test]
+    fn ignores_repeated_whitespace() {
+        assert_eq!(
+            values("  some   user \t hello  "),
+            ["some", "user", "hello"]
+        );
+    }
+
+    #[test]
+    fn groups_quoted_arguments() {
+        assert_eq!(
+            values(r#"whisper "some user" 'hello there'"#),
+            ["whisper", "some user", "hello there"]
+        );
+    }
+
+    #[test]
+    fn joins_adjacent_quoted_and_unquoted_text() {
+        assert_eq!(values(r#"some" user"s"#), ["some users"]);
+    }
+
+    #[test]
+    fn resolves_escapes() {
+        assert_eq!(
+            values(r#"it\'s "a \"quote\"" 'no \escape' \"#),
+            ["it's", r#"a "quote""#, r"no \escape", "\\"]
+        );
+    }
+
+    #[test]
+    fn keeps_empty_quoted_arguments() {
+        assert_eq!(values(r#"a "" b"#), ["a", "", "b"]);
+    }
+
+    #[test]
+    fn returns_error_if_quote_is_unterminated() {
+        assert_eq!(
+            tokenize(r#"say "hello"#),
+            Err(CommandError::UnterminatedQuote)
+        );
+    }
+
+    #[test]
+    fn identifies_options() {
+        let tokens = tokenize(r#"--force -f - -5 "--quoted" -- --after"#).unwrap();
+        let options: Vec<bool> = tokens.iter().map(|token| token.is_option).collect();
+
+        assert_eq!(options, [true, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn pops_positional_arguments_in_order() {
+        let first: String = Word().fake();
+        let second: String = Word().fake();
+        let mut args = Args::from(format!("{first} {second}").as_str());
+
+        assert_eq!(args.pop::<String>("first"), Ok(first));
+        assert_eq!(args.pop::<String>("second"), Ok(second));
+        assert_eq!(args.finish(), Ok(()));
+    }
+
+    #[test]
+    fn returns_error_if_no_arg() {
+        let mut args = Args::from("");
+        let name: String = Word().fake();
+
+        let result = args.pop::<String>(&name);
+
+        assert_eq!(result, Err(CommandError::MissingArgument(name)))
+    }
+
+    #[test]
+    fn returns_error_naming_invalid_argument() {
+        let mut args = Args::from("abc");
+
+        let result = args.pop::<u64>("id");
+        let expected =
+            CommandError::InvalidArgument("id".into(), "abc is not a valid number".into());
+
+        assert_eq!(result, Err(expected));
+    }
+
+    #[test]
+    fn removes_flags() {
+        let mut args = Args::from("-fv --all word");
+
+        assert!(args.flag("force", Some('f')));
+        assert!(args.flag("all", None));
+        assert!(!args.flag("quiet", Some('q')));
+        assert!(args.flag("verbose", Some('v')));
+        assert_eq!(args.pop::<String>("word"), Ok("word".into()));
+        assert_eq!(args.finish(), Ok(()));
+    }
+
+    #[test]
+    fn parses_options_with_values() {
+        let mut args = Args::from("--duration=5m -n 3 --reason spam");
+
+        assert_eq!(
+            args.option::<Duration>("duration", Some('d')),
+            Ok(Some(Duration::from_secs(300)))
+        );
+        assert_eq!(args.option::<u32>("count", Some('n')), Ok(Some(3)));
+        assert_eq!(
+            args.option::<String>("reason", None),
+            Ok(Some("spam".into()))
+        );
+        assert_eq!(args.finish(), Ok(()));
+    }
+
+    #[test]
+    fn returns_error_if_option_is_missing_value() {
+        let mut args = Args::from("--duration");
+
+        let result = args.option::<Duration>("duration", None);
+
+        assert_eq!(
+            result,
+            Err(CommandError::MissingArgument("duration".into()))
+        );
+    }
+
+    #[test]
+    fn returns_error_for_unknown_option() {
+        let args = Args::from("--unknown");
+
+        assert_eq!(
+            args.finish(),
+            Err(CommandError::UnknownOption("--unknown".into()))
+        );
+    }
+
+    #[test]
+    fn returns_error_for_extra_arguments() {
+        let args = Args::from("extra");
+
+        assert_eq!(args.finish(), Err(CommandError::TooManyArguments));
+    }
+
+    #[test]
+    fn rest_returns_raw_remainder() {
+        let mut args = Args::from(r#"user  hello   "there"  "#);
+
+        args.pop::<String>("username").unwrap();
+
+        assert_eq!(args.rest("message"), Ok(r#"hello   "there""#.into()));
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn rest_reads_unterminated_quotes_as_written() {
+        let mut args = Args::from("bob don't do that ");
+
+        assert_eq!(args.pop::<String>("username"), Ok("bob".into()));
+        assert_eq!(args.rest("message"), Ok("don't do that".into()));
+        assert_eq!(args.finish(), Ok(()));
+    }
+
+    #[test]
+    fn rest_excludes_removed_options() {
+        let mut args = Args::from("--loud hello -f  there --reason spam");
+
+        assert!(args.flag("loud", None));
+        assert!(args.flag("force", Some('f')));
+        assert_eq!(
+            args.option::<String>("reason", None),
+            Ok(Some("spam".into()))
+        );
+        assert_eq!(args.rest("message"), Ok("hello there".into()));
+    }
+
+    #[test]
+    fn rest_keeps_double_dash_as_written() {
+        let mut args = Args::from("-- is confused");
+
+        assert!(!args.flag("force", Some('f')));
+        assert_eq!(args.rest("message"), Ok("-- is confused".into()));
+    }
+
+    #[test]
+    fn rest_excludes_double_dash_once_argument_after_it_is_read() {
+        let mut args = Args::from("-- -bob -- hi");
+
+        assert_eq!(args.pop::<String>("username"), Ok("-bob".into()));
+        assert_eq!(args.rest("message"), Ok("-- hi".into()));
+    }
+
+    #[test]
+    fn reads_option_value_after_double_dash() {
+        let mut args = Args::from("alice --reason -- -spam");
+
+        assert_eq!(
+            args.option::<String>("reason", None),
+            Ok(Some("-spam".into()))
+        );
+        assert_eq!(args.pop::<String>("username"), Ok("alice".into()));
+        assert_eq!(args.finish(), Ok(()));
+    }
+
+    #[test]
+    fn finishes_with_only_double_dash_left() {
+        let mut args = Args::from("alice --");
+
+        assert_eq!(args.pop::<String>("username"), Ok("alice".into()));
+        assert_eq!(args.clone().into_values(), Vec::<String>::new());
+        assert_eq!(args.finish(), Ok(()));
+    }
+
+    #[test]
+    fn returns_error_if_argument_has_unterminated_quote() {
+        let mut args = Args::from("it's");
+
+        assert_eq!(
+            args.clone().pop::<String>("word"),
+            Err(CommandError::UnterminatedQuote)
+        );
+        assert_eq!(args.clone().finish(), Err(CommandError::UnterminatedQuote));
+        assert_eq!(args.rest("message"), Ok("it's".into()));
+    }
+
+    #[test]
+    fn into_values_returns_remaining_arguments_in_order() {
+        let args = Args::from(r#"--loud "some user" -x hi"#);
+
+        assert_eq!(args.into_values(), ["--loud", "some user", "-x", "hi"]);
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(Duration::from_arg("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(Duration::from_arg("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(Duration::from_arg("2d"), Ok(Duration::from_secs(172800)));
+        assert!(Duration::from_arg("5x").is_err());
+        assert!(Duration::from_arg("5m3").is_err());
+        assert!(Duration::from_arg("m").is_err());
+    }
+
+    #[test]
+    fn parses_switches() {
+        assert_eq!(bool::from_arg("on"), Ok(true));
+        assert_eq!(bool::from_arg("OFF"), Ok(false));
+        assert!(bool::from_arg("yes").is_err());
+    }
+
+    #[test]
+    fn parses_usernames() {
+        assert_eq!(
+            Mention::from_arg("@user!uQ8unuo3Mk"),
+            Ok(Mention::new("user".into(), Some("uQ8unuo3Mk".into())))
+        );
+        assert!(Mention::from_arg("@").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn tokenize_never_panics(input in any::<String>()) {
+            let _ = tokenize(&input);
+        }
+
+        #[test]
+        fn splits_unquoted_words_like_split_whitespace(
+            words in prop::collection::vec("[a-zA-Z0-9_!@#.]{1,12}", 0..8),
+            separators in prop::collection::vec("[ \t]{1,4}", 8),
+        ) {
+            let input: String = words
+                .iter()
+                .zip(&separators)
+                .map(|(word, separator)| format!("{separator}{word}"))
+                .collect();
+
+            prop_assert_eq!(values(&input), words);
+        }
+
+        #[test]
+        fn quoted_values_round_trip(values_in in prop::collection::vec(any::<String>(), 0..6)) {
+            let input: Vec<String> = values_in.iter().map(|value| quote(value)).collect();
+
+            prop_assert_eq!(values(&input.join(" ")), values_in);
+        }
+
+        #[test]
+        fn quoted_values_are_never_options(value in any::<String>()) {
+            let tokens = tokenize(&quote(&value)).unwrap();
+
+            prop_assert!(tokens.iter().all(|token| !token.is_option));
+        }
+
+        #[test]
+        fn parses_integers(value in any::<i64>()) {
+            let mut args = Args::from(value.to_string().as_str());
+
+            prop_assert_eq!(args.pop::<i64>("value"), Ok(value));
+        }
+
+        #[test]
+        fn parses_durations_with_units(hours in 0u64..1000, minutes in 0u64..60, seconds in 0u64..60) {
+            let value = format!("{hours}h{minutes}m{seconds}s");
+            let expected = Duration::from_secs(hours * 3600 + minutes * 60 + seconds);
+
+            prop_assert_eq!(Duration::from_arg(&value), Ok(expected));
+        }
+    }
+}
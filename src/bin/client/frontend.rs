@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use realtime_chat::{client::StyledLine, frame::Frame};
+
+/// The state of the connection to the server, as shown to the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Reconnecting,
+}
+
+/// Something the user did that the client passes on to the server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserInput {
+    Line(String),
+    /// The user started or stopped typing a message.
+    Typing(bool),
+}
+
+/// Where the client reads what the user types, and displays what it receives
+/// from the server.
+#[async_trait]
+pub trait Frontend: Send {
+    /// Waits for the next line typed by the user, or for them to start or stop
+    /// typing one. Returns `None` once the user has quit.
+    async fn next_input(&mut self) -> Option<UserInput>;
+
+    /// Displays a frame received from the server, which has been rendered as
+    /// the provided lines.
+    fn show(&mut self, frame: &Frame, lines: Vec<StyledLine>);
+
+    /// Displays a line about the client itself, rather than the chat, such as
+    /// the state of the connection.
+    fn notice(&mut self, message: &str);
+
+    fn set_state(&mut self, state: ConnectionState);
+
+    /// Shows which server the client is connected to, and as who.
+    fn set_title(&mut self, _title: String) {}
+
+    /// Clears everything displayed so far.
+    fn clear(&mut self) {}
+}
@@ -0,0 +1,223 @@
+use crate::frontend::{ConnectionState, Frontend, UserInput};
+use async_trait::async_trait;
+use realtime_chat::{
+    client::{is_secret, Colour, Completion, HistoryFile, LineKind, OnlineUsers, StyledLine},
+    frame::Frame,
+};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, CompletionType, Config, Context, Editor,
+    ExternalPrinter, Helper,
+};
+use std::{
+    io::{stdin, IsTerminal},
+    sync::{Arc, Mutex},
+    thread,
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// What the user did at the prompt.
+enum Input {
+    Line(String),
+    Quit,
+}
+
+/// Reads lines from stdin with line editing, history and tab completion, and
+/// prints each frame on its own line, which keeps the client simple to script.
+pub struct LineFrontend {
+    input: UnboundedReceiver<Input>,
+    /// Prints above the line being edited, when stdin is a terminal.
+    printer: Option<Box<dyn ExternalPrinter + Send>>,
+    users: Arc<Mutex<OnlineUsers>>,
+}
+
+impl LineFrontend {
+    /// Starts reading lines on a separate thread, as reading is blocking. The
+    /// lines are saved to the history file, if there is one.
+    pub fn new(history: Option<HistoryFile>) -> rustyline::Result<Self> {
+        let users = Arc::new(Mutex::new(OnlineUsers::default()));
+        let config = Config::builder()
+            .completion_type(CompletionType::List)
+            .build();
+        let mut editor = Editor::with_config(config)?;
+
+        editor.set_helper(Some(Completions {
+            users: users.clone(),
+        }));
+
+        for line in history.iter().flat_map(|history| history.load()).flatten() {
+            editor.add_history_entry(line)?;
+        }
+
+        let printer = editor
+            .create_external_printer()
+            .ok()
+            .map(|printer| Box::new(printer) as Box<dyn ExternalPrinter + Send>);
+
+        let (sender, input) = unbounded_channel();
+        thread::spawn(move || read_lines(editor, history, sender));
+
+        Ok(Self {
+            input,
+            printer,
+            users,
+        })
+    }
+
+    fn print(&mut self, message: String) {
+        match &mut self.printer {
+            Some(printer) => {
+                let _ = printer.print(message);
+            }
+            None => println!("{}", message),
+        }
+    }
+}
+
+#[async_trait]
+impl Frontend for LineFrontend {
+    async fn next_input(&mut self) -> Option<UserInput> {
+        match self.input.recv().await {
+            Some(Input::Line(line)) => Some(UserInput::Line(line)),
+            Some(Input::Quit) => None,
+            // Keep receiving messages once stdin is closed, e.g. when input
+            // is piped in.
+            None => std::future::pending().await,
+        }
+    }
+
+    fn show(&mut self, frame: &Frame, lines: Vec<StyledLine>) {
+        if let Frame::Presence(presence) = frame {
+            if let (Ok(presence), Ok(mut users)) = (presence.parse(), self.users.lock()) {
+                users.apply(presence);
+            }
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+
+        let mut message = lines.iter().map(paint).collect::<Vec<_>>().join("\n");
+
+        // Ring the terminal bell for messages that mention us.
+        if lines.iter().any(|line| line.kind == LineKind::Mention) {
+            message.push('\x07');
+        }
+
+        self.print(message);
+    }
+
+    fn notice(&mut self, message: &str) {
+        // Dimmed to set it apart from chat messages.
+        self.print(format!("\x1b[2m-- {}\x1b[0m", message));
+    }
+
+    fn set_state(&mut self, state: ConnectionState) {
+        if state != ConnectionState::Connected {
+            // The server sends the online users again once reconnected.
+            if let Ok(mut users) = self.users.lock() {
+                users.clear();
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        // Clear the terminal, and move the cursor to the top.
+        self.print(String::from("\x1b[2J\x1b[H"));
+    }
+}
+
+/// Reads lines until the user quits, or stdin is closed.
+fn read_lines(
+    mut editor: Editor<Completions, DefaultHistory>,
+    history: Option<HistoryFile>,
+    sender: UnboundedSender<Input>,
+) {
+    loop {
+        let input = match editor.readline("") {
+            Ok(line) => {
+                // Commands with passwords aren't kept in any history.
+                if !is_secret(&line) && editor.add_history_entry(line.as_str()).unwrap_or(false) {
+                    if let Some(Err(e)) = history.as_ref().map(|history| history.append(&line)) {
+                        eprintln!("Failed to save input history: {}", e);
+                    }
+                }
+
+                Input::Line(line)
+            }
+            // Closing a terminal's input quits, like Ctrl-C.
+            Err(ReadlineError::Eof) if stdin().is_terminal() => Input::Quit,
+            Err(ReadlineError::Interrupted) => Input::Quit,
+            Err(ReadlineError::Eof) => return,
+            Err(e) => {
+                eprintln!("Failed to read input: {:?}", e);
+                return;
+            }
+        };
+
+        if sender.send(input).is_err() {
+            return;
+        }
+    }
+}
+
+/// Completes command names and the usernames of online users.
+struct Completions {
+    users: Arc<Mutex<OnlineUsers>>,
+}
+
+impl Completer for Completions {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let Ok(users) = self.users.lock() else {
+            return Ok((pos, vec![]));
+        };
+
+        let mut completion = Completion::new(line, pos, &users);
+
+        // A single candidate is followed by a space, ready for the next word.
+        if let [candidate] = completion.candidates.as_mut_slice() {
+            candidate.push(' ');
+        }
+
+        Ok((completion.start, completion.candidates))
+    }
+}
+
+impl Hinter for Completions {
+    type Hint = String;
+}
+
+impl Highlighter for Completions {}
+
+impl Validator for Completions {}
+
+impl Helper for Completions {}
+
+/// Colours a line with ANSI escape codes.
+fn paint(line: &StyledLine) -> String {
+    let colour = match line.colour {
+        Colour::Default => None,
+        Colour::Red => Some("31"),
+        Colour::Green => Some("32"),
+        Colour::Yellow => Some("33"),
+        Colour::Blue => Some("34"),
+        Colour::Magenta => Some("35"),
+        Colour::Cyan => Some("36"),
+        Colour::Gray => Some("37"),
+        Colour::DarkGray => Some("90"),
+    };
+
+    let codes: Vec<&str> = line.bold.then_some("1").into_iter().chain(colour).collect();
+
+    match codes.is_empty() {
+        true => line.text.clone(),
+        false => format!("\x1b[{}m{}\x1b[0m", codes.join(";"), line.text),
+    }
+}
@@ -0,0 +1,38 @@
+use crate::frontend::{ConnectionState, Frontend, UserInput};
+use async_trait::async_trait;
+use realtime_chat::{client::StyledLine, frame::Frame};
+
+/// Prints each frame received from the server without reading any input, as
+/// uncoloured text or as a JSON object per line. Notices are printed to stderr, so
+/// that stdout can be piped into other tools.
+pub struct ListenFrontend {
+    pub json: bool,
+}
+
+#[async_trait]
+impl Frontend for ListenFrontend {
+    async fn next_input(&mut self) -> Option<UserInput> {
+        std::future::pending().await
+    }
+
+    fn show(&mut self, frame: &Frame, lines: Vec<StyledLine>) {
+        if !self.json {
+            for line in lines {
+                println!("{}", line.text);
+            }
+
+            return;
+        }
+
+        match serde_json::to_string(frame) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Failed to serialize frame: {}", e),
+        }
+    }
+
+    fn notice(&mut self, message: &str) {
+        eprintln!("-- {}", message);
+    }
+
+    fn set_state(&mut self, _state: ConnectionState) {}
+}
@@ -0,0 +1,633 @@
+mod frontend;
+mod line;
+mod listen;
+mod tui;
+
+use clap::{Parser, Subcommand};
+use frontend::{ConnectionState, Frontend, UserInput};
+use futures::StreamExt;
+use line::LineFrontend;
+use listen::ListenFrontend;
+use realtime_chat::{
+    client::{
+        Backoff, ChatClient, ClientConfig, ConnectConfig, Event, HistoryFile, LocalCommand,
+        Renderer, Setting, Theme, Transcript,
+    },
+    domain::{Capabilities, MessageId},
+    errors::ClientError,
+    frame::Frame,
+};
+use std::{collections::VecDeque, ops::ControlFlow, path::PathBuf, time::Duration};
+use time::{OffsetDateTime, UtcOffset};
+use tokio::time::{interval, sleep, timeout, Instant};
+use tui::Tui;
+
+// The server connected to if neither the command line nor the profile has one.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+// Exit codes for `client send`, besides success.
+const EXIT_REFUSED: i32 = 1;
+const EXIT_UNREACHABLE: i32 = 3;
+const EXIT_TIMED_OUT: i32 = 4;
+
+// How often the last read message is reported to the server.
+const READ_MARKER_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Address of server to connect to. Defaults to the profile's address, or
+    /// 127.0.0.1:8080.
+    #[arg(short, long)]
+    address: Option<String>,
+
+    /// Friendly nickname to display to other users. Defaults to the profile's
+    /// nickname.
+    #[arg(short, long)]
+    nickname: Option<String>,
+
+    /// Password used to generate Tripcode. This will allow you to claim a
+    /// unique username. Passing it here makes it visible to other users of
+    /// this machine, so by default it's read as set by the profile, or
+    /// prompted for.
+    #[arg(short, long)]
+    password: Option<String>,
+
+    /// Profile in the config file to connect with. Defaults to the config
+    /// file's default profile.
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Path to the client's TOML config file. Defaults to a file in the user's
+    /// config directory.
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Don't receive typing indicators from other users.
+    #[arg(long)]
+    no_typing: bool,
+
+    /// Use a full-screen terminal UI, rather than reading and printing lines.
+    #[arg(long)]
+    tui: bool,
+
+    /// File to save the input history to, so that it persists across
+    /// sessions. Defaults to a file in the user's data directory.
+    #[arg(long)]
+    history: Option<PathBuf>,
+
+    /// Don't save the input history.
+    #[arg(long, conflicts_with = "history")]
+    no_history: bool,
+
+    #[command(subcommand)]
+    mode: Option<Mode>,
+}
+
+/// Ways to use the client without typing into it, e.g. from scripts. Without
+/// one, the client is interactive.
+#[derive(Subcommand, Debug)]
+enum Mode {
+    /// Send a message, or a command such as /me, and exit once the server has
+    /// accepted it. Exits with 1 if the server refused the login or message, 3
+    /// if it couldn't be reached, and 4 if it didn't respond in time.
+    Send {
+        message: String,
+
+        /// How many seconds to wait to connect, and for the server to accept
+        /// the message.
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+    },
+    /// Print the frames received from the server, until the client is stopped.
+    Listen {
+        /// Print each frame as a JSON object per line, with its `type` and `text`.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// The server to connect to, and who to connect as.
+struct Identity {
+    address: String,
+    nickname: String,
+    password: String,
+}
+
+impl Identity {
+    /// Combines the command line arguments with the chosen profile, where the
+    /// arguments take precedence.
+    fn resolve(args: &Args, config: &ClientConfig) -> Result<Self, String> {
+        let profile = config
+            .profile(args.profile.as_deref())
+            .map_err(|e| e.to_string())?;
+
+        let nickname = args
+            .nickname
+            .clone()
+            .or(profile.nickname)
+            .ok_or("No nickname provided: use --nickname, or set one in a profile.")?;
+
+        let password = match &args.password {
+            Some(password) => password.clone(),
+            None => profile.password.read().map_err(|e| e.to_string())?,
+        };
+
+        Ok(Self {
+            address: args
+                .address
+                .clone()
+                .or(profile.address)
+                .unwrap_or_else(|| DEFAULT_ADDRESS.into()),
+            nickname,
+            password,
+        })
+    }
+}
+
+/// Loads the config file, if there is one.
+fn load_config(args: &Args) -> Result<ClientConfig, String> {
+    let config = match &args.config {
+        Some(path) => ClientConfig::load(path),
+        None => match dirs::config_dir() {
+            Some(dir) => ClientConfig::load_or_default(&dir.join("realtime-chat/client.toml")),
+            None => Ok(ClientConfig::default()),
+        },
+    };
+
+    config.map_err(|e| e.to_string())
+}
+
+/// Why the connection to the server ended.
+enum Disconnect {
+    /// The user quit the client.
+    Quit,
+    /// The connection dropped, so it's worth reconnecting.
+    Dropped,
+    /// The user asked to connect again with `/connect`, to another server if
+    /// an address is provided, which is done right away.
+    Connect(Option<String>),
+}
+
+/// The state of the client that outlives any one connection to the server.
+struct Client {
+    args: Args,
+    identity: Identity,
+    frontend: Box<dyn Frontend>,
+    renderer: Renderer,
+    transcript: Transcript,
+    /// The token used to resume the session, if the server issued one.
+    resume_token: Option<String>,
+    /// Messages typed while disconnected, sent once connected again.
+    outbox: VecDeque<String>,
+    last_read: Option<MessageId>,
+    reported: Option<MessageId>,
+}
+
+fn main() {
+    // The local time zone can only be found before any other threads start.
+    let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+
+    run(offset);
+}
+
+#[tokio::main]
+async fn run(offset: UtcOffset) {
+    let args = Args::parse();
+    let (config, identity) = load_config(&args)
+        .and_then(|config| {
+            let identity = Identity::resolve(&args, &config)?;
+            Ok((config, identity))
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+
+    let frontend: Box<dyn Frontend> = match &args.mode {
+        Some(Mode::Send { .. }) => Box::new(ListenFrontend { json: false }),
+        Some(Mode::Listen { json }) => Box::new(ListenFrontend { json: *json }),
+        None => interactive_frontend(&args, &identity),
+    };
+
+    let mut client = Client {
+        renderer: Renderer::new(config.display, offset),
+        transcript: Transcript::new(config.transcript, &identity.address),
+        args,
+        identity,
+        frontend,
+        resume_token: None,
+        outbox: VecDeque::new(),
+        last_read: None,
+        reported: None,
+    };
+
+    if let Some(Mode::Send { message, timeout }) = &client.args.mode {
+        let (message, timeout) = (message.clone(), Duration::from_secs(*timeout));
+        std::process::exit(client.send(message, timeout).await);
+    }
+
+    let mut backoff = Backoff::default();
+
+    loop {
+        client.frontend.set_state(match client.resume_token {
+            Some(_) => ConnectionState::Reconnecting,
+            None => ConnectionState::Connecting,
+        });
+
+        let mut chat = match client.connect().await {
+            Ok(chat) => chat,
+            Err(ClientError::Rejected(reason)) if client.resume_token.take().is_some() => {
+                // The session couldn't be resumed, so join again instead.
+                client.frontend.notice(&format!("{reason} Rejoining…"));
+                continue;
+            }
+            Err(ClientError::Rejected(reason)) => {
+                // Restore the terminal before printing why the client exited.
+                drop(client);
+                eprintln!("{reason}");
+                std::process::exit(1);
+            }
+            Err(e) => {
+                let delay = backoff.next_delay();
+                client.frontend.notice(&format!(
+                    "Couldn't connect to {}: {}. Retrying in {:.1}s…",
+                    client.identity.address,
+                    e,
+                    delay.as_secs_f32()
+                ));
+
+                if !client.retry_after(&mut backoff, delay).await {
+                    return;
+                }
+
+                continue;
+            }
+        };
+
+        client.frontend.set_state(ConnectionState::Connected);
+
+        let connected_at = Instant::now();
+        let disconnect = client.run(&mut chat).await;
+        client.resume_token = chat.resume_token().map(str::to_owned);
+
+        match disconnect {
+            Disconnect::Quit => return,
+            Disconnect::Dropped => {
+                backoff.connection_lasted(connected_at.elapsed());
+                let delay = backoff.next_delay();
+                client.frontend.notice(&format!(
+                    "Disconnected from the server. Reconnecting in {:.1}s…",
+                    delay.as_secs_f32()
+                ));
+
+                if !client.retry_after(&mut backoff, delay).await {
+                    return;
+                }
+            }
+            Disconnect::Connect(address) => {
+                backoff.reset();
+                client.switch_server(address);
+            }
+        }
+    }
+}
+
+/// Creates the frontend for typing into the client, which is either a line
+/// editor or the full-screen terminal UI.
+fn interactive_frontend(args: &Args, identity: &Identity) -> Box<dyn Frontend> {
+    let history = match args.no_history {
+        true => None,
+        false => args
+            .history
+            .clone()
+            .or_else(|| Some(dirs::data_dir()?.join("realtime-chat").join("history")))
+            .map(HistoryFile::new),
+    };
+
+    let frontend: Result<Box<dyn Frontend>, String> = match args.tui {
+        true => Tui::new(
+            format!("{} @ {}", identity.nickname, identity.address),
+            history,
+        )
+        .map(|tui| Box::new(tui) as Box<dyn Frontend>)
+        .map_err(|e| format!("Failed to start the terminal UI: {}", e)),
+        false => LineFrontend::new(history)
+            .map(|line| Box::new(line) as Box<dyn Frontend>)
+            .map_err(|e| format!("Failed to read input: {}", e)),
+    };
+
+    frontend.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
+impl Client {
+    /// Connects to the server and completes the handshake, resuming the
+    /// previous session if there is one.
+    async fn connect(&mut self) -> Result<ChatClient, ClientError> {
+        let mut config = ConnectConfig::new(
+            self.identity.address.clone(),
+            self.identity.nickname.clone(),
+            self.identity.password.clone(),
+        );
+
+        config.capabilities = match (&self.args.mode, self.args.no_typing) {
+            // Sending a single message only needs to know it was accepted.
+            (Some(Mode::Send { .. }), _) => Capabilities {
+                typing: false,
+                presence: false,
+                acks: true,
+                actions: false,
+                echo: false,
+            },
+            (_, no_typing) => Capabilities {
+                typing: !no_typing,
+                ..config.capabilities
+            },
+        };
+        config.resume_token = self.resume_token.clone();
+
+        if let Some(Mode::Send { timeout, .. }) = &self.args.mode {
+            config.timeout = Duration::from_secs(*timeout);
+        }
+
+        let mut chat = ChatClient::connect(&config).await?;
+
+        if self.resume_token.is_some() {
+            self.frontend
+                .notice("Reconnected, and resumed the session.");
+        }
+
+        while let Some(input) = self.outbox.pop_front() {
+            if let Err(e) = chat.send(&input).await {
+                self.outbox.push_front(input);
+                return Err(e);
+            }
+        }
+
+        Ok(chat)
+    }
+
+    /// Exchanges messages with the server until the connection drops, or the
+    /// user quits.
+    async fn run(&mut self, chat: &mut ChatClient) -> Disconnect {
+        let mut read_markers = interval(READ_MARKER_INTERVAL);
+
+        loop {
+            tokio::select! {
+                input = self.frontend.next_input() => {
+                    let input = match input {
+                        Some(UserInput::Line(input)) => input,
+                        Some(UserInput::Typing(typing)) => {
+                            if chat.set_typing(typing).await.is_err() {
+                                return Disconnect::Dropped;
+                            }
+
+                            continue;
+                        }
+                        None => return Disconnect::Quit,
+                    };
+
+                    match self.run_local_command(&input) {
+                        Some(ControlFlow::Break(disconnect)) => return disconnect,
+                        Some(ControlFlow::Continue(())) => continue,
+                        None => {}
+                    }
+
+                    if chat.send(&input).await.is_err() {
+                        self.queue(input);
+                        return Disconnect::Dropped;
+                    }
+                },
+                result = chat.next() => match result {
+                    Some(Ok(event)) => {
+                        self.last_read = self.last_read.max(event.message_id());
+
+                        let now = OffsetDateTime::now_utc();
+                        let frame = Frame::from(event);
+                        let lines = self.renderer.render(&frame, now);
+
+                        self.record(&frame, now);
+                        self.frontend.show(&frame, lines);
+                    },
+                    Some(Err(e)) => {
+                        self.frontend.notice(&format!("An error occured: {:?}", e));
+                    },
+                    None => return Disconnect::Dropped,
+                },
+                _ = read_markers.tick() => {
+                    // Let the server know which messages we've seen since the last report.
+                    if let Some(id) = self.last_read.filter(|_| self.last_read > self.reported) {
+                        let _ = chat.mark_read(id).await;
+                        self.reported = self.last_read;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Connects, sends a single message and waits for the server to accept it,
+    /// giving up if that takes longer than `wait`. Returns the exit code of the
+    /// client.
+    async fn send(&mut self, message: String, wait: Duration) -> i32 {
+        timeout(wait, self.deliver(message))
+            .await
+            .unwrap_or_else(|_| {
+                eprintln!("The server didn't accept the message in time.");
+                EXIT_TIMED_OUT
+            })
+    }
+
+    async fn deliver(&mut self, message: String) -> i32 {
+        let mut chat = match self.connect().await {
+            Ok(chat) => chat,
+            Err(ClientError::Rejected(reason)) => {
+                eprintln!("{}", reason);
+                return EXIT_REFUSED;
+            }
+            Err(e @ ClientError::TimedOut(_)) => {
+                eprintln!("{}", e);
+                return EXIT_TIMED_OUT;
+            }
+            Err(e) => {
+                eprintln!("Couldn't connect to {}: {}", self.identity.address, e);
+                return EXIT_UNREACHABLE;
+            }
+        };
+
+        if let Err(e) = chat.send(&message).await {
+            eprintln!("Failed to send message: {}", e);
+            return EXIT_UNREACHABLE;
+        }
+
+        loop {
+            match chat.next().await {
+                Some(Ok(Event::Ack(_))) => return 0,
+                Some(Ok(Event::Error(reason))) => {
+                    eprintln!("{}", reason);
+                    return EXIT_REFUSED;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    eprintln!("Connection failed: {}", e);
+                    return EXIT_UNREACHABLE;
+                }
+                None => {
+                    eprintln!("The server closed the connection.");
+                    return EXIT_UNREACHABLE;
+                }
+            }
+        }
+    }
+
+    /// Waits before trying to connect again, returning `false` if the user quit
+    /// meanwhile. Switching to another server connects to it straight away.
+    async fn retry_after(&mut self, backoff: &mut Backoff, delay: Duration) -> bool {
+        match self.wait(delay).await {
+            Disconnect::Quit => false,
+            Disconnect::Dropped => true,
+            Disconnect::Connect(address) => {
+                backoff.reset();
+                self.switch_server(address);
+                true
+            }
+        }
+    }
+
+    /// Waits before trying to reconnect, queueing anything typed meanwhile.
+    async fn wait(&mut self, delay: Duration) -> Disconnect {
+        let retry = sleep(delay);
+        tokio::pin!(retry);
+
+        loop {
+            tokio::select! {
+                _ = &mut retry => return Disconnect::Dropped,
+                input = self.frontend.next_input() => match input {
+                    Some(UserInput::Line(input)) => match self.run_local_command(&input) {
+                        Some(ControlFlow::Break(disconnect)) => return disconnect,
+                        Some(ControlFlow::Continue(())) => {}
+                        None => self.queue(input),
+                    },
+                    // There's no one to tell while disconnected.
+                    Some(UserInput::Typing(_)) => {}
+                    None => return Disconnect::Quit,
+                },
+            }
+        }
+    }
+
+    /// Runs a command meant for the client itself, rather than the server.
+    /// Returns `None` if the input isn't one, so should be sent to the server,
+    /// or `Break` if the command ends the connection.
+    fn run_local_command(&mut self, input: &str) -> Option<ControlFlow<Disconnect>> {
+        let command = match LocalCommand::parse(input)? {
+            Ok(command) => command,
+            Err(e) => {
+                self.frontend.notice(&e.to_string());
+                return Some(ControlFlow::Continue(()));
+            }
+        };
+
+        match command {
+            LocalCommand::Clear => self.frontend.clear(),
+            LocalCommand::Connect(address) => {
+                return Some(ControlFlow::Break(Disconnect::Connect(address)))
+            }
+            LocalCommand::Log(enabled) => self.log(enabled),
+            LocalCommand::Quit => return Some(ControlFlow::Break(Disconnect::Quit)),
+            LocalCommand::Set(setting) => self.set(setting),
+            LocalCommand::Theme(name) => self.theme(name),
+        }
+
+        Some(ControlFlow::Continue(()))
+    }
+
+    /// Switches to another server before reconnecting with `/connect`, unless
+    /// it's the same server.
+    fn switch_server(&mut self, address: Option<String>) {
+        if let Some(address) = address.filter(|address| *address != self.identity.address) {
+            // Sessions and message IDs belong to the server they came from.
+            self.resume_token = None;
+            self.last_read = None;
+            self.reported = None;
+            self.transcript.set_server(&address);
+            self.identity.address = address;
+            self.frontend.set_title(format!(
+                "{} @ {}",
+                self.identity.nickname, self.identity.address
+            ));
+        }
+
+        self.frontend
+            .notice(&format!("Connecting to {}…", self.identity.address));
+    }
+
+    /// Turns the transcript on or off with `/log on|off`.
+    fn log(&mut self, enabled: Option<bool>) {
+        if let Some(enabled) = enabled {
+            self.transcript.enabled = enabled;
+        }
+
+        let message = match self.transcript.enabled {
+            true => format!(
+                "Logging the chat to {}.",
+                self.transcript.directory().display()
+            ),
+            false => String::from("Not logging the chat. Use /log on to start."),
+        };
+
+        self.frontend.notice(&message);
+    }
+
+    /// Changes a display setting with `/set`, and shows the settings.
+    fn set(&mut self, setting: Option<Setting>) {
+        if let Some(Setting::Timestamps(enabled)) = setting {
+            self.renderer.timestamps = enabled;
+        }
+
+        let timestamps = match self.renderer.timestamps {
+            true => "on",
+            false => "off",
+        };
+
+        self.frontend.notice(&format!("timestamps: {timestamps}"));
+    }
+
+    /// Switches to a theme with `/theme`, and lists the themes.
+    fn theme(&mut self, name: Option<String>) {
+        match name.map(|name| name.parse()) {
+            Some(Ok(theme)) => self.renderer.theme = theme,
+            Some(Err(e)) => return self.frontend.notice(&e),
+            None => {}
+        }
+
+        let themes: Vec<String> = Theme::ALL.iter().map(Theme::to_string).collect();
+
+        self.frontend.notice(&format!(
+            "Using the {} theme. Themes: {}.",
+            self.renderer.theme,
+            themes.join(", ")
+        ));
+    }
+
+    /// Writes a frame to the transcript, which is turned off if it fails.
+    fn record(&mut self, frame: &Frame, at: OffsetDateTime) {
+        if let Err(e) = self.transcript.record(frame, at) {
+            self.transcript.enabled = false;
+            self.frontend.notice(&format!(
+                "Failed to log the chat, so stopped logging: {}",
+                e
+            ));
+        }
+    }
+
+    fn queue(&mut self, input: String) {
+        self.outbox.push_back(input);
+        self.frontend.notice(&format!(
+            "Not connected, {} message(s) will be sent once reconnected.",
+            self.outbox.len()
+        ));
+    }
+}
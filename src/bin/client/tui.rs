@@ -0,0 +1,385 @@
+use crate::frontend::{ConnectionState, Frontend, UserInput};
+use async_trait::async_trait;
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures::StreamExt;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, Paragraph, Wrap},
+    DefaultTerminal, Frame as TerminalFrame,
+};
+use realtime_chat::{
+    client::{Colour, HistoryFile, InputLine, OnlineUsers, StyledLine},
+    frame::Frame,
+};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    io,
+    time::{Duration, Instant},
+};
+
+// The most lines kept in the scrollback.
+const MAX_SCROLLBACK: usize = 2000;
+
+// The width of the sidebar listing online users.
+const SIDEBAR_WIDTH: u16 = 24;
+
+// How often the typing indicator is refreshed while the user keeps typing,
+// which is well within the time the server waits before stopping it.
+const TYPING_REFRESH: Duration = Duration::from_secs(3);
+
+/// A full-screen terminal UI, with a scrollback of the chat above an input
+/// line, a sidebar listing the online users, and a status bar.
+pub struct Tui {
+    terminal: DefaultTerminal,
+    events: EventStream,
+    history: Option<HistoryFile>,
+    /// When the server was last told the user is typing, if they still are.
+    typing_sent: Option<Instant>,
+    view: View,
+}
+
+/// Everything displayed by the terminal UI.
+struct View {
+    title: String,
+    scrollback: VecDeque<Line<'static>>,
+    /// How many lines the scrollback is scrolled up from the latest message.
+    scroll: usize,
+    input: InputLine,
+    users: OnlineUsers,
+    typing: BTreeSet<String>,
+    state: ConnectionState,
+}
+
+/// What the user did with a key press.
+enum Action {
+    Submit(String),
+    Typing(bool),
+    /// Tab completion found more than one candidate.
+    Candidates(Vec<String>),
+    Quit,
+}
+
+impl Tui {
+    /// Switches the terminal to the full-screen UI, which is restored when the
+    /// UI is dropped. The title is shown in the status bar, and submitted
+    /// lines are saved to the history file, if there is one.
+    pub fn new(title: String, history: Option<HistoryFile>) -> io::Result<Self> {
+        let lines = history
+            .iter()
+            .flat_map(|history| history.load())
+            .flatten()
+            .collect();
+
+        Ok(Self {
+            terminal: ratatui::try_init()?,
+            events: EventStream::new(),
+            history,
+            typing_sent: None,
+            view: View {
+                title,
+                scrollback: VecDeque::new(),
+                scroll: 0,
+                input: InputLine::with_history(lines),
+                users: OnlineUsers::default(),
+                typing: BTreeSet::new(),
+                state: ConnectionState::Connecting,
+            },
+        })
+    }
+
+    fn draw(&mut self) {
+        let _ = self.terminal.draw(|frame| self.view.render(frame));
+    }
+
+    /// Works out whether the server needs to be told that the user started or
+    /// stopped typing, now that the input line has changed.
+    fn typing_changed(&mut self) -> Option<Action> {
+        let typing = !self.view.input.value().is_empty();
+
+        match (typing, self.typing_sent) {
+            (true, Some(sent)) if sent.elapsed() < TYPING_REFRESH => None,
+            (true, _) => {
+                self.typing_sent = Some(Instant::now());
+                Some(Action::Typing(true))
+            }
+            (false, Some(_)) => {
+                self.typing_sent = None;
+                Some(Action::Typing(false))
+            }
+            (false, None) => None,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let input = &mut self.view.input;
+        let page = self
+            .terminal
+            .size()
+            .map_or(10, |size| size.height as usize / 2);
+
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('c') => return Some(Action::Quit),
+                KeyCode::Char('d') if input.value().is_empty() => return Some(Action::Quit),
+                KeyCode::Char('a') => input.home(),
+                KeyCode::Char('e') => input.end(),
+                KeyCode::Char('u') => input.clear(),
+                KeyCode::Char('w') => input.delete_word(),
+                KeyCode::Char('l') => {
+                    let _ = self.terminal.clear();
+                }
+                _ => {}
+            }
+
+            return self.typing_changed();
+        }
+
+        match key.code {
+            KeyCode::Enter => match input.submit() {
+                Some(line) => {
+                    // Sending a message stops the typing indicator on the server.
+                    self.typing_sent = None;
+                    return Some(Action::Submit(line));
+                }
+                // A blank line is cleared without being sent.
+                None => return self.typing_changed(),
+            },
+            KeyCode::Tab => {
+                let candidates = input.complete(&self.view.users);
+                return (!candidates.is_empty()).then_some(Action::Candidates(candidates));
+            }
+            KeyCode::Char(c) => input.insert(c),
+            KeyCode::Backspace => input.backspace(),
+            KeyCode::Delete => input.delete(),
+            KeyCode::Left => input.left(),
+            KeyCode::Right => input.right(),
+            KeyCode::Home => input.home(),
+            KeyCode::End => input.end(),
+            KeyCode::Up => input.previous(),
+            KeyCode::Down => input.next(),
+            KeyCode::PageUp => self.view.scroll += page,
+            KeyCode::PageDown => self.view.scroll = self.view.scroll.saturating_sub(page),
+            _ => {}
+        }
+
+        self.typing_changed()
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+#[async_trait]
+impl Frontend for Tui {
+    async fn next_input(&mut self) -> Option<UserInput> {
+        loop {
+            self.draw();
+
+            let key = match self.events.next().await? {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+                Ok(_) => continue,
+                Err(_) => return None,
+            };
+
+            match self.handle_key(key) {
+                Some(Action::Submit(line)) => {
+                    if let Some(Err(e)) = self.history.as_ref().map(|history| history.append(&line))
+                    {
+                        self.notice(&format!("Failed to save input history: {}", e));
+                    }
+
+                    // Sending a message jumps back to the latest messages.
+                    self.view.scroll = 0;
+                    return Some(UserInput::Line(line));
+                }
+                Some(Action::Typing(typing)) => return Some(UserInput::Typing(typing)),
+                Some(Action::Candidates(candidates)) => self.notice(&candidates.join("  ")),
+                Some(Action::Quit) => return None,
+                None => {}
+            }
+        }
+    }
+
+    fn show(&mut self, frame: &Frame, lines: Vec<StyledLine>) {
+        match frame {
+            Frame::Presence(presence) => {
+                if let Ok(presence) = presence.parse() {
+                    self.view.users.apply(presence);
+                }
+            }
+            // Typing is shown in the status bar, rather than the scrollback.
+            Frame::TypingStart(username) => {
+                self.view.typing.insert(username.clone());
+            }
+            Frame::TypingStop(username) => {
+                self.view.typing.remove(username);
+            }
+            _ => {
+                for line in lines {
+                    self.view
+                        .push(Line::styled(line.text, style(line.colour, line.bold)));
+                }
+            }
+        }
+
+        self.draw();
+    }
+
+    fn notice(&mut self, message: &str) {
+        let line = Line::styled(format!("-- {message}"), Style::new().dark_gray().italic());
+
+        self.view.push(line);
+        self.draw();
+    }
+
+    fn set_state(&mut self, state: ConnectionState) {
+        if state != ConnectionState::Connected {
+            // The server sends the online users again once reconnected.
+            self.view.users.clear();
+            self.view.typing.clear();
+        }
+
+        self.view.state = state;
+        self.draw();
+    }
+
+    fn set_title(&mut self, title: String) {
+        self.view.title = title;
+        self.draw();
+    }
+
+    fn clear(&mut self) {
+        self.view.scrollback.clear();
+        self.view.scroll = 0;
+        self.draw();
+    }
+}
+
+impl View {
+    fn push(&mut self, line: Line<'static>) {
+        if self.scrollback.len() == MAX_SCROLLBACK {
+            self.scrollback.pop_front();
+        }
+
+        // Keep the same messages in view while scrolled up.
+        if self.scroll > 0 {
+            self.scroll += 1;
+        }
+
+        self.scrollback.push_back(line);
+    }
+
+    fn render(&mut self, frame: &mut TerminalFrame) {
+        let [main, input, status] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let [messages, users] =
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(SIDEBAR_WIDTH)]).areas(main);
+
+        self.render_scrollback(frame, messages);
+        self.render_users(frame, users);
+        self.render_input(frame, input);
+        self.render_status(frame, status);
+    }
+
+    fn render_scrollback(&mut self, frame: &mut TerminalFrame, area: Rect) {
+        let text = Text::from(self.scrollback.iter().cloned().collect::<Vec<_>>());
+        let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+
+        let max_scroll = paragraph
+            .line_count(area.width)
+            .saturating_sub(area.height as usize);
+        self.scroll = self.scroll.min(max_scroll);
+
+        let offset = (max_scroll - self.scroll).min(u16::MAX as usize) as u16;
+        frame.render_widget(paragraph.scroll((offset, 0)), area);
+    }
+
+    fn render_users(&self, frame: &mut TerminalFrame, area: Rect) {
+        let users = self
+            .users
+            .iter()
+            .map(|username| match username.split_once('!') {
+                Some((nickname, tripcode)) => Line::from(vec![
+                    Span::raw(nickname.to_owned()),
+                    Span::styled(format!("!{tripcode}"), Style::new().dark_gray()),
+                ]),
+                None => Line::raw(username.to_owned()),
+            });
+
+        let block = Block::new()
+            .borders(Borders::LEFT)
+            .title(format!(" Online ({}) ", self.users.len()));
+
+        frame.render_widget(List::new(users).block(block), area);
+    }
+
+    fn render_input(&self, frame: &mut TerminalFrame, area: Rect) {
+        let width = area.width.saturating_sub(2) as usize;
+        let cursor = self.input.cursor();
+
+        // Scroll the input sideways to keep the cursor in view.
+        let offset = (cursor + 1).saturating_sub(width);
+
+        let paragraph = Paragraph::new(self.input.value())
+            .scroll((0, offset.min(u16::MAX as usize) as u16))
+            .block(Block::bordered());
+
+        frame.render_widget(paragraph, area);
+        frame.set_cursor_position((area.x + 1 + (cursor - offset) as u16, area.y + 1));
+    }
+
+    fn render_status(&self, frame: &mut TerminalFrame, area: Rect) {
+        let (state, colour) = match self.state {
+            ConnectionState::Connecting => ("connecting", Color::Yellow),
+            ConnectionState::Connected => ("connected", Color::Green),
+            ConnectionState::Reconnecting => ("reconnecting", Color::Red),
+        };
+
+        let mut spans = vec![
+            Span::styled(format!(" ● {state} "), Style::new().fg(colour).bold()),
+            Span::raw(format!("{} ", self.title)),
+        ];
+
+        if !self.typing.is_empty() {
+            let typing: Vec<&str> = self.typing.iter().map(String::as_str).collect();
+            spans.push(Span::raw(format!("| {} typing… ", typing.join(", "))));
+        }
+
+        if self.scroll > 0 {
+            spans.push(Span::raw(format!("| scrolled up {} lines ", self.scroll)));
+        }
+
+        let line = Line::from(spans).style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_widget(Paragraph::new(line), area);
+    }
+}
+
+/// The style of a line in the scrollback.
+fn style(colour: Colour, bold: bool) -> Style {
+    let style = match colour {
+        Colour::Default => Style::new(),
+        Colour::Red => Style::new().red(),
+        Colour::Green => Style::new().green(),
+        Colour::Yellow => Style::new().yellow(),
+        Colour::Blue => Style::new().blue(),
+        Colour::Magenta => Style::new().magenta(),
+        Colour::Cyan => Style::new().cyan(),
+        Colour::Gray => Style::new().gray(),
+        Colour::DarkGray => Style::new().dark_gray(),
+    };
+
+    match bold {
+        true => style.bold(),
+        false => style,
+    }
+}
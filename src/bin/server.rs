@@ -1,43 +1,47 @@
-use realtime_chat::domain::{Connection, Shared};
-use std::env;
-use std::sync::Arc;
-use tokio::net::TcpListener;
-use tokio::sync::Mutex;
+use clap::Parser;
+use realtime_chat::{config::ServerConfig, server::ChatServer};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Address to listen for connections on.
+    #[arg(default_value_t = String::from("127.0.0.1:8080"))]
+    address: String,
+
+    /// Path to a TOML config file.
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+}
 
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt().compact().init();
 
-    let listener = get_listener().await;
-    let state = Arc::new(Mutex::new(Shared::new()));
-
-    loop {
-        let (socket, addr) = listener.accept().await.unwrap();
-        let state = state.clone();
-
-        tokio::spawn(async move {
-            tracing::info!("New client connection from {:?}", addr);
-
-            match Connection::new(socket, addr, state).await {
-                Ok(mut conn) => conn.process().await,
-                Err(e) => {
-                    tracing::error!("{}", e);
-                    return;
-                }
-            };
+    let args = Args::parse();
+
+    let config = match &args.config {
+        Some(path) => ServerConfig::load(path).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        }),
+        None => ServerConfig::default(),
+    };
+
+    let server = ChatServer::builder()
+        .bind(args.address)
+        .config(config)
+        .start()
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
         });
-    }
-}
-
-async fn get_listener() -> TcpListener {
-    let addr = env::args()
-        .skip(1)
-        .next()
-        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
 
-    let listener = TcpListener::bind(&addr).await.unwrap();
-
-    tracing::info!("Server listening on {}", &addr);
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::error!("Failed to listen for Ctrl-C: {}", e);
+    }
 
-    listener
+    tracing::info!("Shutting down");
+    server.shutdown().await;
 }
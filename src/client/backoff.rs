@@ -0,0 +1,126 @@
+use rand::{rngs::OsRng, RngCore};
+use std::time::Duration;
+
+// The delay before the first retry, which doubles after each failed attempt.
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+// The longest delay between retries.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+// How long a connection must stay up for the backoff to start again from the
+// initial delay once it drops.
+const STABLE_CONNECTION: Duration = Duration::from_secs(10);
+
+/// Exponential backoff with jitter, used to space out attempts to reconnect.
+///
+/// Each delay is at least half of the current backoff, plus a random amount up
+/// to the other half, so that clients disconnected at the same time don't all
+/// reconnect at once.
+#[derive(Debug)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(INITIAL_DELAY, MAX_DELAY)
+    }
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            attempt: 0,
+        }
+    }
+
+    /// The delay before the next attempt, which increases with each call.
+    pub fn next_delay(&mut self) -> Duration {
+        let backoff = self
+            .initial
+            .saturating_mul(2u32.saturating_pow(self.attempt))
+            .min(self.max);
+
+        self.attempt = self.attempt.saturating_add(1);
+
+        let half = backoff / 2;
+        let jitter = OsRng.next_u64() % (half.as_millis() as u64 + 1);
+
+        half + Duration::from_millis(jitter)
+    }
+
+    /// Starts again from the initial delay, e.g. when switching servers.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    /// Starts again from the initial delay if a connection that dropped stayed
+    /// up for long enough, so that a server which accepts connections and then
+    /// drops them straight away isn't retried without any delay.
+    pub fn connection_lasted(&mut self, duration: Duration) {
+        if duration >= STABLE_CONNECTION {
+            self.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn doubles_delay_after_each_attempt() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+
+        for attempt in 0..5 {
+            let backoff_for_attempt = Duration::from_secs(1 << attempt);
+            let delay = backoff.next_delay();
+
+            assert!(delay >= backoff_for_attempt / 2, "{delay:?}");
+            assert!(delay <= backoff_for_attempt, "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn caps_delay_at_maximum() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(4));
+
+        let delays: Vec<Duration> = (0..100).map(|_| backoff.next_delay()).collect();
+
+        assert!(delays.iter().all(|delay| *delay <= Duration::from_secs(4)));
+        assert!(delays[10..]
+            .iter()
+            .all(|delay| *delay >= Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn resets_to_initial_delay() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+
+        for _ in 0..5 {
+            backoff.next_delay();
+        }
+        backoff.reset();
+
+        assert!(backoff.next_delay() <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn only_resets_after_stable_connection() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+
+        for _ in 0..5 {
+            backoff.next_delay();
+        }
+
+        backoff.connection_lasted(Duration::from_secs(1));
+        assert!(backoff.next_delay() >= Duration::from_secs(16));
+
+        backoff.connection_lasted(STABLE_CONNECTION);
+        assert!(backoff.next_delay() <= Duration::from_secs(1));
+    }
+}
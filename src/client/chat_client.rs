@@ -0,0 +1,299 @@
+use super::Event;
+use crate::{
+    args::quote,
+    codec::MessageCodec,
+    domain::{Capabilities, MessageId},
+    errors::ClientError,
+    frame::Frame,
+};
+use futures::{ready, SinkExt, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_util::codec::Framed;
+
+// How long the client waits to connect and complete the handshake by default.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The server to connect to, and who to connect as.
+#[derive(Debug, Clone)]
+pub struct ConnectConfig {
+    pub address: String,
+    pub nickname: String,
+    pub password: String,
+    /// The capabilities requested during the handshake.
+    pub capabilities: Capabilities,
+    /// A token issued by the server to resume a previous session with,
+    /// instead of joining with the nickname/password pair.
+    pub resume_token: Option<String>,
+    /// How long to wait to connect to the server and complete the handshake.
+    pub timeout: Duration,
+}
+
+/// A connection to a chat server, which has completed the handshake.
+///
+/// Messages and commands are sent with its methods, and everything received
+/// from the server is read from it as a `Stream` of `Event`s. Frames that are
+/// part of the protocol rather than the chat, such as resume tokens, are
+/// handled by the client itself.
+pub struct ChatClient {
+    messages: Framed<TcpStream, MessageCodec>,
+    capabilities: Capabilities,
+    resume_token: Option<String>,
+}
+
+impl ConnectConfig {
+    pub fn new(address: String, nickname: String, password: String) -> Self {
+        Self {
+            address,
+            nickname,
+            password,
+            // The client understands actions, and keeps track of the IDs of
+            // its own messages, so it asks for both.
+            capabilities: Capabilities {
+                actions: true,
+                echo: true,
+                ..Capabilities::default()
+            },
+            resume_token: None,
+            timeout: HANDSHAKE_TIMEOUT,
+        }
+    }
+}
+
+impl ChatClient {
+    /// Connects to the server and completes the handshake, joining the chat or
+    /// resuming a session. Returns an error if that takes longer than the
+    /// config's timeout.
+    pub async fn connect(config: &ConnectConfig) -> Result<Self, ClientError> {
+        timeout(config.timeout, Self::handshake(config))
+            .await
+            .unwrap_or(Err(ClientError::TimedOut(config.timeout)))
+    }
+
+    async fn handshake(config: &ConnectConfig) -> Result<Self, ClientError> {
+        let socket = TcpStream::connect(&config.address).await?;
+        let mut messages = Framed::new(socket, MessageCodec {});
+
+        messages
+            .send(Frame::Capabilities(config.capabilities.to_request()))
+            .await?;
+
+        let login = match &config.resume_token {
+            Some(token) => Frame::Resume(token.clone()),
+            None => Frame::Message(format!("{},{}", config.nickname, config.password)),
+        };
+        messages.send(login).await?;
+
+        // The server responds with the enabled capabilities once the handshake
+        // is complete, or an error if it was rejected.
+        let capabilities = match messages.next().await {
+            Some(Ok(Frame::Capabilities(capabilities))) => capabilities,
+            Some(Ok(frame)) => return Err(ClientError::Rejected(frame.message())),
+            Some(Err(e)) => return Err(e.into()),
+            None => return Err(ClientError::Closed),
+        };
+
+        Ok(Self {
+            messages,
+            capabilities: Capabilities::from(capabilities.as_str()),
+            resume_token: None,
+        })
+    }
+
+    /// The capabilities the server enabled for the client.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// The latest token issued by the server to resume the session with, once
+    /// the connection drops.
+    pub fn resume_token(&self) -> Option<&str> {
+        self.resume_token.as_deref()
+    }
+
+    /// Sends a line to the chat as if it was typed, so it can be a message or
+    /// a command such as `/me waves`.
+    pub async fn send(&mut self, message: &str) -> Result<(), ClientError> {
+        Ok(self.messages.send(Frame::Message(message.into())).await?)
+    }
+
+    /// Sends a private message to a user, by their full username.
+    pub async fn whisper(&mut self, username: &str, message: &str) -> Result<(), ClientError> {
+        self.send(&format!("/whisper {} {}", quote(username), message))
+            .await
+    }
+
+    /// Describes an action the user is taking.
+    pub async fn me(&mut self, action: &str) -> Result<(), ClientError> {
+        self.send(&format!("/me {}", action)).await
+    }
+
+    pub async fn reply(&mut self, id: MessageId, message: &str) -> Result<(), ClientError> {
+        self.send(&format!("/reply {} {}", id, message)).await
+    }
+
+    /// Reacts to a message with an emoji, or a shortcode such as `:thumbsup:`.
+    pub async fn react(&mut self, id: MessageId, emoji: &str) -> Result<(), ClientError> {
+        self.send(&format!("/react {} {}", id, quote(emoji))).await
+    }
+
+    /// Lets other users know whether the user is typing. The server stops the
+    /// indicator itself if it isn't refreshed every few seconds.
+    pub async fn set_typing(&mut self, typing: bool) -> Result<(), ClientError> {
+        let frame = match typing {
+            true => Frame::TypingStart(String::new()),
+            false => Frame::TypingStop(String::new()),
+        };
+
+        Ok(self.messages.send(frame).await?)
+    }
+
+    /// Reports the ID of the last message the user has read.
+    pub async fn mark_read(&mut self, id: MessageId) -> Result<(), ClientError> {
+        Ok(self
+            .messages
+            .send(Frame::ReadMarker(id.to_string()))
+            .await?)
+    }
+}
+
+impl Stream for ChatClient {
+    type Item = Result<Event, ClientError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let client = self.get_mut();
+
+        loop {
+            let frame = match ready!(client.messages.poll_next_unpin(cx)) {
+                Some(Ok(frame)) => frame,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                None => return Poll::Ready(None),
+            };
+
+            match frame {
+                Frame::Resume(token) => client.resume_token = Some(token),
+                Frame::Capabilities(capabilities) => {
+                    client.capabilities = Capabilities::from(capabilities.as_str())
+                }
+                frame => return Poll::Ready(Some(Ok(Event::from(frame)))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::ChatMessage;
+    use tokio::net::TcpListener;
+
+    /// Accepts a single client, and returns the frames it sent during the
+    /// handshake once it has responded with the provided frames.
+    async fn serve(listener: TcpListener, responses: Vec<Frame>) -> Vec<Frame> {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut messages = Framed::new(socket, MessageCodec {});
+        let mut received = vec![];
+
+        for _ in 0..2 {
+            received.push(messages.next().await.unwrap().unwrap());
+        }
+
+        for frame in responses {
+            messages.send(frame).await.unwrap();
+        }
+
+        received
+    }
+
+    async fn listener() -> (TcpListener, ConnectConfig) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let config = ConnectConfig::new(address, "alice".into(), "password123".into());
+
+        (listener, config)
+    }
+
+    #[tokio::test]
+    async fn completes_handshake_and_reads_events() {
+        let (listener, mut config) = listener().await;
+        config.capabilities.acks = true;
+
+        let server = tokio::spawn(serve(
+            listener,
+            vec![
+                Frame::Capabilities("typing,presence,acks".into()),
+                Frame::Resume("token".into()),
+                Frame::Message("[1] bob!b2: hi".into()),
+            ],
+        ));
+
+        let mut client = ChatClient::connect(&config).await.unwrap();
+        let event = client.next().await.unwrap().unwrap();
+
+        assert!(client.capabilities().acks);
+        assert_eq!(client.resume_token(), Some("token"));
+        assert_eq!(
+            event,
+            Event::Message(ChatMessage {
+                id: 1,
+                author: "bob!b2".into(),
+                text: "hi".into(),
+            })
+        );
+        assert_eq!(
+            server.await.unwrap(),
+            [
+                Frame::Capabilities("typing,presence,acks,actions,echo".into()),
+                Frame::Message("alice,password123".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn resumes_session_with_token() {
+        let (listener, mut config) = listener().await;
+        config.resume_token = Some("token".into());
+
+        let server = tokio::spawn(serve(
+            listener,
+            vec![Frame::Capabilities("typing,presence".into())],
+        ));
+
+        ChatClient::connect(&config).await.unwrap();
+
+        assert_eq!(server.await.unwrap()[1], Frame::Resume("token".into()));
+    }
+
+    #[tokio::test]
+    async fn returns_error_if_handshake_is_rejected() {
+        let (listener, config) = listener().await;
+
+        tokio::spawn(serve(
+            listener,
+            vec![Frame::Error("Wrong password.".into())],
+        ));
+
+        let result = ChatClient::connect(&config).await;
+
+        assert!(
+            matches!(result, Err(ClientError::Rejected(reason)) if reason == "Wrong password.")
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_error_if_handshake_times_out() {
+        let (listener, mut config) = listener().await;
+        config.timeout = Duration::from_millis(100);
+
+        // The server accepts the connection, but never responds.
+        let _server = tokio::spawn(async move { listener.accept().await });
+
+        let result = ChatClient::connect(&config).await;
+
+        assert!(matches!(result, Err(ClientError::TimedOut(_))));
+    }
+}
@@ -0,0 +1,211 @@
+use super::{OnlineUsers, LOCAL_COMMANDS};
+use crate::commands::{CommandInfo, COMMANDS};
+
+/// The possible completions of the word before the cursor in a line of input.
+#[derive(Debug, PartialEq)]
+pub struct Completion {
+    /// The byte index where the word being completed starts.
+    pub start: usize,
+    pub candidates: Vec<String>,
+}
+
+impl Completion {
+    /// Completes the word ending at the cursor, which is a byte index into the
+    /// line. Command names, including the client's own, are completed at the
+    /// start of the line, the online users after an `@`, and the target of
+    /// commands that take a username, such as `/whisper`.
+    pub fn new(line: &str, cursor: usize, users: &OnlineUsers) -> Self {
+        let before = &line[..cursor];
+        let word = before
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        let start = before.len() - word.len();
+        let previous: Vec<&str> = before[..start].split_whitespace().collect();
+
+        let candidates = match (previous.as_slice(), word) {
+            ([], word) if word.starts_with('/') => complete_command(&word[1..]),
+            (_, word) if word.starts_with('@') => complete_nickname(&word[1..], users),
+            ([command], word) => match target(command) {
+                Some(Target::Username) => complete_username(word, users),
+                Some(Target::Nickname) => complete_nickname(word, users),
+                None => vec![],
+            },
+            _ => vec![],
+        };
+
+        Self { start, candidates }
+    }
+
+    /// The longest prefix shared by every candidate, which is what the word
+    /// can be completed to without choosing between them.
+    pub fn common_prefix(&self) -> Option<&str> {
+        let (first, rest) = self.candidates.split_first()?;
+
+        let length = rest.iter().fold(first.len(), |length, candidate| {
+            first[..length]
+                .char_indices()
+                .zip(candidate.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(length.min(candidate.len()), |((index, _), _)| index)
+        });
+
+        Some(&first[..length])
+    }
+}
+
+/// The kind of user a command takes as its first argument.
+enum Target {
+    /// A full `nickname!tripcode` username.
+    Username,
+    /// A nickname, optionally followed by a tripcode.
+    Nickname,
+}
+
+fn target(command: &str) -> Option<Target> {
+    let command = CommandInfo::find(command.strip_prefix('/')?)?;
+
+    match command.name {
+        "whisper" => Some(Target::Username),
+        "seen" => Some(Target::Nickname),
+        _ => None,
+    }
+}
+
+fn complete_command(prefix: &str) -> Vec<String> {
+    let mut names: Vec<String> = COMMANDS
+        .iter()
+        .chain(LOCAL_COMMANDS)
+        .flat_map(|command| std::iter::once(&command.name).chain(command.aliases))
+        .filter(|name| name.starts_with(prefix))
+        .map(|name| format!("/{name}"))
+        .collect();
+
+    names.sort();
+    names
+}
+
+fn complete_username(prefix: &str, users: &OnlineUsers) -> Vec<String> {
+    users
+        .iter()
+        .filter(|username| starts_with_ignoring_case(username, prefix))
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Completes nicknames of online users, prefixed with an `@`. The full username
+/// is used for nicknames that more than one online user has, so that the
+/// completion refers to a single user.
+fn complete_nickname(prefix: &str, users: &OnlineUsers) -> Vec<String> {
+    let nickname = |username: &str| username.split('!').next().unwrap_or_default().to_owned();
+    let shared = |username: &str| {
+        users
+            .iter()
+            .filter(|other| nickname(other) == nickname(username))
+            .count()
+            > 1
+    };
+
+    users
+        .iter()
+        .filter(|username| starts_with_ignoring_case(username, prefix))
+        .map(|username| match shared(username) {
+            true => format!("@{username}"),
+            false => format!("@{}", nickname(username)),
+        })
+        .collect()
+}
+
+fn starts_with_ignoring_case(value: &str, prefix: &str) -> bool {
+    value.to_lowercase().starts_with(&prefix.to_lowercase())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::Presence;
+
+    fn online(usernames: &[&str]) -> OnlineUsers {
+        let mut users = OnlineUsers::default();
+        users.apply(Presence::Online(
+            usernames.iter().map(|&username| username.into()).collect(),
+        ));
+        users
+    }
+
+    fn complete(line: &str, users: &OnlineUsers) -> Completion {
+        Completion::new(line, line.len(), users)
+    }
+
+    #[test]
+    fn completes_command_names_and_aliases() {
+        let completion = complete("/wh", &OnlineUsers::default());
+
+        assert_eq!(completion.start, 0);
+        assert_eq!(completion.candidates, ["/whisper"]);
+        assert!(complete("/", &OnlineUsers::default())
+            .candidates
+            .contains(&"/msg".to_owned()));
+    }
+
+    #[test]
+    fn completes_local_command_names() {
+        let completion = complete("/qu", &OnlineUsers::default());
+
+        assert_eq!(completion.candidates, ["/quit"]);
+    }
+
+    #[test]
+    fn only_completes_command_at_start_of_line() {
+        let completion = complete("hello /wh", &OnlineUsers::default());
+
+        assert!(completion.candidates.is_empty());
+    }
+
+    #[test]
+    fn completes_mentions_of_online_users() {
+        let users = online(&["alice!a1", "Alfred!b2", "bob!c3"]);
+        let completion = complete("hi @al", &users);
+
+        assert_eq!(completion.start, 3);
+        assert_eq!(completion.candidates, ["@Alfred", "@alice"]);
+    }
+
+    #[test]
+    fn completes_full_username_when_nickname_is_shared() {
+        let users = online(&["alice!a1", "alice!b2"]);
+        let completion = complete("@ali", &users);
+
+        assert_eq!(completion.candidates, ["@alice!a1", "@alice!b2"]);
+    }
+
+    #[test]
+    fn completes_whisper_targets_with_full_username() {
+        let users = online(&["alice!a1", "bob!c3"]);
+        let completion = complete("/w a", &users);
+
+        assert_eq!(completion.start, 3);
+        assert_eq!(completion.candidates, ["alice!a1"]);
+        assert!(complete("/w alice!a1 hi", &users).candidates.is_empty());
+    }
+
+    #[test]
+    fn completes_word_before_cursor() {
+        let users = online(&["zoë!a1"]);
+        let line = "@zo and more";
+        let completion = Completion::new(line, 3, &users);
+
+        assert_eq!(completion.start, 0);
+        assert_eq!(completion.candidates, ["@zoë"]);
+    }
+
+    #[test]
+    fn finds_common_prefix_of_candidates() {
+        let completion = Completion {
+            start: 0,
+            candidates: vec!["/mentions".into(), "/me".into(), "/msg".into()],
+        };
+
+        assert_eq!(completion.common_prefix(), Some("/m"));
+    }
+}
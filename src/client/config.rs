@@ -0,0 +1,257 @@
+use super::{DisplayConfig, TranscriptConfig};
+use crate::errors::ConfigError;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The client's configuration, loaded from a TOML file. Each profile describes
+/// a server to connect to, and who to connect as, for example:
+///
+/// ```toml
+/// default_profile = "home"
+///
+/// [profiles.home]
+/// address = "127.0.0.1:8080"
+/// nickname = "some_user"
+/// password = "prompt"
+///
+/// [profiles.work]
+/// address = "chat.example.com:8080"
+/// nickname = "some_user"
+/// password = { file = "~/.config/realtime-chat/work-password" }
+///
+/// [transcript]
+/// enabled = true
+///
+/// [display]
+/// timestamps = true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientConfig {
+    /// The profile used when none is chosen.
+    pub default_profile: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+    pub transcript: TranscriptConfig,
+    pub display: DisplayConfig,
+}
+
+/// A server to connect to, and who to connect as. Anything left out must be
+/// provided on the command line instead.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub address: Option<String>,
+    pub nickname: Option<String>,
+    pub password: PasswordSource,
+}
+
+/// Where to read the password from, which keeps it out of the command line,
+/// and so out of shell history and the process list.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordSource {
+    /// Prompt for the password, without echoing it.
+    #[default]
+    Prompt,
+    /// Read the password from an environment variable.
+    Env(String),
+    /// Read the password from a file, which must only be accessible by its
+    /// owner. Trailing newlines are ignored.
+    File(PathBuf),
+}
+
+impl ClientConfig {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        fs::read_to_string(path)
+            .map_err(|e| ConfigError::ReadFailure(path.display().to_string(), e))?
+            .parse()
+    }
+
+    /// Loads the config file if it exists, as the client can be used without
+    /// one.
+    pub fn load_or_default(path: &Path) -> Result<Self, ConfigError> {
+        match path.try_exists() {
+            Ok(false) => Ok(Self::default()),
+            _ => Self::load(path),
+        }
+    }
+
+    /// Finds a profile by name, or the default profile if no name is provided.
+    /// Without a default profile, an empty profile is used.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile, ConfigError> {
+        match name.or(self.default_profile.as_deref()) {
+            Some(name) => self
+                .profiles
+                .get(name)
+                .cloned()
+                .ok_or_else(|| ConfigError::UnknownProfile(name.into())),
+            None => Ok(Profile::default()),
+        }
+    }
+}
+
+impl FromStr for ClientConfig {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(toml::from_str(value)?)
+    }
+}
+
+impl PasswordSource {
+    /// Reads the password, prompting for it on the terminal if needed.
+    pub fn read(&self) -> Result<String, ConfigError> {
+        match self {
+            Self::Prompt => rpassword::prompt_password("Password: ")
+                .map_err(|e| ConfigError::PasswordReadFailure("the terminal".into(), e)),
+            Self::Env(name) => {
+                std::env::var(name).map_err(|_| ConfigError::MissingEnvVar(name.clone()))
+            }
+            Self::File(path) => read_password_file(&expand_home(path)),
+        }
+    }
+}
+
+fn read_password_file(path: &Path) -> Result<String, ConfigError> {
+    let read_failure = |e| ConfigError::PasswordReadFailure(path.display().to_string(), e);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = fs::metadata(path)
+            .map_err(read_failure)?
+            .permissions()
+            .mode();
+
+        if mode & 0o077 != 0 {
+            return Err(ConfigError::InsecurePasswordFile(
+                path.display().to_string(),
+            ));
+        }
+    }
+
+    let password = fs::read_to_string(path).map_err(read_failure)?;
+    let password = password.trim_end_matches(['\r', '\n']);
+
+    match password.is_empty() {
+        true => Err(read_failure(io::ErrorKind::UnexpectedEof.into())),
+        false => Ok(password.to_owned()),
+    }
+}
+
+/// Expands a leading `~` in a path to the user's home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::{temp_path, unique_name};
+
+    #[test]
+    fn parses_empty_config() {
+        let config = ClientConfig::from_str("").unwrap();
+
+        assert_eq!(config, ClientConfig::default());
+        assert_eq!(config.profile(None).unwrap(), Profile::default());
+    }
+
+    #[test]
+    fn parses_profiles_with_password_sources() {
+        let config = ClientConfig::from_str(
+            r#"
+            default_profile = "home"
+
+            [profiles.home]
+            address = "127.0.0.1:8080"
+            nickname = "some_user"
+
+            [profiles.work]
+            nickname = "some_user"
+            password = { env = "CHAT_PASSWORD" }
+            "#,
+        )
+        .unwrap();
+
+        let home = config.profile(None).unwrap();
+        assert_eq!(home.address.as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(home.password, PasswordSource::Prompt);
+
+        let work = config.profile(Some("work")).unwrap();
+        assert_eq!(work.address, None);
+        assert_eq!(work.password, PasswordSource::Env("CHAT_PASSWORD".into()));
+    }
+
+    #[test]
+    fn returns_error_for_unknown_profile() {
+        let config = ClientConfig::from_str("default_profile = \"home\"").unwrap();
+
+        assert!(
+            matches!(config.profile(None), Err(ConfigError::UnknownProfile(name)) if name == "home")
+        );
+    }
+
+    #[test]
+    fn returns_error_for_unknown_settings() {
+        let config = ClientConfig::from_str("profiles.home.tls = true");
+
+        assert!(matches!(config, Err(ConfigError::ParseFailure(_))));
+    }
+
+    #[test]
+    fn loads_default_config_if_file_does_not_exist() {
+        let path = temp_path("client").with_extension("toml");
+
+        assert_eq!(
+            ClientConfig::load_or_default(&path).unwrap(),
+            ClientConfig::default()
+        );
+    }
+
+    #[test]
+    fn reads_password_from_env_var() {
+        let name = unique_name("REALTIME_CHAT_TEST");
+        std::env::set_var(&name, "password123");
+
+        let password = PasswordSource::Env(name.clone()).read();
+        std::env::remove_var(&name);
+
+        assert_eq!(password.unwrap(), "password123");
+        assert!(matches!(
+            PasswordSource::Env(name).read(),
+            Err(ConfigError::MissingEnvVar(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_password_file_only_accessible_by_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path("password");
+        fs::write(&path, "password123\n").unwrap();
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let insecure = PasswordSource::File(path.clone()).read();
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        let password = PasswordSource::File(path.clone()).read();
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            insecure,
+            Err(ConfigError::InsecurePasswordFile(_))
+        ));
+        assert_eq!(password.unwrap(), "password123");
+    }
+}
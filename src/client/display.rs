@@ -0,0 +1,411 @@
+use crate::frame::Frame;
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+use time::{macros::format_description, OffsetDateTime, UtcOffset};
+
+/// The kinds of line displayed by the client, each of which has its own
+/// style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineKind {
+    Message,
+    Mention,
+    ServerMessage,
+    Action,
+    PrivateMessage,
+    /// The quote of the parent message above a reply.
+    Quote,
+    Reply,
+    Reaction,
+    Typing,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Colour {
+    /// The terminal's own foreground colour.
+    #[default]
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+    DarkGray,
+}
+
+/// How a kind of line is displayed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineStyle {
+    pub colour: Colour,
+    pub bold: bool,
+    /// Displayed before the line, such as `* ` before an action.
+    pub prefix: String,
+}
+
+/// Changes to a theme's style for a kind of line. Anything left out keeps the
+/// theme's style.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StyleOverride {
+    pub colour: Option<Colour>,
+    pub bold: Option<bool>,
+    pub prefix: Option<String>,
+}
+
+/// A built-in set of styles for each kind of line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// A distinct colour for each kind of line.
+    #[default]
+    Default,
+    /// No colours, for terminals that don't support them.
+    Plain,
+}
+
+/// How the client displays what it receives from the server. For example:
+///
+/// ```toml
+/// [display]
+/// timestamps = true
+/// theme = "plain"
+///
+/// [display.styles.action]
+/// colour = "green"
+/// prefix = "* "
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// Show the local time each line was received. Can also be changed with
+    /// `/set timestamps on|off`.
+    pub timestamps: bool,
+    /// Can also be changed with `/theme`.
+    pub theme: Theme,
+    pub styles: BTreeMap<LineKind, StyleOverride>,
+}
+
+/// A line of text to display, with the style to display it in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledLine {
+    pub kind: LineKind,
+    pub text: String,
+    pub colour: Colour,
+    pub bold: bool,
+}
+
+/// Renders the frames received from the server as styled lines of text.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub timestamps: bool,
+    pub theme: Theme,
+    styles: BTreeMap<LineKind, StyleOverride>,
+    /// The offset of the local time zone, which timestamps are displayed in.
+    offset: UtcOffset,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Default, Theme::Plain];
+
+    pub fn style(self, kind: LineKind) -> LineStyle {
+        let (colour, bold, prefix) = match kind {
+            LineKind::Message | LineKind::Reply => (Colour::Default, false, ""),
+            LineKind::Mention => (Colour::Yellow, true, ""),
+            LineKind::ServerMessage => (Colour::Cyan, false, ""),
+            LineKind::Action => (Colour::Cyan, false, "* "),
+            LineKind::PrivateMessage => (Colour::Magenta, false, "-> "),
+            LineKind::Quote => (Colour::DarkGray, false, "  > "),
+            LineKind::Reaction | LineKind::Typing => (Colour::DarkGray, false, ""),
+            LineKind::Error => (Colour::Red, false, ""),
+        };
+
+        match self {
+            Self::Default => LineStyle {
+                colour,
+                bold,
+                prefix: prefix.into(),
+            },
+            Self::Plain => LineStyle {
+                prefix: prefix.into(),
+                ..LineStyle::default()
+            },
+        }
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Theme::Default => "default",
+            Theme::Plain => "plain",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.to_string() == value)
+            .ok_or_else(|| format!("Unknown theme: {value}."))
+    }
+}
+
+impl Renderer {
+    /// Creates a renderer that displays timestamps with the provided offset
+    /// from UTC.
+    pub fn new(config: DisplayConfig, offset: UtcOffset) -> Self {
+        Self {
+            timestamps: config.timestamps,
+            theme: config.theme,
+            styles: config.styles,
+            offset,
+        }
+    }
+
+    /// The theme's style for a kind of line, with any overrides applied.
+    pub fn style(&self, kind: LineKind) -> LineStyle {
+        let mut style = self.theme.style(kind);
+
+        if let Some(style_override) = self.styles.get(&kind) {
+            let style_override = style_override.clone();
+            style.colour = style_override.colour.unwrap_or(style.colour);
+            style.bold = style_override.bold.unwrap_or(style.bold);
+            style.prefix = style_override.prefix.unwrap_or(style.prefix);
+        }
+
+        style
+    }
+
+    /// Renders a frame received at the provided time. Returns no lines for
+    /// frames that aren't displayed.
+    pub fn render(&self, frame: &Frame, at: OffsetDateTime) -> Vec<StyledLine> {
+        let parts = match frame {
+            Frame::Message(message) => vec![(LineKind::Message, message.clone())],
+            Frame::Mention(message) => vec![(LineKind::Mention, message.clone())],
+            Frame::ServerMessage(message) => vec![(LineKind::ServerMessage, message.clone())],
+            Frame::Action(message) => vec![(LineKind::Action, message.clone())],
+            Frame::PrivateMessage(message) => vec![(LineKind::PrivateMessage, message.clone())],
+            // Replies are rendered beneath a short quote of the parent message.
+            Frame::Reply(message) => match message.split_once('\n') {
+                Some((quote, reply)) => vec![
+                    (LineKind::Quote, quote.to_owned()),
+                    (LineKind::Reply, reply.to_owned()),
+                ],
+                None => vec![(LineKind::Reply, message.clone())],
+            },
+            // Replies that mention the user are highlighted beneath their quote.
+            Frame::ReplyMention(message) => match message.split_once('\n') {
+                Some((quote, reply)) => vec![
+                    (LineKind::Quote, quote.to_owned()),
+                    (LineKind::Mention, reply.to_owned()),
+                ],
+                None => vec![(LineKind::Mention, message.clone())],
+            },
+            Frame::Reaction(message) => vec![(LineKind::Reaction, message.clone())],
+            Frame::TypingStart(username) => {
+                vec![(LineKind::Typing, format!("{username} is typing…"))]
+            }
+            Frame::Error(message) => vec![(LineKind::Error, message.clone())],
+            Frame::Capabilities(_)
+            | Frame::TypingStop(_)
+            | Frame::ReadMarker(_)
+            | Frame::Resume(_)
+            | Frame::Presence(_)
+            | Frame::Ack(_) => return vec![],
+        };
+
+        // Only the first line shows the timestamp.
+        let mut timestamp = match self.timestamps {
+            true => self.timestamp(at),
+            false => String::new(),
+        };
+        let mut lines = vec![];
+
+        for (kind, text) in parts {
+            let style = self.style(kind);
+
+            for (index, line) in text.lines().enumerate() {
+                // Only the first line of each part has the prefix.
+                let prefix = match index {
+                    0 => style.prefix.as_str(),
+                    _ => "",
+                };
+
+                lines.push(StyledLine {
+                    kind,
+                    text: format!(
+                        "{}{prefix}{}",
+                        std::mem::take(&mut timestamp),
+                        strip_control_characters(line)
+                    ),
+                    colour: style.colour,
+                    bold: style.bold,
+                });
+            }
+        }
+
+        lines
+    }
+
+    fn timestamp(&self, at: OffsetDateTime) -> String {
+        at.to_offset(self.offset)
+            .format(format_description!("[[[hour]:[minute]] "))
+            .unwrap_or_default()
+    }
+}
+
+/// Removes control characters from text received from the server, so that it
+/// can't move the cursor, change colours or otherwise take over the terminal.
+/// Tabs are kept as a space.
+fn strip_control_characters(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::ClientConfig;
+    use fake::{faker::lorem::en::Word, Fake};
+    use time::macros::{datetime, offset};
+
+    const AT: OffsetDateTime = datetime!(2026-10-19 09:30:05 UTC);
+
+    fn renderer(config: DisplayConfig) -> Renderer {
+        Renderer::new(config, offset!(+2))
+    }
+
+    #[test]
+    fn renders_messages_with_theme_style() {
+        let message: String = Word().fake();
+        let lines = renderer(DisplayConfig::default()).render(&Frame::Mention(message.clone()), AT);
+
+        assert_eq!(
+            lines,
+            [StyledLine {
+                kind: LineKind::Mention,
+                text: message,
+                colour: Colour::Yellow,
+                bold: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn renders_prefixes_for_actions_and_whispers() {
+        let renderer = renderer(DisplayConfig::default());
+        let action = renderer.render(&Frame::Action("alice!a1 is waving".into()), AT);
+        let whisper = renderer.render(&Frame::PrivateMessage("From bob!b2: hi".into()), AT);
+
+        assert_eq!(action[0].text, "* alice!a1 is waving");
+        assert_eq!(whisper[0].text, "-> From bob!b2: hi");
+    }
+
+    #[test]
+    fn strips_control_characters_from_text() {
+        let frame = Frame::Message("alice!a1: \x1b[2J\x1b]0;pwned\x07hi\tthere\r\u{9b}31m".into());
+        let lines = renderer(DisplayConfig::default()).render(&frame, AT);
+
+        assert_eq!(lines[0].text, "alice!a1: [2J]0;pwnedhi there31m");
+    }
+
+    #[test]
+    fn renders_reply_beneath_quote() {
+        let frame = Frame::Reply("[1] alice!a1: hi\n[2] bob!b2: hello".into());
+        let lines = renderer(DisplayConfig::default()).render(&frame, AT);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].kind, LineKind::Quote);
+        assert_eq!(lines[0].text, "  > [1] alice!a1: hi");
+        assert_eq!(lines[1].text, "[2] bob!b2: hello");
+    }
+
+    #[test]
+    fn highlights_reply_that_mentions_user_beneath_quote() {
+        let frame = Frame::ReplyMention("[1] alice!a1: hi\n[2] bob!b2: @alice hello".into());
+        let lines = renderer(DisplayConfig::default()).render(&frame, AT);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].kind, LineKind::Quote);
+        assert_eq!(lines[1].kind, LineKind::Mention);
+        assert_eq!(lines[1].text, "[2] bob!b2: @alice hello");
+    }
+
+    #[test]
+    fn renders_multiline_mention_without_quote() {
+        let frame = Frame::Mention("[1] alice!a1: hi\n[99] admin!xyz: approved @bob".into());
+        let lines = renderer(DisplayConfig::default()).render(&frame, AT);
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.kind == LineKind::Mention));
+    }
+
+    #[test]
+    fn renders_timestamp_in_local_time_on_first_line() {
+        let config = DisplayConfig {
+            timestamps: true,
+            ..DisplayConfig::default()
+        };
+        let frame = Frame::ServerMessage("Commands:\n/help".into());
+        let lines = renderer(config).render(&frame, AT);
+
+        assert_eq!(lines[0].text, "[11:30] Commands:");
+        assert_eq!(lines[1].text, "/help");
+    }
+
+    #[test]
+    fn does_not_render_hidden_frames() {
+        let renderer = renderer(DisplayConfig::default());
+
+        assert!(renderer.render(&Frame::Ack("1".into()), AT).is_empty());
+        assert!(renderer
+            .render(&Frame::Presence("+alice!a1".into()), AT)
+            .is_empty());
+    }
+
+    #[test]
+    fn applies_style_overrides_to_theme() {
+        let config = ClientConfig::from_str(
+            r#"
+            [display]
+            theme = "plain"
+
+            [display.styles.action]
+            colour = "green"
+            prefix = "** "
+            "#,
+        )
+        .unwrap();
+        let renderer = renderer(config.display);
+
+        assert_eq!(
+            renderer.style(LineKind::Action),
+            LineStyle {
+                colour: Colour::Green,
+                bold: false,
+                prefix: "** ".into(),
+            }
+        );
+        assert_eq!(renderer.style(LineKind::Mention), LineStyle::default());
+    }
+
+    #[test]
+    fn parses_theme_names() {
+        assert_eq!("plain".parse(), Ok(Theme::Plain));
+        assert!("solarized".parse::<Theme>().is_err());
+    }
+}
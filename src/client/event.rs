@@ -0,0 +1,283 @@
+use crate::{
+    domain::{parse_message_id, MessageId, Presence},
+    frame::Frame,
+};
+use std::fmt::Display;
+
+/// A message sent to the chat, formatted by the server as
+/// `[42] some_user!uQ8unuo3Mk: hello`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub id: MessageId,
+    pub author: String,
+    pub text: String,
+}
+
+/// Whether a private message was sent to the client, or by it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    From,
+    To,
+}
+
+/// A private message, formatted by the server as `From some_user!uQ8unuo3Mk:
+/// hello`, or `To ...` for the copy sent back to its sender.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Whisper {
+    pub direction: Direction,
+    pub username: String,
+    pub text: String,
+}
+
+/// Something received from the server, parsed from the frame it was sent in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Message(ChatMessage),
+    /// A message that mentions the client.
+    Mention(ChatMessage),
+    /// A reply, beneath a short quote of the message it replies to.
+    Reply {
+        quote: String,
+        message: ChatMessage,
+        /// Whether the reply mentions the client.
+        mention: bool,
+    },
+    /// An action a user describes with `/me`.
+    Action(String),
+    Whisper(Whisper),
+    ServerMessage(String),
+    Reaction(String),
+    TypingStarted(String),
+    TypingStopped(String),
+    Presence(Presence),
+    /// The server accepted a message, with its ID, or a command, without one.
+    Ack(Option<MessageId>),
+    Error(String),
+    /// A frame that doesn't match any of the other events.
+    Other(Frame),
+}
+
+impl ChatMessage {
+    pub fn parse(value: &str) -> Option<Self> {
+        let id = parse_message_id(value)?;
+        let (_, rest) = value.split_once("] ")?;
+        let (author, text) = rest.split_once(": ")?;
+
+        Some(Self {
+            id,
+            author: author.into(),
+            text: text.into(),
+        })
+    }
+}
+
+impl Display for ChatMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}: {}", self.id, self.author, self.text)
+    }
+}
+
+impl Whisper {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (direction, rest) = value.split_once(' ')?;
+        let (username, text) = rest.split_once(": ")?;
+
+        let direction = match direction {
+            "From" => Direction::From,
+            "To" => Direction::To,
+            _ => return None,
+        };
+
+        Some(Self {
+            direction,
+            username: username.into(),
+            text: text.into(),
+        })
+    }
+}
+
+impl Display for Whisper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = match self.direction {
+            Direction::From => "From",
+            Direction::To => "To",
+        };
+
+        write!(f, "{} {}: {}", direction, self.username, self.text)
+    }
+}
+
+impl Event {
+    /// The ID of the chat message in the event, if it contains one.
+    pub fn message_id(&self) -> Option<MessageId> {
+        match self {
+            Event::Message(message) | Event::Mention(message) | Event::Reply { message, .. } => {
+                Some(message.id)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<Frame> for Event {
+    /// Parses a frame received from the server. Frames that can't be parsed
+    /// are kept as they are in `Event::Other`.
+    fn from(frame: Frame) -> Self {
+        let event = match &frame {
+            Frame::Message(message) => ChatMessage::parse(message).map(Event::Message),
+            Frame::Mention(message) => ChatMessage::parse(message).map(Event::Mention),
+            Frame::Reply(message) => parse_reply(message, false),
+            Frame::ReplyMention(message) => parse_reply(message, true),
+            Frame::Action(action) => Some(Event::Action(action.clone())),
+            Frame::PrivateMessage(message) => Whisper::parse(message).map(Event::Whisper),
+            Frame::ServerMessage(message) => Some(Event::ServerMessage(message.clone())),
+            Frame::Reaction(message) => Some(Event::Reaction(message.clone())),
+            Frame::TypingStart(username) => Some(Event::TypingStarted(username.clone())),
+            Frame::TypingStop(username) => Some(Event::TypingStopped(username.clone())),
+            Frame::Presence(presence) => presence.parse().ok().map(Event::Presence),
+            Frame::Ack(id) if id.is_empty() => Some(Event::Ack(None)),
+            Frame::Ack(id) => id.parse().ok().map(|id| Event::Ack(Some(id))),
+            Frame::Error(message) => Some(Event::Error(message.clone())),
+            _ => None,
+        };
+
+        event.unwrap_or(Event::Other(frame))
+    }
+}
+
+/// Parses a reply beneath the quote of its parent message.
+fn parse_reply(value: &str, mention: bool) -> Option<Event> {
+    let (quote, reply) = value.split_once('\n')?;
+
+    Some(Event::Reply {
+        quote: quote.into(),
+        message: ChatMessage::parse(reply)?,
+        mention,
+    })
+}
+
+impl From<Event> for Frame {
+    /// Formats an event as the frame it was parsed from.
+    fn from(event: Event) -> Self {
+        match event {
+            Event::Message(message) => Frame::Message(message.to_string()),
+            Event::Mention(message) => Frame::Mention(message.to_string()),
+            Event::Reply {
+                quote,
+                message,
+                mention,
+            } => match mention {
+                true => Frame::ReplyMention(format!("{quote}\n{message}")),
+                false => Frame::Reply(format!("{quote}\n{message}")),
+            },
+            Event::Action(action) => Frame::Action(action),
+            Event::Whisper(whisper) => Frame::PrivateMessage(whisper.to_string()),
+            Event::ServerMessage(message) => Frame::ServerMessage(message),
+            Event::Reaction(message) => Frame::Reaction(message),
+            Event::TypingStarted(username) => Frame::TypingStart(username),
+            Event::TypingStopped(username) => Frame::TypingStop(username),
+            Event::Presence(presence) => Frame::Presence(presence.to_string()),
+            Event::Ack(id) => Frame::Ack(id.map(|id| id.to_string()).unwrap_or_default()),
+            Event::Error(message) => Frame::Error(message),
+            Event::Other(frame) => frame,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Sentence, Fake};
+
+    fn round_trip(frame: Frame) -> Event {
+        let event = Event::from(frame.clone());
+        assert_eq!(Frame::from(event.clone()), frame);
+
+        event
+    }
+
+    #[test]
+    fn parses_chat_messages() {
+        let text: String = Sentence(1..5).fake();
+        let event = round_trip(Frame::Mention(format!("[42] alice!a1: {text}")));
+
+        assert_eq!(
+            event,
+            Event::Mention(ChatMessage {
+                id: 42,
+                author: "alice!a1".into(),
+                text,
+            })
+        );
+        assert_eq!(event.message_id(), Some(42));
+    }
+
+    #[test]
+    fn parses_replies() {
+        let event = round_trip(Frame::Reply("[1] alice!a1: hi\n[2] bob!b2: hello".into()));
+
+        assert_eq!(event.message_id(), Some(2));
+        assert!(matches!(event, Event::Reply { quote, .. } if quote == "[1] alice!a1: hi"));
+    }
+
+    #[test]
+    fn parses_replies_that_mention_client() {
+        let event = round_trip(Frame::ReplyMention(
+            "[1] alice!a1: hi\n[2] bob!b2: @alice hello".into(),
+        ));
+
+        assert_eq!(event.message_id(), Some(2));
+        assert!(matches!(event, Event::Reply { mention: true, .. }));
+    }
+
+    #[test]
+    fn parses_multiline_mentions_as_mentions() {
+        // The second line is part of the message, so can't pass as a reply.
+        let text = "hi\n[99] admin!xyz: approved @bob";
+        let event = round_trip(Frame::Mention(format!("[1] alice!a1: {text}")));
+
+        assert_eq!(
+            event,
+            Event::Mention(ChatMessage {
+                id: 1,
+                author: "alice!a1".into(),
+                text: text.into(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_whispers() {
+        let event = round_trip(Frame::PrivateMessage("To bob!b2: psst: hi".into()));
+
+        assert_eq!(
+            event,
+            Event::Whisper(Whisper {
+                direction: Direction::To,
+                username: "bob!b2".into(),
+                text: "psst: hi".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_acks_and_presence() {
+        assert_eq!(round_trip(Frame::Ack("7".into())), Event::Ack(Some(7)));
+        assert_eq!(round_trip(Frame::Ack(String::new())), Event::Ack(None));
+        assert_eq!(
+            round_trip(Frame::Presence("+alice!a1".into())),
+            Event::Presence(Presence::Joined("alice!a1".into()))
+        );
+    }
+
+    #[test]
+    fn keeps_frames_that_do_not_parse() {
+        let frame = Frame::Message("not a chat message".into());
+
+        assert_eq!(round_trip(frame.clone()), Event::Other(frame));
+        assert!(matches!(
+            round_trip(Frame::Resume("token".into())),
+            Event::Other(_)
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use super::input_line::MAX_HISTORY;
+use crate::{
+    commands::{split_name, CommandInfo},
+    utils::private_file,
+};
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+// Commands that take a password, which are never saved to the history.
+const SECRET_COMMANDS: &[&str] = &["passwd", "register"];
+
+/// A file that keeps the lines typed into the client, one per line, so that
+/// the input history persists across sessions. The file can only be read by
+/// its owner.
+#[derive(Debug, Clone)]
+pub struct HistoryFile {
+    path: PathBuf,
+}
+
+impl HistoryFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Loads the most recent lines in the history, oldest first. The file
+    /// doesn't need to exist yet, as it's created when the first line is
+    /// appended.
+    pub fn load(&self) -> io::Result<Vec<String>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let mut lines: Vec<String> = contents.lines().map(str::to_owned).collect();
+        lines.dedup();
+
+        // Lines are only ever appended, so the file is trimmed when loaded to
+        // stop it growing forever.
+        if lines.len() > MAX_HISTORY {
+            lines.drain(..lines.len() - MAX_HISTORY);
+            fs::write(&self.path, lines.join("\n") + "\n")?;
+        }
+
+        Ok(lines)
+    }
+
+    /// Appends a line to the history, unless it's a command with a password.
+    pub fn append(&self, line: &str) -> io::Result<()> {
+        if is_secret(line) {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = private_file().create(true).append(true).open(&self.path)?;
+
+        writeln!(file, "{line}")
+    }
+}
+
+/// Whether a line is a command that takes a password, so shouldn't be kept in
+/// any input history.
+pub fn is_secret(line: &str) -> bool {
+    let Some(command) = line.strip_prefix('/') else {
+        return false;
+    };
+
+    CommandInfo::find(split_name(command).0)
+        .is_some_and(|command| SECRET_COMMANDS.contains(&command.name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::temp_path;
+
+    fn temp_file() -> HistoryFile {
+        let path = temp_path("history");
+        let _ = fs::remove_file(&path);
+
+        HistoryFile::new(path)
+    }
+
+    #[test]
+    fn loads_appended_lines() {
+        let history = temp_file();
+
+        history.append("hello").unwrap();
+        history.append("/me waves").unwrap();
+        let lines = history.load().unwrap();
+        fs::remove_file(&history.path).unwrap();
+
+        assert_eq!(lines, ["hello", "/me waves"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn creates_file_only_accessible_by_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let history = temp_file();
+
+        history.append("hello").unwrap();
+        let mode = fs::metadata(&history.path).unwrap().permissions().mode();
+        fs::remove_file(&history.path).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn loads_nothing_if_file_does_not_exist() {
+        assert!(temp_file().load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn does_not_save_passwords() {
+        assert!(is_secret("/register hunter2"));
+        assert!(is_secret("/passwd hunter2 hunter3"));
+        assert!(!is_secret("/whisper alice!a1 hi"));
+        assert!(!is_secret("register hunter2"));
+    }
+
+    #[test]
+    fn trims_history_when_loaded() {
+        let history = temp_file();
+
+        for i in 0..MAX_HISTORY + 10 {
+            history.append(&i.to_string()).unwrap();
+        }
+
+        let lines = history.load().unwrap();
+        let reloaded = history.load().unwrap();
+        fs::remove_file(&history.path).unwrap();
+
+        assert_eq!(lines.len(), MAX_HISTORY);
+        assert_eq!(lines[0], "10");
+        assert_eq!(reloaded, lines);
+    }
+}
@@ -0,0 +1,306 @@
+use super::{is_secret, Completion, OnlineUsers};
+
+// The most lines kept in the input history.
+pub(super) const MAX_HISTORY: usize = 500;
+
+/// A line of input being edited, along with the history of submitted lines.
+///
+/// The cursor is a character index, so that the line can be edited a
+/// character at a time regardless of how many bytes each takes.
+#[derive(Debug, Default)]
+pub struct InputLine {
+    chars: Vec<char>,
+    cursor: usize,
+    history: Vec<String>,
+    /// The position in the history while browsing it, and the line that was
+    /// being edited before browsing started.
+    browsing: Option<(usize, Vec<char>)>,
+}
+
+impl InputLine {
+    /// Creates an input line with a history of previously submitted lines,
+    /// oldest first.
+    pub fn with_history(history: Vec<String>) -> Self {
+        Self {
+            history,
+            ..Self::default()
+        }
+    }
+
+    pub fn value(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    /// Deletes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    /// Deletes the character under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    /// Deletes the word before the cursor, along with any whitespace after it.
+    pub fn delete_word(&mut self) {
+        let end = self.cursor;
+
+        while self.cursor > 0 && self.chars[self.cursor - 1].is_whitespace() {
+            self.cursor -= 1;
+        }
+
+        while self.cursor > 0 && !self.chars[self.cursor - 1].is_whitespace() {
+            self.cursor -= 1;
+        }
+
+        self.chars.drain(self.cursor..end);
+    }
+
+    pub fn clear(&mut self) {
+        self.chars.clear();
+        self.cursor = 0;
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.chars.len());
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.chars.len();
+    }
+
+    /// Replaces the line with the previous line in the history.
+    pub fn previous(&mut self) {
+        let index = match &self.browsing {
+            Some((0, _)) => return,
+            Some((index, _)) => index - 1,
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+
+        let draft = match self.browsing.take() {
+            Some((_, draft)) => draft,
+            None => std::mem::take(&mut self.chars),
+        };
+
+        self.browsing = Some((index, draft));
+        self.set(self.history[index].chars().collect());
+    }
+
+    /// Replaces the line with the next line in the history, or the line that
+    /// was being edited once the end of the history is reached.
+    pub fn next(&mut self) {
+        let Some((index, draft)) = self.browsing.take() else {
+            return;
+        };
+
+        match self.history.get(index + 1) {
+            Some(line) => {
+                let line = line.chars().collect();
+                self.browsing = Some((index + 1, draft));
+                self.set(line);
+            }
+            None => self.set(draft),
+        }
+    }
+
+    /// Takes the line, adding it to the history unless it's a command with a
+    /// password. Returns `None` if the line is empty.
+    pub fn submit(&mut self) -> Option<String> {
+        let line = self.value();
+
+        self.browsing = None;
+        self.clear();
+
+        if line.trim().is_empty() {
+            return None;
+        }
+
+        // Repeating the previous line doesn't add to the history.
+        if self.history.last() != Some(&line) && !is_secret(&line) {
+            self.history.push(line.clone());
+        }
+
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+
+        Some(line)
+    }
+
+    /// Completes the word before the cursor, if it can be. Returns the
+    /// candidates when there's more than one, after completing as much of the
+    /// word as they have in common.
+    pub fn complete(&mut self, users: &OnlineUsers) -> Vec<String> {
+        let value = self.value();
+        let cursor = value
+            .char_indices()
+            .nth(self.cursor)
+            .map_or(value.len(), |(index, _)| index);
+
+        let completion = Completion::new(&value, cursor, users);
+        let replacement = match completion.candidates.as_slice() {
+            [] => return vec![],
+            [candidate] => format!("{candidate} "),
+            _ => completion.common_prefix().unwrap_or_default().to_owned(),
+        };
+
+        let start = value[..completion.start].chars().count();
+        let mut chars: Vec<char> = self.chars[..start].to_vec();
+        chars.extend(replacement.chars());
+        let cursor = chars.len();
+        chars.extend_from_slice(&self.chars[self.cursor..]);
+
+        self.chars = chars;
+        self.cursor = cursor;
+
+        match completion.candidates.len() {
+            1 => vec![],
+            _ => completion.candidates,
+        }
+    }
+
+    fn set(&mut self, chars: Vec<char>) {
+        self.cursor = chars.len();
+        self.chars = chars;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::Presence;
+
+    fn typed(value: &str) -> InputLine {
+        let mut input = InputLine::default();
+        value.chars().for_each(|c| input.insert(c));
+        input
+    }
+
+    #[test]
+    fn edits_at_cursor() {
+        let mut input = typed("helo");
+
+        input.left();
+        input.insert('l');
+        input.home();
+        input.delete();
+        input.insert('H');
+        input.end();
+        input.backspace();
+
+        assert_eq!(input.value(), "Hell");
+        assert_eq!(input.cursor(), 4);
+    }
+
+    #[test]
+    fn edits_multibyte_characters() {
+        let mut input = typed("zoë");
+
+        input.backspace();
+        input.insert('e');
+
+        assert_eq!(input.value(), "zoe");
+    }
+
+    #[test]
+    fn deletes_previous_word() {
+        let mut input = typed("/whisper alice  ");
+
+        input.delete_word();
+
+        assert_eq!(input.value(), "/whisper ");
+    }
+
+    #[test]
+    fn submits_line_and_adds_to_history() {
+        let mut input = typed("hello");
+
+        assert_eq!(input.submit(), Some("hello".into()));
+        assert_eq!(input.value(), "");
+        assert_eq!(input.history(), ["hello"]);
+    }
+
+    #[test]
+    fn submits_but_does_not_keep_secret_commands() {
+        let mut input = typed("/register password123");
+
+        assert_eq!(input.submit(), Some("/register password123".into()));
+        assert!(input.history().is_empty());
+    }
+
+    #[test]
+    fn does_not_submit_blank_line() {
+        let mut input = typed("   ");
+
+        assert_eq!(input.submit(), None);
+        assert!(input.history().is_empty());
+    }
+
+    #[test]
+    fn completes_word_before_cursor() {
+        let mut users = OnlineUsers::default();
+        users.apply(Presence::Online(vec!["alice!a1".into(), "bob!b2".into()]));
+        let mut input = typed("hi @al!");
+        input.left();
+
+        assert!(input.complete(&users).is_empty());
+        assert_eq!(input.value(), "hi @alice !");
+        assert_eq!(input.cursor(), 10);
+    }
+
+    #[test]
+    fn completes_common_prefix_of_candidates() {
+        let mut input = typed("/me");
+
+        let candidates = input.complete(&OnlineUsers::default());
+
+        assert_eq!(candidates, ["/me", "/mentions"]);
+        assert_eq!(input.value(), "/me");
+    }
+
+    #[test]
+    fn browses_history_and_restores_draft() {
+        let mut input = InputLine::with_history(vec!["first".into(), "second".into()]);
+        "draft".chars().for_each(|c| input.insert(c));
+
+        input.previous();
+        assert_eq!(input.value(), "second");
+
+        input.previous();
+        input.previous();
+        assert_eq!(input.value(), "first");
+
+        input.next();
+        assert_eq!(input.value(), "second");
+
+        input.next();
+        assert_eq!(input.value(), "draft");
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    args::Args,
+    commands::{split_name, CommandInfo},
+    domain::Permission,
+    errors::CommandError,
+};
+
+/// The commands handled by the client itself, which are never sent to the
+/// server.
+pub const LOCAL_COMMANDS: &[CommandInfo] = &[
+    CommandInfo {
+        name: "clear",
+        usage: "/clear",
+        about: "Clear the screen.",
+        aliases: &[],
+        permission: Permission::User,
+    },
+    CommandInfo {
+        name: "connect",
+        usage: "/connect [address]",
+        about: "Reconnect to the server, or connect to another one.",
+        aliases: &[],
+        permission: Permission::User,
+    },
+    CommandInfo {
+        name: "log",
+        usage: "/log [on|off]",
+        about: "Turn logging the chat on or off, or show whether it's logged.",
+        aliases: &[],
+        permission: Permission::User,
+    },
+    CommandInfo {
+        name: "quit",
+        usage: "/quit",
+        about: "Quit the client.",
+        aliases: &["exit"],
+        permission: Permission::User,
+    },
+    CommandInfo {
+        name: "set",
+        usage: "/set [setting] [value]",
+        about: "Change a display setting, e.g. /set timestamps on, or list them.",
+        aliases: &[],
+        permission: Permission::User,
+    },
+    CommandInfo {
+        name: "theme",
+        usage: "/theme [name]",
+        about: "Switch to another theme, or list the themes.",
+        aliases: &[],
+        permission: Permission::User,
+    },
+];
+
+/// A command typed into the client that's run by the client itself.
+#[derive(Debug, PartialEq)]
+pub enum LocalCommand {
+    Clear,
+    /// Reconnects, to another server if an address is provided.
+    Connect(Option<String>),
+    /// Turns logging on or off, or shows whether it's on.
+    Log(Option<bool>),
+    Quit,
+    /// Changes a setting, or lists the settings.
+    Set(Option<Setting>),
+    /// Switches to a theme by name, or lists the themes.
+    Theme(Option<String>),
+}
+
+/// A display setting that can be changed with `/set`.
+#[derive(Debug, PartialEq)]
+pub enum Setting {
+    Timestamps(bool),
+}
+
+impl LocalCommand {
+    /// Parses a line of input as a local command. Returns `None` if it isn't
+    /// one, so should be sent to the server.
+    pub fn parse(input: &str) -> Option<Result<Self, CommandError>> {
+        let (name, args) = split_name(input.strip_prefix('/')?);
+        let command = LOCAL_COMMANDS
+            .iter()
+            .find(|command| command.name == name || command.aliases.contains(&name))?;
+
+        Some(Self::from_args(command.name, Args::from(args)))
+    }
+
+    fn from_args(name: &str, mut args: Args) -> Result<Self, CommandError> {
+        let command = match name {
+            "clear" => Self::Clear,
+            "connect" => Self::Connect(args.pop_optional("address")?),
+            "log" => Self::Log(args.pop_optional("on|off")?),
+            "quit" => Self::Quit,
+            "set" => match args.pop_optional::<String>("setting")? {
+                Some(setting) => Self::Set(Some(Setting::from_args(&setting, &mut args)?)),
+                None => Self::Set(None),
+            },
+            "theme" => Self::Theme(args.pop_optional("name")?),
+            name => return Err(CommandError::UnknownCommand(name.into())),
+        };
+
+        args.finish()?;
+
+        Ok(command)
+    }
+}
+
+impl Setting {
+    fn from_args(name: &str, args: &mut Args) -> Result<Self, CommandError> {
+        match name {
+            "timestamps" => Ok(Self::Timestamps(args.pop("on|off")?)),
+            name => Err(CommandError::InvalidArgument(
+                "setting".into(),
+                format!("{name} is not a setting"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::internet::en::IPv4, Fake};
+
+    #[test]
+    fn ignores_input_that_is_not_a_local_command() {
+        assert_eq!(LocalCommand::parse("quit"), None);
+        assert_eq!(LocalCommand::parse("/me waves"), None);
+    }
+
+    #[test]
+    fn parses_commands_and_aliases() {
+        assert_eq!(LocalCommand::parse("/quit"), Some(Ok(LocalCommand::Quit)));
+        assert_eq!(LocalCommand::parse("/exit"), Some(Ok(LocalCommand::Quit)));
+        assert_eq!(LocalCommand::parse("/clear"), Some(Ok(LocalCommand::Clear)));
+    }
+
+    #[test]
+    fn parses_connect_with_optional_address() {
+        let address = format!("{}:8080", IPv4().fake::<String>());
+        let command = LocalCommand::parse(&format!("/connect {address}"));
+
+        assert_eq!(command, Some(Ok(LocalCommand::Connect(Some(address)))));
+        assert_eq!(
+            LocalCommand::parse("/connect"),
+            Some(Ok(LocalCommand::Connect(None)))
+        );
+    }
+
+    #[test]
+    fn parses_settings() {
+        assert_eq!(
+            LocalCommand::parse("/set timestamps on"),
+            Some(Ok(LocalCommand::Set(Some(Setting::Timestamps(true)))))
+        );
+        assert_eq!(
+            LocalCommand::parse("/set"),
+            Some(Ok(LocalCommand::Set(None)))
+        );
+    }
+
+    #[test]
+    fn returns_error_for_invalid_arguments() {
+        assert_eq!(
+            LocalCommand::parse("/set colours on"),
+            Some(Err(CommandError::InvalidArgument(
+                "setting".into(),
+                "colours is not a setting".into()
+            )))
+        );
+        assert_eq!(
+            LocalCommand::parse("/set timestamps"),
+            Some(Err(CommandError::MissingArgument("on|off".into())))
+        );
+        assert_eq!(
+            LocalCommand::parse("/quit now"),
+            Some(Err(CommandError::TooManyArguments))
+        );
+    }
+}
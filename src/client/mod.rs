@@ -0,0 +1,23 @@
+mod backoff;
+mod chat_client;
+mod completion;
+mod config;
+mod display;
+mod event;
+mod history_file;
+mod input_line;
+mod local_command;
+mod online_users;
+mod transcript;
+
+pub use backoff::*;
+pub use chat_client::*;
+pub use completion::*;
+pub use config::*;
+pub use display::*;
+pub use event::*;
+pub use history_file::*;
+pub use input_line::*;
+pub use local_command::*;
+pub use online_users::*;
+pub use transcript::*;
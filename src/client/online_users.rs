@@ -0,0 +1,75 @@
+use crate::domain::Presence;
+use std::collections::BTreeSet;
+
+/// The users that are online, as reported by the server's presence updates.
+#[derive(Debug, Default)]
+pub struct OnlineUsers(BTreeSet<String>);
+
+impl OnlineUsers {
+    pub fn apply(&mut self, presence: Presence) {
+        match presence {
+            Presence::Online(usernames) => self.0 = usernames.into_iter().collect(),
+            Presence::More(usernames) => self.0.extend(usernames),
+            Presence::Joined(username) => {
+                self.0.insert(username);
+            }
+            Presence::Left(username) => {
+                self.0.remove(&username);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The online usernames, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn applies_presence_updates() {
+        let mut users = OnlineUsers::default();
+
+        users.apply(Presence::Online(vec!["bob!b".into(), "alice!a".into()]));
+        users.apply(Presence::Joined("carol!c".into()));
+        users.apply(Presence::Left("bob!b".into()));
+
+        assert_eq!(users.iter().collect::<Vec<_>>(), ["alice!a", "carol!c"]);
+    }
+
+    #[test]
+    fn replaces_users_with_online_list() {
+        let mut users = OnlineUsers::default();
+
+        users.apply(Presence::Joined("alice!a".into()));
+        users.apply(Presence::Online(vec!["bob!b".into()]));
+
+        assert_eq!(users.iter().collect::<Vec<_>>(), ["bob!b"]);
+    }
+
+    #[test]
+    fn continues_online_list_with_more_users() {
+        let mut users = OnlineUsers::default();
+
+        users.apply(Presence::Joined("alice!a".into()));
+        users.apply(Presence::Online(vec!["bob!b".into()]));
+        users.apply(Presence::More(vec!["carol!c".into()]));
+
+        assert_eq!(users.iter().collect::<Vec<_>>(), ["bob!b", "carol!c"]);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::frame::Frame;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use time::{format_description::well_known::Rfc3339, macros::format_description, OffsetDateTime};
+
+// The conversation that messages to everyone are logged to.
+const CHAT_CONVERSATION: &str = "chat";
+
+/// How each entry in a transcript is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// A line of text per entry, after the time it was received in UTC.
+    #[default]
+    Text,
+    /// A JSON object per line, with the frame's `type` and `text`, and the
+    /// `time` it was received.
+    Jsonl,
+}
+
+/// Where transcripts are written, and how they're rotated. For example:
+///
+/// ```toml
+/// [transcript]
+/// enabled = true
+/// format = "jsonl"
+/// max_bytes = 1048576
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TranscriptConfig {
+    /// Whether transcripts are written from the start. They can also be
+    /// turned on and off with `/log on|off`.
+    pub enabled: bool,
+    /// Defaults to a directory in the user's data directory.
+    pub directory: Option<PathBuf>,
+    pub format: TranscriptFormat,
+    /// Start a new file for each conversation every day.
+    pub daily: bool,
+    /// Start a new file once a file would grow past this size.
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for TranscriptConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            format: TranscriptFormat::default(),
+            daily: true,
+            max_bytes: None,
+        }
+    }
+}
+
+/// A log of the frames received from a server, written to a file per
+/// conversation: one for the chat, and one for each user whispered with.
+#[derive(Debug)]
+pub struct Transcript {
+    pub enabled: bool,
+    /// The directory containing each server's transcripts.
+    root: PathBuf,
+    directory: PathBuf,
+    format: TranscriptFormat,
+    daily: bool,
+    max_bytes: Option<u64>,
+}
+
+/// An entry in a JSON lines transcript.
+#[derive(Serialize)]
+struct Entry<'a> {
+    time: String,
+    #[serde(flatten)]
+    frame: &'a Frame,
+}
+
+impl Transcript {
+    /// Creates a transcript of the frames received from a server, which is
+    /// kept in its own directory.
+    pub fn new(config: TranscriptConfig, server: &str) -> Self {
+        let root = config
+            .directory
+            .or_else(|| Some(dirs::data_dir()?.join("realtime-chat").join("logs")))
+            .unwrap_or_default();
+
+        Self {
+            enabled: config.enabled,
+            directory: root.join(file_name(server)),
+            root,
+            format: config.format,
+            daily: config.daily,
+            max_bytes: config.max_bytes,
+        }
+    }
+
+    /// Writes the transcript to another server's directory from now on.
+    pub fn set_server(&mut self, server: &str) {
+        self.directory = self.root.join(file_name(server));
+    }
+
+    /// The directory the transcript is written to.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Writes a frame to its conversation's transcript, if it's displayed to
+    /// the user and transcripts are enabled.
+    pub fn record(&self, frame: &Frame, at: OffsetDateTime) -> io::Result<()> {
+        let Some(conversation) = conversation(frame).filter(|_| self.enabled) else {
+            return Ok(());
+        };
+
+        let mut entry = match self.format {
+            TranscriptFormat::Text => {
+                let time = at
+                    .format(format_description!(
+                        "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    ))
+                    .map_err(io::Error::other)?;
+
+                // Continuation lines, e.g. the reply beneath a quote, are indented.
+                let message = frame.clone().message().replace('\n', "\n    ");
+
+                format!("[{time}] {message}")
+            }
+            TranscriptFormat::Jsonl => {
+                let time = at.format(&Rfc3339).map_err(io::Error::other)?;
+                serde_json::to_string(&Entry { time, frame })?
+            }
+        };
+        entry.push('\n');
+
+        fs::create_dir_all(&self.directory)?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(&conversation, at, entry.len() as u64)?)?;
+
+        file.write_all(entry.as_bytes())
+    }
+
+    /// The file to write an entry of the provided length to, which is a new
+    /// file each day, and each time the previous file would grow too large.
+    fn path(&self, conversation: &str, at: OffsetDateTime, length: u64) -> io::Result<PathBuf> {
+        let mut base = conversation.to_owned();
+
+        if self.daily {
+            let date = at
+                .format(format_description!("[year]-[month]-[day]"))
+                .map_err(io::Error::other)?;
+            base = format!("{base}-{date}");
+        }
+
+        let extension = match self.format {
+            TranscriptFormat::Text => "log",
+            TranscriptFormat::Jsonl => "jsonl",
+        };
+
+        let mut index = 0;
+
+        loop {
+            let path = match index {
+                0 => self.directory.join(format!("{base}.{extension}")),
+                index => self.directory.join(format!("{base}.{index}.{extension}")),
+            };
+
+            let size = match fs::metadata(&path) {
+                Ok(metadata) => metadata.len(),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(path),
+                Err(e) => return Err(e),
+            };
+
+            // An entry larger than the limit is still written to an empty file.
+            match self.max_bytes {
+                Some(max) if size > 0 && size + length > max => index += 1,
+                _ => return Ok(path),
+            }
+        }
+    }
+}
+
+/// The conversation a frame belongs to, or `None` if it isn't displayed.
+fn conversation(frame: &Frame) -> Option<String> {
+    match frame {
+        // Private messages are formatted as `From user: ...` or `To user: ...`.
+        Frame::PrivateMessage(message) => {
+            let (_, rest) = message.split_once(' ')?;
+            let (username, _) = rest.split_once(": ")?;
+
+            Some(format!("whisper-{}", file_name(username)))
+        }
+        Frame::Message(_)
+        | Frame::Mention(_)
+        | Frame::ServerMessage(_)
+        | Frame::Action(_)
+        | Frame::Reply(_)
+        | Frame::ReplyMention(_)
+        | Frame::Reaction(_)
+        | Frame::Error(_) => Some(CHAT_CONVERSATION.into()),
+        _ => None,
+    }
+}
+
+/// Replaces any characters that aren't safe to use in a file name.
+fn file_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c.is_alphanumeric() || "!-_".contains(c) {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::temp_path;
+    use time::macros::datetime;
+
+    const AT: OffsetDateTime = datetime!(2026-10-19 09:30:05 UTC);
+
+    fn transcript(format: TranscriptFormat, max_bytes: Option<u64>) -> Transcript {
+        let directory = temp_path("logs");
+        let _ = fs::remove_dir_all(&directory);
+
+        let config = TranscriptConfig {
+            enabled: true,
+            directory: Some(directory),
+            format,
+            max_bytes,
+            ..TranscriptConfig::default()
+        };
+
+        Transcript::new(config, "127.0.0.1:8080")
+    }
+
+    fn read(transcript: &Transcript, name: &str) -> String {
+        fs::read_to_string(transcript.directory.join(name)).unwrap()
+    }
+
+    #[test]
+    fn writes_text_entries_per_conversation_and_day() {
+        let transcript = transcript(TranscriptFormat::Text, None);
+
+        transcript
+            .record(&Frame::Message("[1] alice!a1: hi".into()), AT)
+            .unwrap();
+        transcript
+            .record(&Frame::PrivateMessage("From bob!b2: psst".into()), AT)
+            .unwrap();
+
+        assert!(transcript.directory.ends_with("127_0_0_1_8080"));
+        assert_eq!(
+            read(&transcript, "chat-2026-10-19.log"),
+            "[2026-10-19 09:30:05] [1] alice!a1: hi\n"
+        );
+        assert_eq!(
+            read(&transcript, "whisper-bob!b2-2026-10-19.log"),
+            "[2026-10-19 09:30:05] From bob!b2: psst\n"
+        );
+
+        fs::remove_dir_all(&transcript.directory).unwrap();
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let transcript = transcript(TranscriptFormat::Jsonl, None);
+
+        transcript
+            .record(&Frame::ServerMessage("hello".into()), AT)
+            .unwrap();
+
+        assert_eq!(
+            read(&transcript, "chat-2026-10-19.jsonl"),
+            "{\"time\":\"2026-10-19T09:30:05Z\",\"type\":\"server_message\",\"text\":\"hello\"}\n"
+        );
+
+        fs::remove_dir_all(&transcript.directory).unwrap();
+    }
+
+    #[test]
+    fn rotates_files_that_would_grow_too_large() {
+        let transcript = transcript(TranscriptFormat::Text, Some(40));
+        let frame = Frame::Message("[1] alice!a1: hi".into());
+
+        for _ in 0..3 {
+            transcript.record(&frame, AT).unwrap();
+        }
+
+        assert_eq!(read(&transcript, "chat-2026-10-19.log").lines().count(), 1);
+        assert_eq!(
+            read(&transcript, "chat-2026-10-19.1.log").lines().count(),
+            1
+        );
+        assert_eq!(
+            read(&transcript, "chat-2026-10-19.2.log").lines().count(),
+            1
+        );
+
+        fs::remove_dir_all(&transcript.directory).unwrap();
+    }
+
+    #[test]
+    fn writes_to_new_server_directory() {
+        let mut transcript = transcript(TranscriptFormat::Text, None);
+
+        transcript.set_server("chat.example.com:8080");
+
+        assert_eq!(
+            transcript.directory(),
+            transcript.root.join("chat_example_com_8080")
+        );
+    }
+
+    #[test]
+    fn does_not_write_hidden_frames_or_when_disabled() {
+        let mut transcript = transcript(TranscriptFormat::Text, None);
+
+        transcript
+            .record(&Frame::Presence("+alice!a1".into()), AT)
+            .unwrap();
+        transcript.enabled = false;
+        transcript.record(&Frame::Message("hi".into()), AT).unwrap();
+
+        assert!(!transcript.directory.exists());
+    }
+}
@@ -20,7 +20,7 @@ const RESERVED_BYTES: usize = PREFIX_BYTE_LEN + LENGTH_BYTE_LEN;
 
 // Maximum message length is 512 characters, regardless of
 // type of frame
-const MAX_LENGTH: usize = size_of::<char>() * 512;
+pub const MAX_LENGTH: usize = size_of::<char>() * 512;
 
 impl Encoder<Frame> for MessageCodec {
     type Error = Error;
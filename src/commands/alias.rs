@@ -0,0 +1,129 @@
+use super::CommandInfo;
+use crate::{
+    domain::{AliasStore, Connection},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "alias",
+    about = "Define a shortcut for a command, using $1 to $9 and $* for its arguments."
+)]
+pub struct Alias {
+    name: String,
+    #[arg(rest)]
+    expansion: String,
+}
+
+impl Alias {
+    pub fn new(name: String, expansion: String) -> Self {
+        Self { name, expansion }
+    }
+
+    /// The name of the alias, without a leading slash. Aliases can't replace
+    /// built-in commands, and must be a single word.
+    fn name(&self) -> Result<&str, CommandError> {
+        let name = self.name.strip_prefix('/').unwrap_or(&self.name);
+        let invalid = |reason: String| CommandError::InvalidArgument("name".into(), reason);
+
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(invalid(format!("{name:?} must be a single word")));
+        }
+
+        if CommandInfo::find(name).is_some() {
+            return Err(invalid(format!("/{name} is already a command")));
+        }
+
+        Ok(name)
+    }
+}
+
+#[async_trait]
+impl CommandApply for Alias {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let name = self.name()?;
+        let expansion = self.expansion.strip_prefix('/').unwrap_or(&self.expansion);
+
+        // Commands provided by plugins and scripts can't be replaced either.
+        if conn.state.lock().await.plugins.find_command(name).is_some() {
+            return Err(CommandError::InvalidArgument(
+                "name".into(),
+                format!("/{name} is already a command"),
+            ));
+        }
+
+        AliasStore::update(&conn.state, &conn.peer.username, |aliases| {
+            aliases.insert(name.into(), expansion.into())
+        })
+        .await?;
+
+        let frame = Frame::ServerMessage(format!("/{} now expands to /{}", name, expansion));
+
+        conn.messages
+            .send(frame)
+            .await
+            .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+    use fake::{
+        faker::lorem::en::{Sentence, Word},
+        Fake,
+    };
+
+    #[test]
+    fn parses_alias_command() {
+        let name: String = Word().fake();
+        let expansion: String = Sentence(1..3).fake();
+        let args = Args::from(format!("{name} {expansion}").as_str());
+
+        let command = Alias::try_from(args);
+        let expected = Alias::new(name, expansion);
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn returns_error_if_missing_expansion_arg() {
+        let name: String = Word().fake();
+        let args = Args::from(name.as_str());
+
+        let command = Alias::try_from(args);
+        let expected = CommandError::MissingArgument("expansion".into());
+
+        assert_eq!(command, Err(expected));
+    }
+
+    #[test]
+    fn strips_leading_slash_from_name() {
+        let alias = Alias::new("/greet".into(), "me waves".into());
+
+        assert_eq!(alias.name(), Ok("greet"));
+    }
+
+    #[test]
+    fn rejects_name_of_existing_command() {
+        let alias = Alias::new("msg".into(), "me waves".into());
+        let expected =
+            CommandError::InvalidArgument("name".into(), "/msg is already a command".into());
+
+        assert_eq!(alias.name(), Err(expected));
+    }
+
+    #[test]
+    fn rejects_name_containing_whitespace() {
+        let alias = Alias::new("some alias".into(), "me waves".into());
+
+        assert!(alias.name().is_err());
+    }
+}
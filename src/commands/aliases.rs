@@ -0,0 +1,75 @@
+use crate::{
+    domain::Connection,
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(name = "aliases", about = "List the aliases you have defined.")]
+pub struct Aliases {}
+
+#[async_trait]
+impl CommandApply for Aliases {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let aliases: Vec<String> = conn
+            .state
+            .lock()
+            .await
+            .aliases
+            .get(&conn.peer.username)
+            .map(|aliases| {
+                aliases
+                    .iter()
+                    .map(|(name, expansion)| format!("  /{} → /{}", name, expansion))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let header = match aliases.len() {
+            0 => String::from("You haven't defined any aliases."),
+            _ => String::from("Your aliases:"),
+        };
+
+        let frames = std::iter::once(header)
+            .chain(aliases)
+            .map(Frame::ServerMessage);
+
+        for frame in frames {
+            conn.messages
+                .send(frame)
+                .await
+                .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn parses_aliases_command() {
+        let args = Args::from("");
+
+        let command = Aliases::try_from(args);
+
+        assert_eq!(command, Ok(Aliases {}));
+    }
+
+    #[test]
+    fn returns_error_if_too_many_arguments() {
+        let word: String = Word().fake();
+        let args = Args::from(word.as_str());
+
+        let command = Aliases::try_from(args);
+
+        assert_eq!(command, Err(CommandError::TooManyArguments));
+    }
+}
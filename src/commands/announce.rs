@@ -0,0 +1,62 @@
+use crate::{
+    domain::{check_length, Connection},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "announce",
+    permission = Moderator,
+    about = "Send an announcement to everyone in the chat."
+)]
+pub struct Announce {
+    #[arg(rest)]
+    message: String,
+}
+
+impl Announce {
+    pub fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+#[async_trait]
+impl CommandApply for Announce {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let message = format!("Announcement from {}: {}", conn.peer.username, self.message);
+        check_length(&message)?;
+
+        let mut state = conn.state.lock().await;
+
+        state.broadcast_all(Frame::ServerMessage(message)).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{args::Args, commands::Command, domain::Permission};
+    use fake::{faker::lorem::en::Sentence, Fake};
+
+    #[test]
+    fn parses_announce_command() {
+        let message: String = Sentence(1..3).fake();
+        let args = Args::from(message.as_str());
+
+        let command = Announce::try_from(args);
+
+        assert_eq!(command, Ok(Announce::new(message)));
+    }
+
+    #[test]
+    fn requires_moderator_permission() {
+        let command = Command::try_from("announce hello").unwrap();
+
+        assert_eq!(command.permission(), Permission::Moderator);
+    }
+}
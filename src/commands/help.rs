@@ -1,23 +1,158 @@
-use crate::{domain::Connection, errors::CommandError, frame::Frame, traits::CommandApply};
+use super::{CommandInfo, COMMANDS};
+use crate::{
+    domain::{Connection, Permission},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
 use async_trait::async_trait;
 use futures::SinkExt;
 
-#[derive(Debug, PartialEq)]
-pub struct Help {}
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "help",
+    about = "List every command, or show how to use a command."
+)]
+pub struct Help {
+    #[arg(optional)]
+    command: Option<String>,
+}
+
+impl Help {
+    pub fn new(command: Option<String>) -> Self {
+        Self { command }
+    }
+
+    /// Formats the help text, including any commands provided by plugins.
+    fn format_lines(&self, plugin_commands: &[CommandInfo]) -> Result<Vec<String>, CommandError> {
+        let mut commands = COMMANDS.iter().chain(plugin_commands);
+
+        let Some(name) = &self.command else {
+            let header = String::from("Available commands:");
+            let commands = commands.map(|command| match command.permission {
+                Permission::User => format!("  {} - {}", command.usage, command.about),
+                permission => {
+                    format!("  {} - {} ({})", command.usage, command.about, permission)
+                }
+            });
+
+            return Ok(std::iter::once(header).chain(commands).collect());
+        };
+
+        // Allow the command to be written with or without the leading slash.
+        let name = name.strip_prefix('/').unwrap_or(name);
+
+        let command = commands
+            .find(|command| command.name == name || command.aliases.contains(&name))
+            .ok_or_else(|| CommandError::UnknownCommand(name.into()))?;
+
+        let mut lines = vec![
+            format!("Usage: {}", command.usage),
+            command.about.to_string(),
+        ];
+
+        if command.permission > Permission::User {
+            lines.push(format!(
+                "Requires the {} permission level.",
+                command.permission
+            ));
+        }
 
-impl Help {}
+        if !command.aliases.is_empty() {
+            let aliases: Vec<String> = command.aliases.iter().map(|a| format!("/{a}")).collect();
+            lines.push(format!("Aliases: {}", aliases.join(", ")));
+        }
+
+        Ok(lines)
+    }
+}
 
 #[async_trait]
 impl CommandApply for Help {
     async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
-        // TODO: Generate Command list
-        let frame = Frame::ServerMessage(String::from("Reply from HELP"));
+        let plugin_commands = conn.state.lock().await.plugins.commands();
 
-        conn.messages
-            .send(frame)
-            .await
-            .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+        for line in self.format_lines(&plugin_commands)? {
+            conn.messages
+                .send(Frame::ServerMessage(line))
+                .await
+                .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{args::Args, commands::Whisper};
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn parses_help_command() {
+        let args = Args::from("");
+
+        let command = Help::try_from(args);
+
+        assert_eq!(command, Ok(Help::new(None)));
+    }
+
+    #[test]
+    fn parses_help_command_with_command_arg() {
+        let args = Args::from("whisper");
+
+        let command = Help::try_from(args);
+
+        assert_eq!(command, Ok(Help::new(Some("whisper".into()))));
+    }
+
+    #[test]
+    fn lists_every_registered_command() {
+        let lines = Help::new(None).format_lines(&[]).unwrap();
+
+        assert_eq!(lines.len(), COMMANDS.len() + 1);
+        assert!(lines.iter().any(|line| line.contains(Whisper::USAGE)));
+    }
+
+    #[test]
+    fn shows_usage_of_command() {
+        let lines = Help::new(Some("/whisper".into()))
+            .format_lines(&[])
+            .unwrap();
+
+        assert_eq!(lines[0], "Usage: /whisper <username> <message...>");
+    }
+
+    #[test]
+    fn shows_usage_of_command_by_alias() {
+        let lines = Help::new(Some("msg".into())).format_lines(&[]).unwrap();
+
+        assert_eq!(lines[0], "Usage: /whisper <username> <message...>");
+        assert_eq!(lines[2], "Aliases: /msg, /w");
+    }
+
+    #[test]
+    fn shows_usage_of_plugin_command() {
+        let plugin_commands = [CommandInfo {
+            name: "roll",
+            usage: "/roll [dice]",
+            about: "Roll some dice.",
+            aliases: &[],
+            permission: Permission::User,
+        }];
+
+        let lines = Help::new(None).format_lines(&plugin_commands).unwrap();
+
+        assert_eq!(lines.last().unwrap(), "  /roll [dice] - Roll some dice.");
+    }
+
+    #[test]
+    fn returns_error_if_command_is_unknown() {
+        let name: String = Word().fake();
+
+        let lines = Help::new(Some(name.clone())).format_lines(&[]);
+
+        assert_eq!(lines, Err(CommandError::UnknownCommand(name)));
+    }
+}
@@ -1,8 +1,14 @@
-use crate::{domain::Connection, errors::CommandError, frame::Frame, traits::CommandApply};
+use crate::{
+    domain::{check_length, Connection},
+    errors::CommandError,
+    traits::{ChatCommand, CommandApply},
+};
 use async_trait::async_trait;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(name = "me", about = "Describe an action you're taking.")]
 pub struct Me {
+    #[arg(rest)]
     message: String,
 }
 
@@ -15,33 +21,23 @@ impl Me {
 #[async_trait]
 impl CommandApply for Me {
     async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
-        let mut state = conn.state.lock().await;
-        let message = format!("{} is {}", conn.peer.username.to_string(), self.message);
-        let frame = Frame::ServerMessage(message);
-
-        state.broadcast(conn.peer.addr, frame).await;
-
-        Ok(())
-    }
-}
+        let message = conn.filter(&self.message).await?;
 
-impl TryFrom<Vec<&str>> for Me {
-    type Error = CommandError;
+        let message = format!("{} is {}", conn.peer.username, message);
+        check_length(&message)?;
 
-    fn try_from(args: Vec<&str>) -> Result<Self, Self::Error> {
-        if args.len() == 0 {
-            return Err(CommandError::MissingArgument("message".into()));
-        }
+        let mut state = conn.state.lock().await;
 
-        let message = args.join(" ");
+        state.broadcast_action(conn.peer.addr, message).await;
 
-        Ok(Self { message })
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::args::Args;
     use fake::{
         faker::lorem::en::{Sentence, Word},
         Fake,
@@ -49,29 +45,39 @@ mod test {
 
     #[test]
     fn parses_me_command() {
-        let word: &str = &Word().fake::<String>();
-        let args = vec![word];
+        let word: String = Word().fake();
+        let args = Args::from(word.as_str());
 
         let command = Me::try_from(args);
-        let expected = Me::new(word.into());
+        let expected = Me::new(word);
 
         assert_eq!(command, Ok(expected));
     }
 
     #[test]
     fn parses_whitespace_delimited_sentence() {
-        let sentence: &str = &Sentence(0..2).fake::<String>();
-        let args = vec![sentence];
+        let sentence: String = Sentence(0..2).fake();
+        let args = Args::from(sentence.as_str());
+
+        let command = Me::try_from(args);
+        let expected = Me::new(sentence);
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn keeps_leading_double_dash_in_message() {
+        let args = Args::from("-- is confused");
 
         let command = Me::try_from(args);
-        let expected = Me::new(sentence.into());
+        let expected = Me::new("-- is confused".into());
 
         assert_eq!(command, Ok(expected));
     }
 
     #[test]
     fn returns_error_if_missing_message_argument() {
-        let args = vec![];
+        let args = Args::from("");
 
         let command = Me::try_from(args);
         let expected = CommandError::MissingArgument("message".into());
@@ -0,0 +1,81 @@
+use crate::{
+    domain::Connection,
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+
+// Maximum number of recent mentions to display.
+const MAX_MENTIONS: usize = 10;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(name = "mentions", about = "List your most recent mentions.")]
+pub struct Mentions {}
+
+#[async_trait]
+impl CommandApply for Mentions {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let state = conn.state.lock().await;
+
+        let mut mentions: Vec<String> = state
+            .history
+            .iter_recent()
+            .filter(|entry| entry.mentions(&conn.peer.username))
+            .take(MAX_MENTIONS)
+            .map(|entry| entry.format())
+            .collect();
+
+        // Release the lock before writing to the sender's stream.
+        drop(state);
+
+        // Display the mentions in the order they were sent.
+        mentions.reverse();
+
+        let header = match mentions.len() {
+            0 => String::from("You haven't been mentioned recently."),
+            1 => String::from("Your most recent mention:"),
+            count => format!("Your {} most recent mentions:", count),
+        };
+
+        let frames = std::iter::once(header)
+            .chain(mentions)
+            .map(Frame::ServerMessage);
+
+        for frame in frames {
+            conn.messages
+                .send(frame)
+                .await
+                .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn parses_mentions_command() {
+        let args = Args::from("");
+
+        let command = Mentions::try_from(args);
+
+        assert_eq!(command, Ok(Mentions {}));
+    }
+
+    #[test]
+    fn returns_error_if_too_many_arguments() {
+        let word: String = Word().fake();
+        let args = Args::from(word.as_str());
+
+        let command = Mentions::try_from(args);
+
+        assert_eq!(command, Err(CommandError::TooManyArguments));
+    }
+}
@@ -1,49 +1,147 @@
+mod alias;
+mod aliases;
+mod announce;
 mod help;
 mod me;
+mod mentions;
+mod passwd;
+mod react;
+mod register;
+mod reply;
+mod seen;
+mod thread;
+mod unalias;
 mod whisper;
 
+pub use alias::*;
+pub use aliases::*;
+pub use announce::*;
 pub use help::*;
 pub use me::*;
+pub use mentions::*;
+pub use passwd::*;
+pub use react::*;
+pub use register::*;
+pub use reply::*;
+pub use seen::*;
+pub use thread::*;
+pub use unalias::*;
 pub use whisper::*;
 
-use crate::{domain::Connection, errors::CommandError, traits::CommandApply};
+use crate::{
+    args::Args,
+    domain::{Connection, Permission},
+    errors::CommandError,
+    traits::{ChatCommand, CommandApply},
+};
+
+/// The details of a registered command, as displayed by `/help`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandInfo {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub about: &'static str,
+    pub aliases: &'static [&'static str],
+    pub permission: Permission,
+}
 
-#[derive(Debug, PartialEq)]
-pub enum Command {
-    Help(Help),
-    Whisper(Whisper),
-    Me(Me),
+impl CommandInfo {
+    /// Finds a registered command by its name, or any of its aliases.
+    pub fn find(name: &str) -> Option<&'static CommandInfo> {
+        COMMANDS
+            .iter()
+            .find(|command| command.name == name || command.aliases.contains(&name))
+    }
 }
 
-impl Command {
-    pub async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
-        let fut = match self {
-            Command::Help(cmd) => cmd.apply(conn),
-            Command::Whisper(cmd) => cmd.apply(conn),
-            Command::Me(cmd) => cmd.apply(conn),
-        };
+/// Splits a command into its name, which is everything up to the first
+/// whitespace, and its arguments.
+pub fn split_name(value: &str) -> (&str, &str) {
+    let value = value.trim_start();
+
+    value.split_once(char::is_whitespace).unwrap_or((value, ""))
+}
 
-        fut.await?;
+/// Registers each of the provided `ChatCommand` types, generating the
+/// `Command` enum, its parsing and dispatch, and the list of `COMMANDS`.
+macro_rules! commands {
+    ($($command:ident),* $(,)?) => {
+        #[derive(Debug, PartialEq)]
+        pub enum Command {
+            $($command($command),)*
+        }
+
+        /// Every registered command, in the order they're listed by `/help`.
+        pub const COMMANDS: &[CommandInfo] = &[
+            $(CommandInfo {
+                name: <$command as ChatCommand>::NAME,
+                usage: <$command as ChatCommand>::USAGE,
+                about: <$command as ChatCommand>::ABOUT,
+                aliases: <$command as ChatCommand>::ALIASES,
+                permission: <$command as ChatCommand>::PERMISSION,
+            },)*
+        ];
+
+        impl Command {
+            /// Runs the command, if the sender has the permission level it requires.
+            pub async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+                let required = self.permission();
+                let permission = conn.state.lock().await.permissions.level(&conn.peer.username);
+
+                if permission < required {
+                    return Err(CommandError::PermissionDenied(required));
+                }
+
+                match self {
+                    $(Command::$command(cmd) => cmd.apply(conn).await,)*
+                }
+            }
+
+            /// The permission level required to run the command.
+            pub fn permission(&self) -> Permission {
+                match self {
+                    $(Command::$command(_) => <$command as ChatCommand>::PERMISSION,)*
+                }
+            }
+
+            fn parse(name: &str, args: Args) -> Result<Self, CommandError> {
+                match name {
+                    $(name if name == <$command as ChatCommand>::NAME
+                        || <$command as ChatCommand>::ALIASES.contains(&name) => {
+                        Ok(Self::$command($command::try_from(args)?))
+                    })*
+                    "" => Err(CommandError::MissingName),
+                    name => Err(CommandError::UnknownCommand(name.into())),
+                }
+            }
+        }
+    };
+}
 
-        Ok(())
-    }
+commands! {
+    Help,
+    Alias,
+    Aliases,
+    Announce,
+    Me,
+    Mentions,
+    Passwd,
+    React,
+    Register,
+    Reply,
+    Seen,
+    Thread,
+    Unalias,
+    Whisper,
 }
 
 impl TryFrom<&str> for Command {
     type Error = CommandError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut parts = value.split(" ");
-        let name = parts.next().ok_or_else(|| CommandError::MissingName)?;
-        let args: Vec<&str> = parts.collect();
-
-        Ok(match name {
-            "help" => Self::Help(Help {}),
-            "whisper" => Self::Whisper(Whisper::try_from(args)?),
-            "me" => Self::Me(Me::try_from(args)?),
-            cmd if cmd.is_empty() => Err(CommandError::MissingName)?,
-            cmd => Err(CommandError::UnknownCommand(cmd.into()))?,
-        })
+        let (name, args) = split_name(value);
+
+        Self::parse(name, Args::from(args))
     }
 }
 
@@ -66,6 +164,44 @@ mod test {
         assert_eq!(command, Ok(expected));
     }
 
+    #[test]
+    fn parses_command_with_repeated_whitespace() {
+        let username: String = Username().fake();
+
+        let value = &format!("  whisper   {}   hello", &username);
+        let command = Command::try_from(value.as_str());
+        let expected = Command::Whisper(Whisper::new(username, "hello".into()));
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn returns_error_if_quote_is_unterminated() {
+        let value = String::from("whisper \"some user hello");
+        let command = Command::try_from(value.as_str());
+
+        assert_eq!(command, Err(CommandError::UnterminatedQuote));
+    }
+
+    #[test]
+    fn parses_free_text_with_apostrophes() {
+        assert_eq!(
+            Command::try_from("me it's raining"),
+            Ok(Command::Me(Me::new("it's raining".into())))
+        );
+        assert_eq!(
+            Command::try_from("whisper bob don't do that"),
+            Ok(Command::Whisper(Whisper::new(
+                "bob".into(),
+                "don't do that".into()
+            )))
+        );
+        assert_eq!(
+            Command::try_from("reply 3 can't"),
+            Ok(Command::Reply(Reply::new(3, "can't".into())))
+        );
+    }
+
     #[test]
     fn returns_error_if_command_name_is_empty() {
         let value = String::new();
@@ -0,0 +1,87 @@
+use crate::{
+    domain::{hash_password, Accounts, Connection},
+    errors::{AccountError, CommandError},
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "passwd",
+    about = "Change the password of your registered nickname."
+)]
+pub struct Passwd {
+    current: String,
+    new: String,
+}
+
+impl Passwd {
+    pub fn new(current: String, new: String) -> Self {
+        Self { current, new }
+    }
+}
+
+#[async_trait]
+impl CommandApply for Passwd {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let nickname = conn.peer.username.nickname().to_owned();
+
+        let account = conn
+            .state
+            .lock()
+            .await
+            .accounts
+            .find(&nickname)
+            .filter(|account| account.nickname == nickname)
+            .ok_or_else(|| AccountError::NotRegistered(nickname.clone()))?;
+
+        // Passwords are verified and hashed without holding the lock, as
+        // hashing is deliberately slow.
+        account.verify(&nickname, &self.current).await?;
+
+        let hash = hash_password(&self.new).await?;
+
+        Accounts::update(&conn.state, |accounts| accounts.set_hash(&nickname, hash)).await?;
+
+        let frame = Frame::ServerMessage(String::from(
+            "Password changed. Connect with the new password from now on.",
+        ));
+
+        conn.messages
+            .send(frame)
+            .await
+            .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+
+    #[test]
+    fn parses_passwd_command() {
+        let args = Args::from("old \"new password\"");
+
+        let command = Passwd::try_from(args);
+
+        assert_eq!(
+            command,
+            Ok(Passwd::new("old".into(), "new password".into()))
+        );
+    }
+
+    #[test]
+    fn returns_error_if_missing_new_arg() {
+        let args = Args::from("old");
+
+        let command = Passwd::try_from(args);
+        let expected = CommandError::MissingArgument("new".into());
+
+        assert_eq!(command, Err(expected));
+    }
+}
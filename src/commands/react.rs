@@ -0,0 +1,115 @@
+use crate::{
+    domain::{Connection, Emoji, MessageId},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "react",
+    about = "React to a message with an emoji or :shortcode:."
+)]
+pub struct React {
+    id: MessageId,
+    emoji: Emoji,
+}
+
+impl React {
+    pub fn new(id: MessageId, emoji: Emoji) -> Self {
+        Self { id, emoji }
+    }
+}
+
+#[async_trait]
+impl CommandApply for React {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let mut state = conn.state.lock().await;
+
+        let entry = state.history.get_mut(self.id).ok_or_else(|| {
+            CommandError::ExecutionError(format!("No message with ID {}", self.id))
+        })?;
+
+        if !entry
+            .reactions
+            .add(self.emoji.clone(), conn.peer.username.clone())
+        {
+            return Err(CommandError::ExecutionError(format!(
+                "You have already reacted to [{}] with {}",
+                self.id, self.emoji
+            )));
+        }
+
+        let message = format!(
+            "{} reacted to [{}] with {} ({})",
+            conn.peer.username,
+            self.id,
+            self.emoji,
+            entry.reactions.format()
+        );
+
+        // The sender should also see the updated reactions.
+        state.broadcast_all(Frame::Reaction(message)).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn parses_react_command_with_emoji() {
+        let args = Args::from("42 🎉");
+
+        let command = React::try_from(args);
+        let expected = React::new(42, Emoji::resolve("🎉").unwrap());
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn parses_react_command_with_shortcode() {
+        let args = Args::from("42 :thumbsup:");
+
+        let command = React::try_from(args);
+        let expected = React::new(42, Emoji::resolve("👍").unwrap());
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn returns_error_if_emoji_is_unknown() {
+        let word: String = Word().fake();
+        let args = Args::from(format!("42 {word}").as_str());
+
+        let command = React::try_from(args);
+        let expected =
+            CommandError::InvalidArgument("emoji".into(), format!("{word} is not a known emoji"));
+
+        assert_eq!(command, Err(expected));
+    }
+
+    #[test]
+    fn returns_error_if_missing_emoji_arg() {
+        let args = Args::from("42");
+
+        let command = React::try_from(args);
+        let expected = CommandError::MissingArgument("emoji".into());
+
+        assert_eq!(command, Err(expected));
+    }
+
+    #[test]
+    fn returns_error_if_too_many_arguments() {
+        let args = Args::from("42 🎉 🔥");
+
+        let command = React::try_from(args);
+
+        assert_eq!(command, Err(CommandError::TooManyArguments));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    domain::{hash_password, Accounts, Connection},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "register",
+    about = "Register your nickname, so that only you can connect with it."
+)]
+pub struct Register {
+    password: String,
+}
+
+impl Register {
+    pub fn new(password: String) -> Self {
+        Self { password }
+    }
+}
+
+#[async_trait]
+impl CommandApply for Register {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let nickname = conn.peer.username.nickname().to_owned();
+
+        let hash = hash_password(&self.password).await?;
+
+        Accounts::update(&conn.state, |accounts| accounts.register(&nickname, hash)).await?;
+
+        let frame = Frame::ServerMessage(format!(
+            "Registered {}. Connect with this password from now on.",
+            nickname
+        ));
+
+        conn.messages
+            .send(frame)
+            .await
+            .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::{quote, Args};
+    use fake::{faker::internet::en::Password, Fake};
+
+    #[test]
+    fn parses_register_command() {
+        let password: String = Password(8..16).fake();
+        let args = Args::from(quote(&password).as_str());
+
+        let command = Register::try_from(args);
+
+        assert_eq!(command, Ok(Register::new(password)));
+    }
+
+    #[test]
+    fn returns_error_if_missing_password_arg() {
+        let args = Args::from("");
+
+        let command = Register::try_from(args);
+        let expected = CommandError::MissingArgument("password".into());
+
+        assert_eq!(command, Err(expected));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    domain::{check_length, Connection, MessageId, PluginEvent},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(name = "reply", about = "Reply to a message by its ID.")]
+pub struct Reply {
+    #[arg(name = "id")]
+    parent: MessageId,
+    #[arg(rest)]
+    message: String,
+}
+
+impl Reply {
+    pub fn new(parent: MessageId, message: String) -> Self {
+        Self { parent, message }
+    }
+}
+
+#[async_trait]
+impl CommandApply for Reply {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let message = conn.filter(&self.message).await?;
+
+        let mut state = conn.state.lock().await;
+
+        // Quote the parent message, if it is still in the history.
+        let quote = state
+            .history
+            .get(self.parent)
+            .map(|parent| parent.quote())
+            .ok_or_else(|| {
+                CommandError::ExecutionError(format!("No message with ID {}", self.parent))
+            })?;
+
+        let formatted = state.history.format_next(&conn.peer.username, &message);
+        check_length(&format!("{}\n{}", quote, formatted))?;
+
+        let entry = state
+            .history
+            .push(conn.peer.username.clone(), message, Some(self.parent))
+            .clone();
+        let mentions = state.mentioned_peers(&entry.mentions);
+        let reply = format!("{}\n{}", quote, entry.format());
+
+        // Mentioned users receive the reply as a `Frame::ReplyMention`, beneath
+        // the same quote, so that it's highlighted.
+        state
+            .broadcast_message(
+                conn.peer.addr,
+                Frame::Reply,
+                Frame::ReplyMention,
+                reply.clone(),
+                &mentions,
+            )
+            .await;
+        state
+            .plugins
+            .notify(&conn.state, PluginEvent::Message(entry));
+        drop(state);
+
+        conn.echo(Frame::Reply(reply)).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+    use fake::{
+        faker::lorem::en::{Sentence, Word},
+        Fake,
+    };
+
+    #[test]
+    fn parses_reply_command() {
+        let message: String = Word().fake();
+        let args = Args::from(format!("42 {message}").as_str());
+
+        let command = Reply::try_from(args);
+        let expected = Reply::new(42, message);
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn parses_space_delimited_message_arg() {
+        let message: String = Sentence(0..2).fake();
+        let args = Args::from(format!("42 {message}").as_str());
+
+        let command = Reply::try_from(args);
+        let expected = Reply::new(42, message);
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn returns_error_if_id_is_invalid() {
+        let id: String = Word().fake();
+        let message: String = Word().fake();
+        let args = Args::from(format!("{id} {message}").as_str());
+
+        let command = Reply::try_from(args);
+        let expected =
+            CommandError::InvalidArgument("id".into(), format!("{id} is not a valid number"));
+
+        assert_eq!(command, Err(expected));
+    }
+
+    #[test]
+    fn returns_error_if_missing_message_arg() {
+        let args = Args::from("42");
+
+        let command = Reply::try_from(args);
+        let expected = CommandError::MissingArgument("message".into());
+
+        assert_eq!(command, Err(expected));
+    }
+}
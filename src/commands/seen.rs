@@ -0,0 +1,123 @@
+use crate::{
+    domain::{Connection, Mention},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+    utils::format_elapsed,
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+use std::cmp::Reverse;
+
+// Maximum number of matching users to display.
+const MAX_USERS: usize = 5;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(name = "seen", about = "Show when a user was last active.")]
+pub struct Seen {
+    username: Mention,
+}
+
+impl Seen {
+    pub fn new(username: Mention) -> Self {
+        Self { username }
+    }
+}
+
+#[async_trait]
+impl CommandApply for Seen {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let state = conn.state.lock().await;
+
+        // A nickname may be shared by several users with different tripcodes,
+        // so display the most recently active of them.
+        let mut matches: Vec<_> = state
+            .last_seen
+            .iter()
+            .filter(|(username, _)| self.username.matches(username))
+            .collect();
+
+        matches.sort_by_key(|(_, last_seen)| Reverse(last_seen.last_active));
+
+        if matches.is_empty() {
+            return Err(CommandError::ExecutionError(format!(
+                "No record of user {}",
+                self.username
+            )));
+        }
+
+        let frames: Vec<Frame> = matches
+            .into_iter()
+            .take(MAX_USERS)
+            .map(|(username, last_seen)| {
+                let elapsed = format_elapsed(last_seen.last_active.elapsed().unwrap_or_default());
+
+                let activity = match state.peers.contains_key(username) {
+                    true => format!("{} is online (last active {})", username, elapsed),
+                    false => format!("{} was last active {}", username, elapsed),
+                };
+
+                // Quote the last read message, if it's still in the history.
+                let last_read = match last_seen.last_read {
+                    Some(id) => match state.history.get(id) {
+                        Some(entry) => format!("last read {}", entry.quote()),
+                        None => format!("last read [{}]", id),
+                    },
+                    None => String::from("hasn't read any messages"),
+                };
+
+                Frame::ServerMessage(format!("{}, and {}", activity, last_read))
+            })
+            .collect();
+
+        // Release the lock before writing to the sender's stream.
+        drop(state);
+
+        for frame in frames {
+            conn.messages
+                .send(frame)
+                .await
+                .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+    use fake::{faker::internet::en::Username, Fake};
+
+    #[test]
+    fn parses_seen_command() {
+        let username: String = Username().fake();
+        let args = Args::from(format!("@{username}").as_str());
+
+        let command = Seen::try_from(args);
+        let expected = Seen::new(Mention::new(username, None));
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn returns_error_if_missing_username_arg() {
+        let args = Args::from("");
+
+        let command = Seen::try_from(args);
+        let expected = CommandError::MissingArgument("username".into());
+
+        assert_eq!(command, Err(expected));
+    }
+
+    #[test]
+    fn returns_error_if_too_many_arguments() {
+        let username: String = Username().fake();
+        let args = Args::from(format!("{username} {username}").as_str());
+
+        let command = Seen::try_from(args);
+
+        assert_eq!(command, Err(CommandError::TooManyArguments));
+    }
+}
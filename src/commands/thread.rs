@@ -0,0 +1,93 @@
+use crate::{
+    domain::{Connection, MessageId},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(name = "thread", about = "Show the thread that a message belongs to.")]
+pub struct Thread {
+    id: MessageId,
+}
+
+impl Thread {
+    pub fn new(id: MessageId) -> Self {
+        Self { id }
+    }
+}
+
+#[async_trait]
+impl CommandApply for Thread {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let state = conn.state.lock().await;
+        let thread = state.history.thread(self.id);
+
+        let Some(root) = thread.first() else {
+            return Err(CommandError::ExecutionError(format!(
+                "No message with ID {}",
+                self.id
+            )));
+        };
+
+        let mut frames = vec![Frame::ServerMessage(format!(
+            "Thread [{}] ({} messages):",
+            root.id,
+            thread.len()
+        ))];
+
+        // Indent replies beneath the message at the root of the thread.
+        frames.extend(thread.iter().map(|entry| match entry.parent {
+            Some(_) => Frame::ServerMessage(format!("  {}", entry.format())),
+            None => Frame::ServerMessage(entry.format()),
+        }));
+
+        // Release the lock before writing to the sender's stream.
+        drop(state);
+
+        for frame in frames {
+            conn.messages
+                .send(frame)
+                .await
+                .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+
+    #[test]
+    fn parses_thread_command() {
+        let args = Args::from("42");
+
+        let command = Thread::try_from(args);
+
+        assert_eq!(command, Ok(Thread::new(42)));
+    }
+
+    #[test]
+    fn returns_error_if_missing_id_arg() {
+        let args = Args::from("");
+
+        let command = Thread::try_from(args);
+        let expected = CommandError::MissingArgument("id".into());
+
+        assert_eq!(command, Err(expected));
+    }
+
+    #[test]
+    fn returns_error_if_too_many_arguments() {
+        let args = Args::from("42 43");
+
+        let command = Thread::try_from(args);
+
+        assert_eq!(command, Err(CommandError::TooManyArguments));
+    }
+}
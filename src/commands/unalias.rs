@@ -0,0 +1,74 @@
+use crate::{
+    domain::{AliasStore, Connection},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
+};
+use async_trait::async_trait;
+use futures::SinkExt;
+
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(name = "unalias", about = "Remove an alias defined with /alias.")]
+pub struct Unalias {
+    name: String,
+}
+
+impl Unalias {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[async_trait]
+impl CommandApply for Unalias {
+    async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
+        let name = self.name.strip_prefix('/').unwrap_or(&self.name);
+
+        AliasStore::update(&conn.state, &conn.peer.username, |aliases| {
+            match aliases.remove(name) {
+                true => Ok(()),
+                false => Err(CommandError::ExecutionError(format!(
+                    "No alias named /{}",
+                    name
+                ))),
+            }
+        })
+        .await?;
+
+        let frame = Frame::ServerMessage(format!("Removed alias /{}", name));
+
+        conn.messages
+            .send(frame)
+            .await
+            .map_err(|e| CommandError::ExecutionError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::args::Args;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn parses_unalias_command() {
+        let name: String = Word().fake();
+        let args = Args::from(name.as_str());
+
+        let command = Unalias::try_from(args);
+
+        assert_eq!(command, Ok(Unalias::new(name)));
+    }
+
+    #[test]
+    fn returns_error_if_missing_name_arg() {
+        let args = Args::from("");
+
+        let command = Unalias::try_from(args);
+        let expected = CommandError::MissingArgument("name".into());
+
+        assert_eq!(command, Err(expected));
+    }
+}
@@ -1,13 +1,21 @@
 use crate::{
-    domain::Connection, errors::CommandError, frame::Frame, traits::CommandApply,
-    utils::try_pop_arg,
+    domain::{check_length, Connection},
+    errors::CommandError,
+    frame::Frame,
+    traits::{ChatCommand, CommandApply},
 };
 use async_trait::async_trait;
 use futures::SinkExt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "whisper",
+    aliases = ["msg", "w"],
+    about = "Send a private message to a user."
+)]
 pub struct Whisper {
     username: String,
+    #[arg(rest)]
     message: String,
 }
 
@@ -16,21 +24,22 @@ impl Whisper {
         Self { username, message }
     }
 
-    fn format_message(&self, prefix: &str, username: &str) -> String {
-        format!("{} {}: {}", prefix, username, self.message)
+    fn format_message(prefix: &str, username: &str, message: &str) -> String {
+        format!("{} {}: {}", prefix, username, message)
     }
 }
 
 #[async_trait]
 impl CommandApply for Whisper {
     async fn apply(&self, conn: &mut Connection) -> Result<(), CommandError> {
-        let state = conn.state.lock().await;
-
         // If the sender tries to message their own username, do nothing.
         if self.username == conn.peer.username.to_string() {
             return Ok(());
         }
 
+        let message = conn.filter(&self.message).await?;
+        let state = conn.state.lock().await;
+
         // Locate the connected peer to address the private message to,
         // if possible. Otherwise return an error to the sender.
         let target_peer = state
@@ -41,9 +50,15 @@ impl CommandApply for Whisper {
                 CommandError::ExecutionError(format!("No user with username {}", self.username))
             })?;
 
-        let to_message = Frame::PrivateMessage(self.format_message("To", &self.username));
-        let from_message =
-            Frame::PrivateMessage(self.format_message("From", &conn.peer.username.to_string()));
+        let sender = conn.peer.username.to_string();
+        let to_message = Self::format_message("To", &self.username, &message);
+        let from_message = Self::format_message("From", &sender, &message);
+
+        check_length(&to_message)?;
+        check_length(&from_message)?;
+
+        let to_message = Frame::PrivateMessage(to_message);
+        let from_message = Frame::PrivateMessage(from_message);
 
         // Send the message directly to the connected peer
         target_peer.1.tx.send(from_message).await.map_err(|_| {
@@ -58,25 +73,10 @@ impl CommandApply for Whisper {
     }
 }
 
-impl TryFrom<Vec<&str>> for Whisper {
-    type Error = CommandError;
-
-    fn try_from(args: Vec<&str>) -> Result<Self, Self::Error> {
-        let mut args = args.iter();
-
-        let username = try_pop_arg(&mut args, "username")?;
-        let message = try_pop_arg(&mut args, "message")?;
-
-        // Join the rest of the message with space delimiter
-        let message = args.fold(message, |a, b| format!("{a} {b}"));
-
-        Ok(Self { username, message })
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::args::Args;
     use fake::{
         faker::{
             internet::en::Username,
@@ -87,31 +87,42 @@ mod test {
 
     #[test]
     fn parses_whisper_command() {
-        let username: &str = &Username().fake::<String>();
-        let message: &str = &Word().fake::<String>();
-        let args = vec![username, message];
+        let username: String = Username().fake();
+        let message: String = Word().fake();
+        let args = Args::from(format!("{username} {message}").as_str());
 
         let command = Whisper::try_from(args);
-        let expected = Whisper::new(username.into(), message.into());
+        let expected = Whisper::new(username, message);
 
         assert_eq!(command, Ok(expected));
     }
 
     #[test]
     fn parses_space_delimited_message_arg() {
-        let username: &str = &Username().fake::<String>();
-        let message: &str = &Sentence(0..2).fake::<String>();
-        let args = vec![username, message];
+        let username: String = Username().fake();
+        let message: String = Sentence(0..2).fake();
+        let args = Args::from(format!("{username} {message}").as_str());
+
+        let command = Whisper::try_from(args);
+        let expected = Whisper::new(username, message);
+
+        assert_eq!(command, Ok(expected));
+    }
+
+    #[test]
+    fn parses_quoted_username_arg() {
+        let message: String = Word().fake();
+        let args = Args::from(format!("\"some user\" {message}").as_str());
 
         let command = Whisper::try_from(args);
-        let expected = Whisper::new(username.into(), message.into());
+        let expected = Whisper::new("some user".into(), message);
 
         assert_eq!(command, Ok(expected));
     }
 
     #[test]
     fn returns_error_if_missing_username_arg() {
-        let args = vec![];
+        let args = Args::from("");
 
         let command = Whisper::try_from(args);
         let expected = CommandError::MissingArgument("username".into());
@@ -121,8 +132,8 @@ mod test {
 
     #[test]
     fn returns_error_if_missing_message_arg() {
-        let username: &str = &Username().fake::<String>();
-        let args = vec![username];
+        let username: String = Username().fake();
+        let args = Args::from(username.as_str());
 
         let command = Whisper::try_from(args);
         let expected = CommandError::MissingArgument("message".into());
@@ -0,0 +1,215 @@
+use crate::{domain::Permissions, errors::ConfigError, filters::FilterPipeline};
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// The server's configuration, loaded from a TOML file. Every setting is
+/// optional, for example:
+///
+/// ```toml
+/// [permissions]
+/// operators = ["some_user!uQ8unuo3Mk"]
+/// moderators = []
+/// voice = []
+///
+/// [scripts]
+/// directory = "scripts"
+/// timeout_ms = 250
+///
+/// [accounts]
+/// path = "accounts.toml"
+///
+/// [aliases]
+/// path = "aliases.toml"
+///
+/// [sessions]
+/// grace_period_secs = 30
+///
+/// [filters]
+/// words = ["heck"]
+/// block_links = true
+/// rules = [{ pattern = "(?i)buy now", action = "mute", mute_for = "10m" }]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub permissions: Permissions,
+    pub scripts: ScriptConfig,
+    pub filters: FilterPipeline,
+    pub accounts: AccountConfig,
+    pub aliases: AliasConfig,
+    pub sessions: SessionConfig,
+}
+
+/// Where to load scripts from, and how long each call to a script may run for.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptConfig {
+    pub directory: Option<PathBuf>,
+    pub timeout_ms: u64,
+}
+
+impl Default for ScriptConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            timeout_ms: 250,
+        }
+    }
+}
+
+/// Where registered accounts are saved. Without a path, accounts are only kept
+/// until the server stops.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccountConfig {
+    pub path: Option<PathBuf>,
+}
+
+/// Where the aliases users define are saved. Without a path, aliases are only
+/// kept until the server stops.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AliasConfig {
+    pub path: Option<PathBuf>,
+}
+
+/// How long a session can be resumed for after its connection drops. A grace
+/// period of zero disables resuming sessions.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SessionConfig {
+    pub grace_period_secs: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            grace_period_secs: 30,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+}
+
+impl ScriptConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+impl ServerConfig {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        fs::read_to_string(path)
+            .map_err(|e| ConfigError::ReadFailure(path.display().to_string(), e))?
+            .parse()
+    }
+}
+
+impl FromStr for ServerConfig {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let config: Self = toml::from_str(value)?;
+
+        // A nickname alone can be claimed by anyone, so permissions must
+        // always be granted to a specific tripcode.
+        let invalid = config.permissions.usernames().find(|username| {
+            !username
+                .split_once('!')
+                .is_some_and(|(nickname, tripcode)| !nickname.is_empty() && !tripcode.is_empty())
+        });
+
+        if let Some(username) = invalid {
+            return Err(ConfigError::InvalidUsername(username.clone()));
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::filters::Verdict;
+
+    #[test]
+    fn parses_empty_config() {
+        let config = ServerConfig::from_str("").unwrap();
+
+        assert_eq!(config, ServerConfig::default());
+    }
+
+    #[test]
+    fn parses_permissions() {
+        let config = ServerConfig::from_str(
+            r#"
+            [permissions]
+            operators = ["admin!uQ8unuo3Mk"]
+            voice = ["guest!0000000000"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.permissions.operators, ["admin!uQ8unuo3Mk"]);
+        assert_eq!(config.permissions.voice, ["guest!0000000000"]);
+        assert!(config.permissions.moderators.is_empty());
+    }
+
+    #[test]
+    fn parses_script_directory_with_default_timeout() {
+        let config = ServerConfig::from_str("scripts.directory = \"scripts\"").unwrap();
+
+        assert_eq!(config.scripts.directory, Some(PathBuf::from("scripts")));
+        assert_eq!(config.scripts.timeout(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn parses_filters() {
+        let config = ServerConfig::from_str(
+            r#"
+            [filters]
+            words = ["heck"]
+            rules = [{ pattern = "spam", action = "block", reason = "No spam" }]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.filters.apply("heck, spam"),
+            Verdict::Block("No spam".into())
+        );
+    }
+
+    #[test]
+    fn returns_error_for_invalid_filter_pattern() {
+        let config =
+            ServerConfig::from_str("filters.rules = [{ pattern = \"[\", action = \"mask\" }]");
+
+        assert!(matches!(config, Err(ConfigError::ParseFailure(_))));
+    }
+
+    #[test]
+    fn returns_error_if_username_has_no_tripcode() {
+        let config = ServerConfig::from_str("permissions.moderators = [\"admin\"]");
+
+        assert!(
+            matches!(config, Err(ConfigError::InvalidUsername(username)) if username == "admin")
+        );
+    }
+
+    #[test]
+    fn returns_error_for_unknown_settings() {
+        let config = ServerConfig::from_str("unknown = true");
+
+        assert!(matches!(config, Err(ConfigError::ParseFailure(_))));
+    }
+}
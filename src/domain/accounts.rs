@@ -0,0 +1,321 @@
+use super::{nickname_skeleton, State};
+use crate::{
+    errors::AccountError,
+    utils::{run_blocking, save_private_file},
+};
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use rand::rngs::OsRng;
+use std::{collections::BTreeMap, fs, io, path::PathBuf, sync::Arc};
+use tokio::sync::Mutex;
+
+/// Hashes a password with a random salt, returning the hash in PHC string
+/// format. Hashing is deliberately slow, so it's done on a blocking thread,
+/// and should be done without holding the lock on the shared state.
+pub async fn hash_password(password: &str) -> Result<String, AccountError> {
+    let password = password.to_owned();
+
+    run_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(AccountError::HashFailure)
+    })
+    .await
+}
+
+/// Whether a password matches a hash in PHC string format.
+pub fn verify_password(hash: &str, password: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// A registered nickname, along with the hash of its password.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub nickname: String,
+    hash: String,
+}
+
+impl Account {
+    /// Checks that a user may connect with a nickname matching the account,
+    /// using the provided password. The password is verified on a blocking
+    /// thread, as it's deliberately slow.
+    pub async fn verify(&self, nickname: &str, password: &str) -> Result<(), AccountError> {
+        if self.nickname != nickname {
+            return Err(AccountError::ConfusableNickname(
+                nickname.into(),
+                self.nickname.clone(),
+            ));
+        }
+
+        let hash = self.hash.clone();
+        let password = password.to_owned();
+
+        if !run_blocking(move || verify_password(&hash, &password)).await {
+            return Err(AccountError::IncorrectPassword(nickname.into()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Registered accounts, which reserve a nickname for whoever knows its password.
+///
+/// Accounts are saved to a TOML file that maps each nickname to the hash of
+/// its password, or only kept in memory if no file is configured. The file can
+/// only be read by its owner.
+#[derive(Debug, Default)]
+pub struct Accounts {
+    path: Option<PathBuf>,
+    hashes: BTreeMap<String, String>,
+    // Held while the accounts are being changed and saved, so that only one
+    // change is saved at a time.
+    saving: Arc<Mutex<()>>,
+}
+
+impl Accounts {
+    /// Loads the accounts saved in a file. The file doesn't need to exist yet,
+    /// as it's created when the first account is registered.
+    pub fn load(path: PathBuf) -> Result<Self, AccountError> {
+        let hashes = match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(AccountError::ReadFailure(path.display().to_string(), e)),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            hashes,
+            saving: Arc::default(),
+        })
+    }
+
+    /// Makes a change to the accounts in the shared state, then saves them.
+    /// The file is written without holding the lock on the shared state, and
+    /// the change is undone if the accounts can't be saved.
+    pub async fn update(
+        state: &State,
+        change: impl FnOnce(&mut Accounts) -> Result<(), AccountError>,
+    ) -> Result<(), AccountError> {
+        let saving = state.lock().await.accounts.saving.clone();
+        let _saving = saving.lock().await;
+
+        let mut shared = state.lock().await;
+        let previous = shared.accounts.hashes.clone();
+        change(&mut shared.accounts)?;
+
+        let Some(path) = shared.accounts.path.clone() else {
+            return Ok(());
+        };
+
+        let contents = toml::to_string(&shared.accounts.hashes);
+        drop(shared);
+
+        let result = match contents {
+            Ok(contents) => {
+                let path = path.clone();
+                run_blocking(move || save_private_file(&path, contents)).await
+            }
+            Err(e) => Err(io::Error::other(e)),
+        };
+
+        if let Err(e) = result {
+            state.lock().await.accounts.hashes = previous;
+            return Err(AccountError::WriteFailure(path.display().to_string(), e));
+        }
+
+        Ok(())
+    }
+
+    /// The number of registered accounts.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Finds the account that a nickname belongs to, or looks like.
+    pub fn find(&self, nickname: &str) -> Option<Account> {
+        let skeleton = nickname_skeleton(nickname);
+
+        self.hashes
+            .get_key_value(nickname)
+            .or_else(|| {
+                self.hashes
+                    .iter()
+                    .find(|(registered, _)| nickname_skeleton(registered) == skeleton)
+            })
+            .map(|(nickname, hash)| Account {
+                nickname: nickname.clone(),
+                hash: hash.clone(),
+            })
+    }
+
+    /// Registers a nickname with the hash of its password, unless it or
+    /// a lookalike is already registered. Use with `update` to save it.
+    pub fn register(&mut self, nickname: &str, hash: String) -> Result<(), AccountError> {
+        if let Some(account) = self.find(nickname) {
+            return Err(AccountError::AlreadyRegistered(account.nickname));
+        }
+
+        self.hashes.insert(nickname.into(), hash);
+
+        Ok(())
+    }
+
+    /// Replaces the hash of a registered nickname's password. Use with
+    /// `update` to save it.
+    pub fn set_hash(&mut self, nickname: &str, hash: String) -> Result<(), AccountError> {
+        let previous = self
+            .hashes
+            .get_mut(nickname)
+            .ok_or_else(|| AccountError::NotRegistered(nickname.into()))?;
+
+        *previous = hash;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::Shared;
+    use crate::utils;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    async fn registered(nickname: &str, password: &str) -> Accounts {
+        let mut accounts = Accounts::default();
+        let hash = hash_password(password).await.unwrap();
+
+        accounts.register(nickname, hash).unwrap();
+
+        accounts
+    }
+
+    fn temp_path() -> PathBuf {
+        let path = utils::temp_path("accounts").with_extension("toml");
+        let _ = fs::remove_file(&path);
+
+        path
+    }
+
+    fn state_with(accounts: Accounts) -> State {
+        Arc::new(Mutex::new(Shared {
+            accounts,
+            ..Shared::new()
+        }))
+    }
+
+    #[tokio::test]
+    async fn hashes_passwords_in_phc_format_with_random_salt() {
+        let first = hash_password("password123").await.unwrap();
+        let second = hash_password("password123").await.unwrap();
+
+        assert!(first.starts_with("$argon2id$"));
+        assert_ne!(first, second);
+        assert!(verify_password(&first, "password123"));
+        assert!(!verify_password(&first, "password124"));
+    }
+
+    #[tokio::test]
+    async fn verifies_registered_nickname_and_password() {
+        let accounts = registered("alice", "password123").await;
+        let account = accounts.find("alice").unwrap();
+
+        assert!(account.verify("alice", "password123").await.is_ok());
+        assert!(matches!(
+            account.verify("alice", "wrong").await,
+            Err(AccountError::IncorrectPassword(nickname)) if nickname == "alice"
+        ));
+    }
+
+    #[tokio::test]
+    async fn finds_account_for_lookalike_nickname() {
+        // The `а` is Cyrillic.
+        let accounts = registered("alice", "password123").await;
+        let account = accounts.find("\u{0430}lice").unwrap();
+
+        assert_eq!(account.nickname, "alice");
+        assert!(matches!(
+            account.verify("\u{0430}lice", "password123").await,
+            Err(AccountError::ConfusableNickname(_, registered)) if registered == "alice"
+        ));
+    }
+
+    #[test]
+    fn does_not_find_unregistered_nickname() {
+        let accounts = Accounts::default();
+        let nickname: String = Word().fake();
+
+        assert_eq!(accounts.find(&nickname), None);
+    }
+
+    #[test]
+    fn returns_error_if_already_registered() {
+        let mut accounts = Accounts::default();
+        accounts.register("alice", String::new()).unwrap();
+
+        let result = accounts.register("Alice", String::new());
+
+        assert!(
+            matches!(result, Err(AccountError::AlreadyRegistered(nickname)) if nickname == "alice")
+        );
+    }
+
+    #[test]
+    fn returns_error_when_changing_unregistered_password() {
+        let mut accounts = Accounts::default();
+
+        let result = accounts.set_hash("alice", String::new());
+
+        assert!(matches!(result, Err(AccountError::NotRegistered(_))));
+    }
+
+    #[tokio::test]
+    async fn saves_and_loads_accounts() {
+        let path = temp_path();
+        let state = state_with(Accounts::load(path.clone()).unwrap());
+        let hash = hash_password("password123").await.unwrap();
+
+        Accounts::update(&state, |accounts| accounts.register("zoë", hash))
+            .await
+            .unwrap();
+
+        let loaded = Accounts::load(path.clone()).unwrap();
+        let metadata = fs::metadata(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let account = loaded.find("zoë").unwrap();
+        assert!(account.verify("zoë", "password123").await.is_ok());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn undoes_change_if_accounts_cannot_be_saved() {
+        // The accounts can't be saved in a directory that doesn't exist.
+        let path = temp_path().join("accounts.toml");
+        let state = state_with(Accounts::load(path).unwrap());
+
+        let result =
+            Accounts::update(&state, |accounts| accounts.register("alice", String::new())).await;
+
+        assert!(matches!(result, Err(AccountError::WriteFailure(..))));
+        assert!(state.lock().await.accounts.is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+use super::{AliasTable, State, Username};
+use crate::{
+    errors::{AliasError, CommandError},
+    utils::{run_blocking, save_private_file},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::PathBuf,
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+/// The aliases that each identity has defined.
+///
+/// Aliases are saved to a TOML file that maps each username to its aliases, or
+/// only kept in memory if no file is configured.
+#[derive(Debug, Default)]
+pub struct AliasStore {
+    path: Option<PathBuf>,
+    tables: HashMap<Username, AliasTable>,
+    // Held while aliases are being changed and saved, so that only one change
+    // is saved at a time.
+    saving: Arc<Mutex<()>>,
+}
+
+impl AliasStore {
+    /// Loads the aliases saved in a file. The file doesn't need to exist yet,
+    /// as it's created when the first alias is defined.
+    pub fn load(path: PathBuf) -> Result<Self, AliasError> {
+        let saved: BTreeMap<String, AliasTable> = match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(AliasError::ReadFailure(path.display().to_string(), e)),
+        };
+
+        let tables = saved
+            .into_iter()
+            .map(|(username, table)| match Username::parse(&username) {
+                Some(username) => Ok((username, table)),
+                None => Err(AliasError::InvalidUsername(username)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            path: Some(path),
+            tables,
+            saving: Arc::default(),
+        })
+    }
+
+    /// Makes a change to a user's aliases in the shared state, then saves them.
+    /// The file is written without holding the lock on the shared state, and
+    /// the change is undone if the aliases can't be saved.
+    pub async fn update<T>(
+        state: &State,
+        username: &Username,
+        change: impl FnOnce(&mut AliasTable) -> Result<T, CommandError>,
+    ) -> Result<T, CommandError> {
+        let saving = state.lock().await.aliases.saving.clone();
+        let _saving = saving.lock().await;
+
+        let mut shared = state.lock().await;
+        let previous = shared.aliases.get(username).cloned();
+
+        let mut aliases = previous.clone().unwrap_or_default();
+        let value = change(&mut aliases)?;
+        shared.set_aliases(username, aliases);
+
+        let Some(path) = shared.aliases.path.clone() else {
+            return Ok(value);
+        };
+
+        let saved: BTreeMap<String, &AliasTable> = shared
+            .aliases
+            .tables
+            .iter()
+            .map(|(username, table)| (username.to_string(), table))
+            .collect();
+        let contents = toml::to_string(&saved);
+        drop(shared);
+
+        let result = match contents {
+            Ok(contents) => {
+                let path = path.clone();
+                run_blocking(move || save_private_file(&path, contents)).await
+            }
+            Err(e) => Err(io::Error::other(e)),
+        };
+
+        if let Err(e) = result {
+            let aliases = &mut state.lock().await.aliases;
+
+            match previous {
+                Some(previous) => aliases.insert(username.clone(), previous),
+                None => aliases.remove(username),
+            }
+
+            return Err(AliasError::WriteFailure(path.display().to_string(), e).into());
+        }
+
+        Ok(value)
+    }
+
+    /// The number of users that have defined aliases.
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// The aliases a user has defined, if any.
+    pub fn get(&self, username: &Username) -> Option<&AliasTable> {
+        self.tables.get(username)
+    }
+
+    /// Iterates over every user that has defined aliases.
+    pub fn usernames(&self) -> impl Iterator<Item = &Username> {
+        self.tables.keys()
+    }
+
+    /// Replaces a user's aliases. Use `Shared::set_aliases` instead, so that
+    /// the number of users with aliases stays bounded.
+    pub(crate) fn insert(&mut self, username: Username, aliases: AliasTable) {
+        self.tables.insert(username, aliases);
+    }
+
+    pub(crate) fn remove(&mut self, username: &Username) {
+        self.tables.remove(username);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::Shared;
+    use crate::utils;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    fn temp_path() -> PathBuf {
+        let path = utils::temp_path("aliases").with_extension("toml");
+        let _ = fs::remove_file(&path);
+
+        path
+    }
+
+    fn state_with(aliases: AliasStore) -> State {
+        Arc::new(Mutex::new(Shared {
+            aliases,
+            ..Shared::new()
+        }))
+    }
+
+    fn username() -> Username {
+        Username::from_credentials(&Word().fake::<String>(), "password123").unwrap()
+    }
+
+    #[tokio::test]
+    async fn saves_and_loads_aliases() {
+        let path = temp_path();
+        let state = state_with(AliasStore::load(path.clone()).unwrap());
+        let username = username();
+
+        AliasStore::update(&state, &username, |aliases| {
+            aliases.insert("w".into(), "whisper $*".into())
+        })
+        .await
+        .unwrap();
+
+        let loaded = AliasStore::load(path.clone()).unwrap();
+        let metadata = fs::metadata(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let aliases = loaded.get(&username).unwrap();
+        assert_eq!(aliases.expand("w bob hi"), Ok("whisper bob hi".into()));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn does_not_keep_change_that_fails() {
+        let state = state_with(AliasStore::default());
+        let username = username();
+
+        let result = AliasStore::update(&state, &username, |aliases| {
+            aliases.insert("w".into(), "whisper $*".into())?;
+            Err::<(), _>(CommandError::MissingName)
+        })
+        .await;
+
+        assert_eq!(result, Err(CommandError::MissingName));
+        assert!(state.lock().await.aliases.is_empty());
+    }
+
+    #[tokio::test]
+    async fn forgets_users_without_aliases() {
+        let state = state_with(AliasStore::default());
+        let username = username();
+
+        AliasStore::update(&state, &username, |aliases| {
+            aliases.insert("w".into(), "whisper $*".into())
+        })
+        .await
+        .unwrap();
+        AliasStore::update(&state, &username, |aliases| Ok(aliases.remove("w")))
+            .await
+            .unwrap();
+
+        assert!(state.lock().await.aliases.is_empty());
+    }
+
+    #[test]
+    fn returns_error_if_saved_username_is_invalid() {
+        let path = temp_path();
+        fs::write(&path, "[alice]\nw = \"whisper $*\"\n").unwrap();
+
+        let result = AliasStore::load(path.clone());
+        fs::remove_file(&path).unwrap();
+
+        assert!(
+            matches!(result, Err(AliasError::InvalidUsername(username)) if username == "alice")
+        );
+    }
+}
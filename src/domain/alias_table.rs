@@ -0,0 +1,270 @@
+use crate::{
+    args::{quote, tokenize_prefix},
+    commands::split_name,
+    errors::CommandError,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Maximum number of aliases that each user may define.
+const MAX_ALIASES: usize = 50;
+
+// Maximum length in bytes of a command once its aliases are expanded, as
+// substituting `$*` more than once doubles the length at each step.
+const MAX_EXPANSION_LENGTH: usize = 2048;
+
+/// The aliases that a user has defined with `/alias`, mapping the name of each
+/// alias to the command it expands to. Tracked per identity, and saved in the
+/// `AliasStore`, so that aliases survive reconnecting.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AliasTable(BTreeMap<String, String>);
+
+impl AliasTable {
+    /// Defines an alias, replacing any existing alias with the same name.
+    pub fn insert(&mut self, name: String, expansion: String) -> Result<(), CommandError> {
+        if self.0.len() >= MAX_ALIASES && !self.0.contains_key(&name) {
+            return Err(CommandError::ExecutionError(format!(
+                "You can't define more than {} aliases",
+                MAX_ALIASES
+            )));
+        }
+
+        self.0.insert(name, expansion);
+
+        Ok(())
+    }
+
+    /// Removes an alias, returning whether it was defined.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.0.remove(name).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over every alias and its expansion, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+
+    /// Expands the alias that a command starts with, if any, repeating until
+    /// the command no longer starts with an alias. Returns an error if an
+    /// alias expands to itself, either directly or through other aliases, or
+    /// to a command longer than `MAX_EXPANSION_LENGTH`.
+    pub fn expand(&self, command: &str) -> Result<String, CommandError> {
+        let mut command = command.to_owned();
+        let mut expanded: Vec<String> = vec![];
+
+        loop {
+            let (name, args) = split_name(&command);
+
+            let Some(expansion) = self.0.get(name) else {
+                return Ok(command);
+            };
+
+            if expanded.iter().any(|alias| alias == name) {
+                return Err(CommandError::RecursiveAlias(name.into()));
+            }
+
+            let next = substitute(name, expansion, args)?;
+            expanded.push(name.to_owned());
+            command = next;
+        }
+    }
+}
+
+/// Substitutes the arguments passed to an alias into its expansion. `$1` to `$9`
+/// are replaced with individual arguments, `$*` with every argument as written,
+/// and `$$` with a literal `$`. If the expansion has no parameters, the
+/// arguments are appended to it instead.
+///
+/// Quotes only need to be terminated in the arguments that are substituted
+/// individually, so `$*` can pass on free text such as `it's raining`.
+fn substitute(name: &str, expansion: &str, args: &str) -> Result<String, CommandError> {
+    let too_long = || CommandError::AliasTooLong(name.into(), MAX_EXPANSION_LENGTH);
+    let (tokens, unterminated) = tokenize_prefix(args);
+    let mut result = String::new();
+    let mut has_parameters = false;
+    let mut chars = expansion.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        // Checked as the expansion is built, so that it stops growing as soon
+        // as it's too long.
+        if result.len() > MAX_EXPANSION_LENGTH {
+            return Err(too_long());
+        }
+
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.peek().copied() {
+            Some('*') => {
+                chars.next();
+                has_parameters = true;
+                result.push_str(args.trim());
+            }
+            Some(digit @ '1'..='9') => {
+                chars.next();
+                has_parameters = true;
+
+                // Digits are always valid indexes here, so this can't fail.
+                let index = digit.to_digit(10).unwrap() as usize - 1;
+                let token = tokens.get(index).ok_or_else(|| match unterminated {
+                    Some(_) => CommandError::UnterminatedQuote,
+                    None => CommandError::MissingArgument(format!("${digit}")),
+                })?;
+
+                // Keep options as they are, but make sure that other arguments
+                // are still read as a single argument once substituted.
+                match token.is_option {
+                    true => result.push_str(&token.value),
+                    false => result.push_str(&quote(&token.value)),
+                }
+            }
+            Some('$') => {
+                chars.next();
+                result.push('$');
+            }
+            _ => result.push('$'),
+        }
+    }
+
+    let args = args.trim();
+
+    if !has_parameters && !args.is_empty() {
+        result = format!("{} {}", result.trim_end(), args);
+    }
+
+    if result.len() > MAX_EXPANSION_LENGTH {
+        return Err(too_long());
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    fn aliases(definitions: &[(&str, &str)]) -> AliasTable {
+        let mut aliases = AliasTable::default();
+
+        for (name, expansion) in definitions {
+            aliases
+                .insert(name.to_string(), expansion.to_string())
+                .unwrap();
+        }
+
+        aliases
+    }
+
+    #[test]
+    fn returns_command_without_alias_unchanged() {
+        let aliases = aliases(&[("m", "whisper bob")]);
+
+        assert_eq!(aliases.expand("me waves"), Ok("me waves".into()));
+    }
+
+    #[test]
+    fn appends_arguments_to_expansion_without_parameters() {
+        let aliases = aliases(&[("b", "whisper bob")]);
+
+        assert_eq!(
+            aliases.expand("b hello there"),
+            Ok("whisper bob hello there".into())
+        );
+    }
+
+    #[test]
+    fn substitutes_positional_and_rest_parameters() {
+        let aliases = aliases(&[("re", "reply $1 re: $*")]);
+
+        assert_eq!(aliases.expand("re 42 ok"), Ok("reply 42 re: 42 ok".into()));
+    }
+
+    #[test]
+    fn substitutes_quoted_arguments_as_single_argument() {
+        let aliases = aliases(&[("w", "whisper $1 $2")]);
+
+        assert_eq!(
+            aliases.expand("w \"some user\" hi"),
+            Ok("whisper \"some user\" hi".into())
+        );
+    }
+
+    #[test]
+    fn passes_on_unterminated_quotes_in_free_text() {
+        let aliases = aliases(&[("sh", "me shouts $*"), ("w", "whisper $1 $2")]);
+
+        assert_eq!(aliases.expand("sh it's"), Ok("me shouts it's".into()));
+        assert_eq!(aliases.expand("b it's"), Ok("b it's".into()));
+        assert_eq!(
+            aliases.expand("w bob it's"),
+            Err(CommandError::UnterminatedQuote)
+        );
+    }
+
+    #[test]
+    fn substitutes_literal_dollar_sign() {
+        let aliases = aliases(&[("price", "me paid $$5")]);
+
+        assert_eq!(aliases.expand("price"), Ok("me paid $5".into()));
+    }
+
+    #[test]
+    fn returns_error_if_positional_argument_is_missing() {
+        let aliases = aliases(&[("w", "whisper $1 $2")]);
+
+        assert_eq!(
+            aliases.expand("w bob"),
+            Err(CommandError::MissingArgument("$2".into()))
+        );
+    }
+
+    #[test]
+    fn expands_nested_aliases() {
+        let aliases = aliases(&[("b", "w bob"), ("w", "whisper")]);
+
+        assert_eq!(aliases.expand("b hi"), Ok("whisper bob hi".into()));
+    }
+
+    #[test]
+    fn returns_error_if_alias_is_recursive() {
+        let name: String = Word().fake();
+        let aliases = aliases(&[(&name, "loop"), ("loop", &name)]);
+
+        assert_eq!(
+            aliases.expand(&name),
+            Err(CommandError::RecursiveAlias(name))
+        );
+    }
+
+    #[test]
+    fn returns_error_if_expansion_is_too_long() {
+        let aliases = aliases(&[("a", "b $* $*"), ("b", "c $* $*"), ("c", "me $* $*")]);
+        let args = "x".repeat(MAX_EXPANSION_LENGTH / 8);
+
+        assert!(aliases.expand(&format!("b {args}")).is_ok());
+        assert_eq!(
+            aliases.expand(&format!("a {args}")),
+            Err(CommandError::AliasTooLong("c".into(), MAX_EXPANSION_LENGTH))
+        );
+    }
+
+    #[test]
+    fn insert_rejects_aliases_beyond_limit() {
+        let mut aliases = AliasTable::default();
+
+        for index in 0..MAX_ALIASES {
+            aliases.insert(index.to_string(), "me".into()).unwrap();
+        }
+
+        assert!(aliases.insert("another".into(), "me".into()).is_err());
+        assert!(aliases.insert("0".into(), "me waves".into()).is_ok());
+    }
+}
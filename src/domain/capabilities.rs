@@ -0,0 +1,213 @@
+use std::fmt::Display;
+
+/// Optional features that a client may opt out of during the handshake,
+/// for example to save bandwidth.
+///
+/// Capabilities are negotiated with a comma-separated list, where each
+/// capability may be prefixed with `no-` to disable it, for example:
+/// `no-typing`. Unknown capabilities are ignored. Acks, actions and echo are
+/// the exception, and are disabled unless listed: acks are only useful to
+/// clients that wait for them, actions are sent in a frame that older clients
+/// can't parse, and older clients would show their own messages twice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capabilities {
+    /// Receive typing indicators from other users.
+    pub typing: bool,
+    /// Receive the list of online users, and updates as users join and leave.
+    pub presence: bool,
+    /// Receive a `Frame::Ack` once each message or command is accepted.
+    pub acks: bool,
+    /// Receive `/me` actions as a `Frame::Action`, rather than as a server
+    /// message.
+    pub actions: bool,
+    /// Receive a copy of the client's own messages and replies, so that it
+    /// learns their IDs, e.g. to reply or react to them.
+    pub echo: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            typing: true,
+            presence: true,
+            acks: false,
+            actions: false,
+            echo: false,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Formats the capabilities as a client requests them during the
+    /// handshake, where those that are disabled are prefixed with `no-`.
+    pub fn to_request(&self) -> String {
+        let request = |name: &str, enabled: bool| match enabled {
+            true => name.to_owned(),
+            false => format!("no-{name}"),
+        };
+
+        [
+            request("typing", self.typing),
+            request("presence", self.presence),
+            request("acks", self.acks),
+            request("actions", self.actions),
+            request("echo", self.echo),
+        ]
+        .join(",")
+    }
+}
+
+impl From<&str> for Capabilities {
+    fn from(value: &str) -> Self {
+        let mut capabilities = Self::default();
+
+        for capability in value.split(',').map(str::trim) {
+            let (name, enabled) = match capability.strip_prefix("no-") {
+                Some(name) => (name, false),
+                None => (capability, true),
+            };
+
+            match name {
+                "typing" => capabilities.typing = enabled,
+                "presence" => capabilities.presence = enabled,
+                "acks" => capabilities.acks = enabled,
+                "actions" => capabilities.actions = enabled,
+                "echo" => capabilities.echo = enabled,
+                _ => {}
+            }
+        }
+
+        capabilities
+    }
+}
+
+impl Display for Capabilities {
+    /// Formats the list of enabled capabilities.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut enabled = vec![];
+
+        if self.typing {
+            enabled.push("typing");
+        }
+
+        if self.presence {
+            enabled.push("presence");
+        }
+
+        if self.acks {
+            enabled.push("acks");
+        }
+
+        if self.actions {
+            enabled.push("actions");
+        }
+
+        if self.echo {
+            enabled.push("echo");
+        }
+
+        write!(f, "{}", enabled.join(","))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn enables_only_typing_and_presence_by_default() {
+        let capabilities = Capabilities::from("");
+
+        assert_eq!(
+            capabilities,
+            Capabilities {
+                typing: true,
+                presence: true,
+                acks: false,
+                actions: false,
+                echo: false,
+            }
+        );
+    }
+
+    #[test]
+    fn disables_prefixed_capabilities() {
+        let capabilities = Capabilities::from("no-typing,no-presence");
+
+        assert_eq!(
+            capabilities,
+            Capabilities {
+                typing: false,
+                presence: false,
+                acks: false,
+                actions: false,
+                echo: false,
+            }
+        );
+    }
+
+    #[test]
+    fn enables_acks_actions_and_echo_only_when_listed() {
+        let capabilities = Capabilities::from("acks");
+
+        assert!(capabilities.acks);
+        assert!(!capabilities.actions);
+        assert!(!capabilities.echo);
+        assert_eq!(capabilities.to_string(), "typing,presence,acks");
+        assert!(Capabilities::from("actions").actions);
+        assert!(Capabilities::from("echo").echo);
+    }
+
+    #[test]
+    fn ignores_unknown_capabilities() {
+        let value = format!("{}, no-typing", Word().fake::<String>());
+        let capabilities = Capabilities::from(value.as_str());
+
+        assert_eq!(
+            capabilities,
+            Capabilities {
+                typing: false,
+                presence: true,
+                acks: false,
+                actions: false,
+                echo: false,
+            }
+        );
+    }
+
+    #[test]
+    fn formats_enabled_capabilities() {
+        let all = Capabilities::default();
+        let none = Capabilities {
+            typing: false,
+            presence: false,
+            acks: false,
+            actions: false,
+            echo: false,
+        };
+
+        assert_eq!(all.to_string(), "typing,presence");
+        assert_eq!(none.to_string(), "");
+    }
+
+    #[test]
+    fn formats_request_that_parses_to_same_capabilities() {
+        let capabilities = Capabilities {
+            typing: false,
+            presence: true,
+            acks: true,
+            actions: false,
+            echo: false,
+        };
+
+        assert_eq!(
+            capabilities.to_request(),
+            "no-typing,presence,acks,no-actions,no-echo"
+        );
+        assert_eq!(
+            Capabilities::from(capabilities.to_request().as_str()),
+            capabilities
+        );
+    }
+}
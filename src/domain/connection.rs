@@ -1,45 +1,195 @@
-use super::{Message, Peer, State, Username};
-use crate::{codec::MessageCodec, frame::Frame};
+use super::{
+    generate_token, Buffered, Handshake, Login, Message, Peer, Permission, PluginEvent, Plugins,
+    Presence, State,
+};
+use crate::{
+    args::Args,
+    codec::{MessageCodec, MAX_LENGTH},
+    commands::split_name,
+    errors::{CommandError, HandshakeError},
+    filters::Verdict,
+    frame::Frame,
+    utils::format_duration,
+};
 use futures::{SinkExt, StreamExt};
-use std::net::SocketAddr;
-use tokio::net::TcpStream;
+use std::{io::ErrorKind, net::SocketAddr, time::Duration};
+use tokio::{
+    net::TcpStream,
+    time::{sleep_until, Instant},
+};
 use tokio_util::codec::Framed;
 
 pub type Messages = Framed<TcpStream, MessageCodec>;
 
+// How long a typing indicator lasts without being refreshed by the client.
+const TYPING_TIMEOUT: Duration = Duration::from_secs(6);
+
+// Minimum time between typing indicators being broadcast for a peer.
+const TYPING_THROTTLE: Duration = Duration::from_secs(1);
+
+/// Checks that the text of a frame built from a user's message, e.g. once it's
+/// prefixed with its ID and author, can be sent to other users.
+pub fn check_length(text: &str) -> Result<(), CommandError> {
+    match text.len() > MAX_LENGTH {
+        true => Err(CommandError::MessageTooLong(MAX_LENGTH)),
+        false => Ok(()),
+    }
+}
+
 #[derive(Debug)]
 pub struct Connection {
     pub peer: Peer,
     pub messages: Messages,
     pub state: State,
+    /// The token the client can use to resume the session.
+    token: String,
+    /// Whether the client wants each accepted message acknowledged.
+    acks: bool,
+    /// Whether the client wants a copy of its own messages.
+    echo: bool,
+    typing_expiry: Option<Instant>,
+    typing_started: Option<Instant>,
 }
 
 impl Connection {
     pub async fn new(socket: TcpStream, addr: SocketAddr, state: State) -> Result<Self, String> {
         let mut messages = Framed::new(socket, MessageCodec {});
 
-        let username = Username::from_frame(&mut messages)
-            .await
-            .map_err(|err| format!("Failed to construct username: {:?}", err))?;
-
-        let peer = Peer::new(username, addr, state.clone()).await;
+        let (peer, buffered) = match Self::attach(&mut messages, addr, &state).await {
+            Ok(attached) => attached,
+            Err(err) => {
+                // Let the client know why it was rejected, e.g. an invalid nickname.
+                let _ = messages.send(Frame::Error(err.to_string())).await;
+                return Err(format!("Failed to complete handshake: {:?}", err));
+            }
+        };
 
-        let connection = Self {
+        let mut connection = Self {
             peer,
             messages,
             state,
+            token: generate_token(),
+            acks: false,
+            echo: false,
+            typing_expiry: None,
+            typing_started: None,
         };
 
-        connection.on_connect().await;
+        // Respond with the capabilities that are enabled for the client, and
+        // the token it can use to resume the session if the connection drops.
+        let shared = connection.state.lock().await;
+        let capabilities = shared
+            .peers
+            .get(&connection.peer.username)
+            .map(|peer| peer.capabilities.clone())
+            .unwrap_or_default();
+        let online = shared.online();
+        drop(shared);
+
+        connection.acks = capabilities.acks;
+        connection.echo = capabilities.echo;
+
+        let _ = connection
+            .messages
+            .send(Frame::Capabilities(capabilities.to_string()))
+            .await;
+        let _ = connection
+            .messages
+            .send(Frame::Resume(connection.token.clone()))
+            .await;
+
+        if capabilities.presence {
+            for presence in online {
+                let _ = connection
+                    .messages
+                    .send(Frame::Presence(presence.to_string()))
+                    .await;
+            }
+        }
+
+        match buffered {
+            Some(buffered) => connection.replay(buffered).await,
+            None => {
+                connection.send_unread_count().await;
+                connection.on_connect().await;
+            }
+        }
 
         Ok(connection)
     }
 
-    pub async fn process(&mut self) {
+    /// Completes the handshake, attaching the client to a new session, or to
+    /// the session it's resuming along with the frames buffered for it.
+    async fn attach(
+        messages: &mut Messages,
+        addr: SocketAddr,
+        state: &State,
+    ) -> Result<(Peer, Option<Buffered>), HandshakeError> {
+        let handshake = Handshake::from_frames(messages).await?;
+
+        match handshake.login {
+            Login::Credentials { username, password } => {
+                // Registered nicknames, and their lookalikes, may only be used
+                // with the account's password.
+                let account = state.lock().await.accounts.find(username.nickname());
+
+                if let Some(account) = account {
+                    account.verify(username.nickname(), &password).await?;
+                }
+
+                let peer = Peer::new(username, addr, handshake.capabilities, state.clone()).await?;
+
+                Ok((peer, None))
+            }
+            Login::Resume(token) => {
+                let session = state.lock().await.sessions.take(&token);
+
+                let (mut peer, buffered) = match session {
+                    Some(session) => session.resume().await,
+                    None => None,
+                }
+                .ok_or(HandshakeError::InvalidResumeToken)?;
+
+                // The client has most likely reconnected from a new address.
+                peer.addr = addr;
+
+                if let Some(connection) = state.lock().await.peers.get_mut(&peer.username) {
+                    connection.addr = addr;
+                }
+
+                Ok((peer, Some(buffered)))
+            }
+        }
+    }
+
+    /// Sends the frames that were buffered while the session was detached.
+    async fn replay(&mut self, buffered: Buffered) {
+        if buffered.dropped > 0 {
+            let message = format!(
+                "{} earlier messages were missed while you were disconnected",
+                buffered.dropped
+            );
+            let _ = self.messages.send(Frame::ServerMessage(message)).await;
+        }
+
+        for frame in buffered.frames {
+            let _ = self.messages.send(frame).await;
+        }
+    }
+
+    pub async fn process(mut self) {
         loop {
+            let typing_expiry = self.typing_expiry;
+
             tokio::select! {
-                Some(message) = self.peer.rx.recv() => {
-                    self.messages.send(message).await.unwrap();
+                Some(message) = self.peer.rx.recv() => match self.messages.send(message).await {
+                    Ok(()) => {},
+                    // A frame that's too large to encode is dropped, rather
+                    // than the client.
+                    Err(e) if e.kind() == ErrorKind::InvalidData => {
+                        tracing::warn!("Dropped a frame for client at {}: {}", self.peer.addr, e);
+                    },
+                    Err(_) => break,
                 },
                 result = self.messages.next() => match result {
                     Some(Ok(message)) => {
@@ -51,29 +201,64 @@ impl Connection {
                         );
                     },
                     None => break
+                },
+                _ = sleep_until(typing_expiry.unwrap_or_else(Instant::now)), if typing_expiry.is_some() => {
+                    // The client hasn't refreshed its typing indicator in time.
+                    self.stop_typing().await;
                 }
             }
         }
 
         // TODO: Once async trait fns are in Stable Rust, move this
         // into Drop implementation.
+        self.stop_typing().await;
         self.on_disconnect().await;
     }
 
     pub async fn handle_incoming_message(&mut self, message: Frame) {
+        self.state.lock().await.touch(&self.peer.username);
+
+        let message = match message {
+            Frame::TypingStart(_) => return self.start_typing().await,
+            Frame::TypingStop(_) => return self.stop_typing().await,
+            Frame::ReadMarker(id) => return self.mark_read(&id).await,
+            message => message,
+        };
+
+        // Sending a message implicitly stops typing.
+        self.stop_typing().await;
+
+        let message = match self.expand_aliases(message).await {
+            Ok(message) => message,
+            Err(err) => {
+                let _ = self.messages.send(Frame::Error(err.to_string())).await;
+                return;
+            }
+        };
+
+        if let Some(result) = self.apply_plugin_command(&message).await {
+            match result {
+                Ok(()) => self.ack(String::new()).await,
+                Err(err) => {
+                    let _ = self.messages.send(Frame::Error(err.to_string())).await;
+                }
+            }
+
+            return;
+        }
+
         match Message::try_from(message) {
-            Ok(Message::Cmd(cmd_type)) => {
-                if let Err(err) = cmd_type.apply(self).await {
+            Ok(Message::Cmd(cmd_type)) => match cmd_type.apply(self).await {
+                Ok(()) => self.ack(String::new()).await,
+                Err(err) => {
                     let frame = Frame::Error(err.to_string());
                     let _ = self.messages.send(frame).await;
                 }
-            }
+            },
             Ok(Message::Raw(msg)) => {
-                let mut state = self.state.lock().await;
-                let message = format!("{}: {}", &self.peer.username.to_string(), &msg);
-                let frame = Frame::Message(message);
-
-                state.broadcast(self.peer.addr, frame).await;
+                if let Err(err) = self.send_message(&msg).await {
+                    let _ = self.messages.send(Frame::Error(err.to_string())).await;
+                }
             }
             Err(err) => {
                 let frame = Frame::Error(err.to_string());
@@ -82,20 +267,263 @@ impl Connection {
         }
     }
 
+    /// Filters a message and sends it to the chat.
+    async fn send_message(&mut self, msg: &str) -> Result<(), CommandError> {
+        let msg = self.filter(msg).await?;
+
+        let mut state = self.state.lock().await;
+        check_length(&state.history.format_next(&self.peer.username, &msg))?;
+
+        let entry = state
+            .history
+            .push(self.peer.username.clone(), msg, None)
+            .clone();
+        let mentions = state.mentioned_peers(&entry.mentions);
+
+        state
+            .broadcast_message(
+                self.peer.addr,
+                Frame::Message,
+                Frame::Mention,
+                entry.format(),
+                &mentions,
+            )
+            .await;
+        let id = entry.id;
+        let frame = Frame::Message(entry.format());
+        state
+            .plugins
+            .notify(&self.state, PluginEvent::Message(entry));
+        drop(state);
+
+        self.echo(frame).await;
+        self.ack(id.to_string()).await;
+
+        Ok(())
+    }
+
+    /// Sends the client a copy of a message it sent, if it asked for one, so
+    /// that it learns the message's ID.
+    pub async fn echo(&mut self, frame: Frame) {
+        if self.echo {
+            let _ = self.messages.send(frame).await;
+        }
+    }
+
+    /// Lets the client know that its message or command was accepted, if it
+    /// asked for acks.
+    async fn ack(&mut self, id: String) {
+        if self.acks {
+            let _ = self.messages.send(Frame::Ack(id)).await;
+        }
+    }
+
+    /// Expands any of the user's aliases in a command, before it is parsed.
+    async fn expand_aliases(&self, message: Frame) -> Result<Frame, CommandError> {
+        let Frame::Message(value) = &message else {
+            return Ok(message);
+        };
+
+        let Some(command) = value.strip_prefix('/') else {
+            return Ok(message);
+        };
+
+        let state = self.state.lock().await;
+
+        // A plugin or script may have added a command with the same name as
+        // an alias since it was defined, in which case the command is used.
+        if state.plugins.find_command(split_name(command).0).is_some() {
+            return Ok(message);
+        }
+
+        match state.aliases.get(&self.peer.username) {
+            Some(aliases) => Ok(Frame::Message(format!("/{}", aliases.expand(command)?))),
+            None => Ok(message),
+        }
+    }
+
+    /// Applies the server's filters to the text of a message that other users
+    /// will see, such as a chat message or the text of `/me`, returning the
+    /// text to send. Returns an error if it was blocked, or the user is muted.
+    /// Voiced users can't be muted by the filters, and moderators and above
+    /// aren't filtered.
+    ///
+    /// Only the text is filtered, rather than the whole command, so that e.g.
+    /// the password given to `/register` is never altered.
+    pub async fn filter(&mut self, text: &str) -> Result<String, CommandError> {
+        let username = &self.peer.username;
+        let mut state = self.state.lock().await;
+
+        let level = state.permissions.level(username);
+
+        if level >= Permission::Moderator {
+            return Ok(text.to_owned());
+        }
+
+        let now = Instant::now();
+
+        let verdict = match state.mutes.get(username) {
+            Some(until) if *until > now => {
+                let remaining = format_duration(*until - now);
+                Err(format!("You are muted for another {remaining}."))
+            }
+            _ => match state.filters.apply(text) {
+                Verdict::Allow { message, warnings } => Ok((message, warnings)),
+                Verdict::Block(reason) => Err(format!("Your message was blocked: {reason}.")),
+                // Voiced users can't be muted, so the message is only blocked.
+                Verdict::Mute(reason, _) if level >= Permission::Voice => {
+                    Err(format!("Your message was blocked: {reason}."))
+                }
+                Verdict::Mute(reason, duration) => {
+                    state.mutes.insert(username.clone(), now + duration);
+                    Err(format!(
+                        "Your message was blocked and you have been muted for {}: {}.",
+                        format_duration(duration),
+                        reason
+                    ))
+                }
+            },
+        };
+
+        // Expired mutes are only removed once the user speaks again.
+        if state.mutes.get(username).is_some_and(|until| *until <= now) {
+            state.mutes.remove(username);
+        }
+
+        drop(state);
+
+        let (message, warnings) = verdict.map_err(CommandError::Filtered)?;
+
+        for warning in warnings {
+            let frame = Frame::ServerMessage(format!("Warning: {warning}."));
+            let _ = self.messages.send(frame).await;
+        }
+
+        Ok(message)
+    }
+
+    /// Runs a command provided by a plugin, if the message is one. Returns
+    /// `None` if it isn't, including when a built-in command has the same name.
+    async fn apply_plugin_command(&self, message: &Frame) -> Option<Result<(), CommandError>> {
+        let Frame::Message(value) = message else {
+            return None;
+        };
+
+        let (name, args) = split_name(value.strip_prefix('/')?);
+
+        let state = self.state.lock().await;
+        let (plugin, command) = state.plugins.find_command(name)?;
+        let permission = state.permissions.level(&self.peer.username);
+        drop(state);
+
+        if permission < command.permission {
+            return Some(Err(CommandError::PermissionDenied(command.permission)));
+        }
+
+        let args = Args::from(args);
+        let username = &self.peer.username;
+        let state = self.state.clone();
+
+        Some(Plugins::apply(plugin, state, username, command.name, args).await)
+    }
+
+    async fn mark_read(&mut self, id: &str) {
+        let mut state = self.state.lock().await;
+        let latest_id = state.history.latest_id();
+
+        // Clients can't have read messages that haven't been sent yet.
+        match id.parse() {
+            Ok(id) if latest_id.is_some_and(|latest_id| id <= latest_id) => {
+                state.last_seen_mut(&self.peer.username).mark_read(id);
+            }
+            _ => {
+                drop(state);
+
+                let frame = Frame::Error(format!("Invalid read marker: {id}"));
+                let _ = self.messages.send(frame).await;
+            }
+        }
+    }
+
+    async fn send_unread_count(&mut self) {
+        let unread = self.state.lock().await.unread_count(&self.peer.username);
+
+        let message = match unread {
+            None | Some(0) => return,
+            Some(1) => String::from("1 new message since you left"),
+            Some(count) => format!("{} new messages since you left", count),
+        };
+
+        let _ = self.messages.send(Frame::ServerMessage(message)).await;
+    }
+
+    async fn start_typing(&mut self) {
+        let now = Instant::now();
+
+        if self.typing_expiry.is_none() {
+            // Ignore clients that rapidly start and stop typing.
+            if self
+                .typing_started
+                .is_some_and(|started| now - started < TYPING_THROTTLE)
+            {
+                return;
+            }
+
+            let mut state = self.state.lock().await;
+            let frame = Frame::TypingStart(self.peer.username.to_string());
+
+            state.broadcast_typing(self.peer.addr, frame);
+            self.typing_started = Some(now);
+        }
+
+        self.typing_expiry = Some(now + TYPING_TIMEOUT);
+    }
+
+    async fn stop_typing(&mut self) {
+        if self.typing_expiry.take().is_none() {
+            return;
+        }
+
+        let mut state = self.state.lock().await;
+        let frame = Frame::TypingStop(self.peer.username.to_string());
+
+        state.broadcast_typing(self.peer.addr, frame);
+    }
+
     pub async fn on_connect(&self) {
         let mut state = self.state.lock().await;
-        let message = format!("{} has joined the chat", self.peer.username.to_string());
+        state.touch(&self.peer.username);
+
+        let message = format!("{} has joined the chat", self.peer.username);
         let frame = Frame::ServerMessage(message);
 
         state.broadcast(self.peer.addr, frame).await;
+        state
+            .broadcast_presence(
+                self.peer.addr,
+                Presence::Joined(self.peer.username.to_string()),
+            )
+            .await;
+
+        let event = PluginEvent::Join(self.peer.username.clone());
+        state.plugins.notify(&self.state, event);
     }
 
-    pub async fn on_disconnect(&self) {
+    /// Detaches the session once the connection drops, so that the client can
+    /// resume it. The user only leaves the chat if it isn't resumed in time.
+    pub async fn on_disconnect(self) {
         let mut state = self.state.lock().await;
-        let message = format!("{} has left the chat", &self.peer.username.to_string());
-        let frame = Frame::ServerMessage(message);
 
-        state.peers.remove(&self.peer.username);
-        state.broadcast(self.peer.addr, frame).await;
+        if state.sessions.grace_period.is_zero() {
+            state
+                .leave(&self.state, &self.peer.username, self.peer.addr)
+                .await;
+            return;
+        }
+
+        state.touch(&self.peer.username);
+        state
+            .sessions
+            .detach(self.token, self.peer, self.state.clone());
     }
 }
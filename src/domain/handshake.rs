@@ -0,0 +1,164 @@
+use super::{Capabilities, Messages, Username};
+use crate::{
+    errors::{HandshakeError, UsernameError},
+    frame::Frame,
+    utils::run_blocking,
+};
+use futures::StreamExt;
+use std::time::Duration;
+use tokio::time::{timeout_at, Instant};
+
+// How long a client has to send its handshake after connecting.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a client identifies itself during the handshake.
+#[derive(Debug)]
+pub enum Login {
+    /// A nickname/password pair, used to construct the client's Username. The
+    /// password is kept to verify registered nicknames.
+    Credentials {
+        username: Username,
+        password: String,
+    },
+    /// A token issued by the server, used to resume a session after the
+    /// client's connection dropped.
+    Resume(String),
+}
+
+/// The handshake is made up of the first frames sent by a client after
+/// connecting. The client may optionally negotiate its capabilities with a
+/// `Frame::Capabilities`, before sending either the nickname/password pair used
+/// to construct its Username, or a `Frame::Resume` to resume a session.
+#[derive(Debug)]
+pub struct Handshake {
+    pub login: Login,
+    pub capabilities: Capabilities,
+}
+
+impl Handshake {
+    /// Reads the handshake from the first frames sent by a client. Returns an
+    /// error if the client doesn't send it within `HANDSHAKE_TIMEOUT`.
+    pub async fn from_frames(messages: &mut Messages) -> Result<Self, HandshakeError> {
+        Self::read(messages, HANDSHAKE_TIMEOUT).await
+    }
+
+    async fn read(messages: &mut Messages, timeout: Duration) -> Result<Self, HandshakeError> {
+        let deadline = Instant::now() + timeout;
+        let mut capabilities = None;
+
+        loop {
+            let frame = match timeout_at(deadline, messages.next()).await {
+                Ok(Some(Ok(frame))) => frame,
+                Ok(Some(Err(err))) => Err(HandshakeError::ReadFailure(err))?,
+                Ok(None) => Err(HandshakeError::NoData)?,
+                Err(_) => Err(HandshakeError::TimedOut(timeout))?,
+            };
+
+            let login = match frame {
+                Frame::Capabilities(_) if capabilities.is_some() => {
+                    Err(HandshakeError::RepeatedCapabilities)?
+                }
+                Frame::Capabilities(value) => {
+                    capabilities = Some(Capabilities::from(value.as_str()));
+                    continue;
+                }
+                Frame::Resume(token) => Login::Resume(token),
+                frame => {
+                    // The message will have the format nickname,password
+                    // e.g. some_user,password123
+                    let message = frame.message();
+                    let (nickname, password) =
+                        message.split_once(',').ok_or(UsernameError::ParseFailure)?;
+                    let (nickname, password) = (nickname.to_owned(), password.to_owned());
+
+                    // Tripcodes are hashed off the async workers, as hashing
+                    // is deliberately slow.
+                    let (username, password) = run_blocking(move || {
+                        (Username::from_credentials(&nickname, &password), password)
+                    })
+                    .await;
+
+                    Login::Credentials {
+                        username: username?,
+                        password,
+                    }
+                }
+            };
+
+            return Ok(Self {
+                login,
+                capabilities: capabilities.unwrap_or_default(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec::MessageCodec;
+    use futures::SinkExt;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::codec::Framed;
+
+    /// Connects a client, returning the server's and the client's end.
+    async fn connect() -> (Messages, Messages) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+
+        (
+            Framed::new(server, MessageCodec {}),
+            Framed::new(client, MessageCodec {}),
+        )
+    }
+
+    #[tokio::test]
+    async fn reads_capabilities_and_resume_token() {
+        let (mut server, mut client) = connect().await;
+
+        client
+            .send(Frame::Capabilities("no-typing".into()))
+            .await
+            .unwrap();
+        client.send(Frame::Resume("token".into())).await.unwrap();
+
+        let handshake = Handshake::from_frames(&mut server).await.unwrap();
+
+        assert!(!handshake.capabilities.typing);
+        assert!(matches!(handshake.login, Login::Resume(token) if token == "token"));
+    }
+
+    #[tokio::test]
+    async fn returns_error_if_capabilities_are_repeated() {
+        let (mut server, mut client) = connect().await;
+
+        for _ in 0..2 {
+            client
+                .send(Frame::Capabilities("typing".into()))
+                .await
+                .unwrap();
+        }
+
+        let result = Handshake::from_frames(&mut server).await;
+
+        assert!(matches!(result, Err(HandshakeError::RepeatedCapabilities)));
+    }
+
+    #[tokio::test]
+    async fn returns_error_if_handshake_is_not_sent_in_time() {
+        let (mut server, mut client) = connect().await;
+        let timeout = Duration::from_millis(50);
+
+        client
+            .send(Frame::Capabilities("typing".into()))
+            .await
+            .unwrap();
+
+        let result = Handshake::read(&mut server, timeout).await;
+
+        assert!(matches!(result, Err(HandshakeError::TimedOut(_))));
+    }
+}
@@ -0,0 +1,312 @@
+use super::{Mention, Reactions, Username};
+use std::collections::{HashSet, VecDeque};
+
+pub type MessageId = u64;
+
+// Maximum number of messages retained in the history.
+const HISTORY_CAPACITY: usize = 1000;
+
+// Maximum number of characters of a parent message shown when quoting.
+const QUOTE_LENGTH: usize = 40;
+
+/// Parses the ID from a message formatted by `HistoryEntry::format`.
+pub fn parse_message_id(message: &str) -> Option<MessageId> {
+    let (id, _) = message.strip_prefix('[')?.split_once("] ")?;
+
+    id.parse().ok()
+}
+
+/// A single message that was broadcast to the chat, along with the
+/// message it was replying to, if any, the users it mentioned, and the
+/// reactions it has received.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub id: MessageId,
+    pub author: Username,
+    pub message: String,
+    pub parent: Option<MessageId>,
+    pub mentions: Vec<Mention>,
+    pub reactions: Reactions,
+}
+
+impl HistoryEntry {
+    /// Formats the entry as it is displayed to users, for example:
+    /// `[42] some_user!uQ8unuo3Mk: hello`
+    pub fn format(&self) -> String {
+        format_entry(self.id, &self.author, &self.message)
+    }
+
+    /// Whether the entry mentions a user, whether or not they were online
+    /// when it was sent.
+    pub fn mentions(&self, username: &Username) -> bool {
+        self.mentions
+            .iter()
+            .any(|mention| mention.matches(username))
+    }
+
+    /// Formats a short, single line excerpt of the entry, used when
+    /// quoting the entry in a reply.
+    pub fn quote(&self) -> String {
+        let line = self.message.lines().next().unwrap_or_default();
+        let mut excerpt: String = line.chars().take(QUOTE_LENGTH).collect();
+
+        if excerpt.len() < self.message.len() {
+            excerpt.push('…');
+        }
+
+        format!("[{}] {}: {}", self.id, self.author, excerpt)
+    }
+}
+
+/// A bounded, in-memory log of the most recent messages. Once the
+/// capacity is reached, the oldest messages are discarded.
+#[derive(Debug)]
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+    next_id: MessageId,
+    capacity: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(HISTORY_CAPACITY)
+    }
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            next_id: 1,
+            capacity,
+        }
+    }
+
+    /// Appends a message to the history, assigning it the next message ID.
+    pub fn push(
+        &mut self,
+        author: Username,
+        message: String,
+        parent: Option<MessageId>,
+    ) -> &mut HistoryEntry {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        let mentions = Mention::parse_all(&message);
+        let entry = HistoryEntry {
+            id: self.next_id,
+            author,
+            message,
+            parent,
+            mentions,
+            reactions: Reactions::default(),
+        };
+
+        self.next_id += 1;
+        self.entries.push_back(entry);
+
+        // We've just pushed an entry, so this can't fail.
+        self.entries.back_mut().unwrap()
+    }
+
+    /// Formats a message as it will be displayed once it's pushed, so that its
+    /// length can be checked beforehand.
+    pub fn format_next(&self, author: &Username, message: &str) -> String {
+        format_entry(self.next_id, author, message)
+    }
+
+    /// Retrieves a message by ID, if it is still retained.
+    pub fn get(&self, id: MessageId) -> Option<&HistoryEntry> {
+        self.entries.get(self.index(id)?)
+    }
+
+    /// Retrieves a mutable reference to a message by ID, if it is still retained.
+    pub fn get_mut(&mut self, id: MessageId) -> Option<&mut HistoryEntry> {
+        let index = self.index(id)?;
+
+        self.entries.get_mut(index)
+    }
+
+    /// Retrieves every retained message in the thread that the provided
+    /// message belongs to, starting with the message at the root of the
+    /// thread, in the order they were sent.
+    pub fn thread(&self, id: MessageId) -> Vec<&HistoryEntry> {
+        let Some(mut root) = self.get(id) else {
+            return vec![];
+        };
+
+        // Walk up the chain of parents for as long as they're retained.
+        while let Some(parent) = root.parent.and_then(|parent| self.get(parent)) {
+            root = parent;
+        }
+
+        // Replies are always newer than their parents, so a single pass from
+        // the root is enough to collect every nested reply.
+        let mut ids = HashSet::from([root.id]);
+
+        self.entries
+            .iter()
+            .skip_while(|entry| entry.id != root.id)
+            .filter(|entry| {
+                let in_thread =
+                    entry.id == root.id || entry.parent.is_some_and(|parent| ids.contains(&parent));
+
+                if in_thread {
+                    ids.insert(entry.id);
+                }
+
+                in_thread
+            })
+            .collect()
+    }
+
+    /// The ID of the most recent message, if any messages have been sent.
+    pub fn latest_id(&self) -> Option<MessageId> {
+        self.entries.back().map(|entry| entry.id)
+    }
+
+    /// Iterates over every retained message, from newest to oldest.
+    pub fn iter_recent(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev()
+    }
+
+    fn index(&self, id: MessageId) -> Option<usize> {
+        // IDs are contiguous, so the position of an entry can be derived
+        // from the ID of the oldest retained entry.
+        let first = self.entries.front()?.id;
+
+        id.checked_sub(first).map(|index| index as usize)
+    }
+}
+
+fn format_entry(id: MessageId, author: &Username, message: &str) -> String {
+    format!("[{}] {}: {}", id, author, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    fn username() -> Username {
+        Username::new(Word().fake(), Word().fake())
+    }
+
+    #[test]
+    fn parses_id_from_formatted_message() {
+        let mut history = History::default();
+        let entry = history.push(username(), Word().fake(), None);
+
+        assert_eq!(parse_message_id(&entry.format()), Some(1));
+    }
+
+    #[test]
+    fn returns_none_when_parsing_unformatted_message() {
+        let message: String = Word().fake();
+
+        assert_eq!(parse_message_id(&message), None);
+    }
+
+    #[test]
+    fn formats_next_entry_before_push() {
+        let mut history = History::default();
+        let author = username();
+        let message: String = Word().fake();
+
+        let formatted = history.format_next(&author, &message);
+        let entry = history.push(author, message, None);
+
+        assert_eq!(formatted, entry.format());
+    }
+
+    #[test]
+    fn push_records_mentions() {
+        let mut history = History::default();
+        let mentioned = username();
+        let message = format!("hello @{}", mentioned.nickname());
+
+        let entry = history.push(username(), message, None);
+        let other = Username::new(format!("not_{}", mentioned.nickname()), Word().fake());
+
+        assert!(entry.mentions(&mentioned));
+        assert!(!entry.mentions(&other));
+    }
+
+    #[test]
+    fn push_assigns_incrementing_ids() {
+        let mut history = History::default();
+
+        let first = history.push(username(), Word().fake(), None).id;
+        let second = history.push(username(), Word().fake(), None).id;
+
+        assert_eq!((first, second), (1, 2));
+    }
+
+    #[test]
+    fn push_discards_oldest_entry_when_full() {
+        let mut history = History::new(2);
+
+        for _ in 0..3 {
+            history.push(username(), Word().fake(), None);
+        }
+
+        assert!(history.get(1).is_none());
+        assert!(history.get(2).is_some());
+        assert!(history.get(3).is_some());
+    }
+
+    #[test]
+    fn get_returns_none_for_unknown_id() {
+        let mut history = History::default();
+        history.push(username(), Word().fake(), None);
+
+        assert!(history.get(0).is_none());
+        assert!(history.get(2).is_none());
+    }
+
+    #[test]
+    fn thread_collects_nested_replies() {
+        let mut history = History::default();
+
+        let root = history.push(username(), Word().fake(), None).id;
+        history.push(username(), Word().fake(), None);
+        let reply = history.push(username(), Word().fake(), Some(root)).id;
+        let nested = history.push(username(), Word().fake(), Some(reply)).id;
+
+        let ids: Vec<MessageId> = history.thread(root).iter().map(|e| e.id).collect();
+
+        assert_eq!(ids, vec![root, reply, nested]);
+    }
+
+    #[test]
+    fn thread_resolves_root_from_reply() {
+        let mut history = History::default();
+
+        let root = history.push(username(), Word().fake(), None).id;
+        let reply = history.push(username(), Word().fake(), Some(root)).id;
+        let sibling = history.push(username(), Word().fake(), Some(root)).id;
+
+        let ids: Vec<MessageId> = history.thread(reply).iter().map(|e| e.id).collect();
+
+        assert_eq!(ids, vec![root, reply, sibling]);
+    }
+
+    #[test]
+    fn thread_returns_empty_for_unknown_id() {
+        let history = History::default();
+
+        assert!(history.thread(1).is_empty());
+    }
+
+    #[test]
+    fn quote_truncates_long_messages() {
+        let mut history = History::default();
+        let author = username();
+        let entry = history.push(author.clone(), "a".repeat(QUOTE_LENGTH + 1), None);
+
+        let expected = format!("[1] {}: {}…", author, "a".repeat(QUOTE_LENGTH));
+
+        assert_eq!(entry.quote(), expected);
+    }
+}
@@ -0,0 +1,54 @@
+use super::MessageId;
+use std::time::SystemTime;
+
+/// When a user was last active, and the ID of the last message they have
+/// read, tracked per identity so that it survives reconnecting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastSeen {
+    pub last_active: SystemTime,
+    pub last_read: Option<MessageId>,
+}
+
+impl Default for LastSeen {
+    fn default() -> Self {
+        Self {
+            last_active: SystemTime::now(),
+            last_read: None,
+        }
+    }
+}
+
+impl LastSeen {
+    /// Marks the user as active now.
+    pub fn touch(&mut self) {
+        self.last_active = SystemTime::now();
+    }
+
+    /// Records that the user has read up to the provided message. Read markers
+    /// never move backwards, in case they arrive out of order.
+    pub fn mark_read(&mut self, id: MessageId) {
+        self.last_read = Some(self.last_read.map_or(id, |last_read| last_read.max(id)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mark_read_records_message_id() {
+        let mut seen = LastSeen::default();
+        seen.mark_read(42);
+
+        assert_eq!(seen.last_read, Some(42));
+    }
+
+    #[test]
+    fn mark_read_does_not_move_backwards() {
+        let mut seen = LastSeen::default();
+        seen.mark_read(42);
+        seen.mark_read(41);
+
+        assert_eq!(seen.last_read, Some(42));
+    }
+}
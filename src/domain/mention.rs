@@ -0,0 +1,131 @@
+use super::Username;
+use std::fmt::Display;
+
+// Punctuation that may directly follow a mention without being part of it,
+// for example `@some_user, hello!`
+const TRAILING_PUNCTUATION: &[char] = &[',', '.', ':', ';', '!', '?', ')', '"', '\''];
+
+/// A reference to another user within a message, written either as
+/// `@nickname`, or `@nickname!tripcode` to address a specific user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mention {
+    nickname: String,
+    tripcode: Option<String>,
+}
+
+impl Mention {
+    pub fn new(nickname: String, tripcode: Option<String>) -> Self {
+        Self { nickname, tripcode }
+    }
+
+    /// Parses every mention in a message. A mention must be at the start of
+    /// the message, or preceded by whitespace.
+    pub fn parse_all(message: &str) -> Vec<Self> {
+        message
+            .split_whitespace()
+            .filter_map(|word| word.strip_prefix('@'))
+            .filter_map(Self::parse)
+            .collect()
+    }
+
+    /// Parses a single `nickname` or `nickname!tripcode`, without the leading `@`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim_end_matches(TRAILING_PUNCTUATION);
+
+        let (nickname, tripcode) = match value.split_once('!') {
+            Some((nickname, tripcode)) => (nickname, Some(tripcode.to_owned())),
+            None => (value, None),
+        };
+
+        (!nickname.is_empty()).then(|| Self::new(nickname.to_owned(), tripcode))
+    }
+
+    /// Whether the mention refers to the provided user. A mention without
+    /// a tripcode refers to every user with a matching nickname.
+    pub fn matches(&self, username: &Username) -> bool {
+        self.nickname == username.nickname()
+            && self
+                .tripcode
+                .as_ref()
+                .is_none_or(|tripcode| tripcode == username.tripcode())
+    }
+}
+
+impl Display for Mention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.tripcode {
+            Some(tripcode) => write!(f, "{}!{}", self.nickname, tripcode),
+            None => write!(f, "{}", self.nickname),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn parses_nickname_mention() {
+        let nickname: String = Word().fake();
+        let message = format!("hello @{nickname}, how are you?");
+
+        let mentions = Mention::parse_all(&message);
+
+        assert_eq!(mentions, vec![Mention::new(nickname, None)]);
+    }
+
+    #[test]
+    fn parses_nickname_and_tripcode_mention() {
+        let nickname: String = Word().fake();
+        let message = format!("@{nickname}!uQ8unuo3Mk hello");
+
+        let mentions = Mention::parse_all(&message);
+
+        assert_eq!(
+            mentions,
+            vec![Mention::new(nickname, Some("uQ8unuo3Mk".into()))]
+        );
+    }
+
+    #[test]
+    fn ignores_trailing_exclamation_mark() {
+        let nickname: String = Word().fake();
+        let message = format!("hello @{nickname}!");
+
+        let mentions = Mention::parse_all(&message);
+
+        assert_eq!(mentions, vec![Mention::new(nickname, None)]);
+    }
+
+    #[test]
+    fn ignores_email_addresses_and_lone_at_signs() {
+        let mentions = Mention::parse_all("mail someone@example.com @ noon");
+
+        assert!(mentions.is_empty());
+    }
+
+    #[test]
+    fn matches_by_nickname() {
+        let username = Username::new(Word().fake(), Word().fake());
+        let mention = Mention::new(username.nickname().into(), None);
+
+        assert!(mention.matches(&username));
+    }
+
+    #[test]
+    fn matches_by_nickname_and_tripcode() {
+        let username = Username::new(Word().fake(), Word().fake());
+        let mention = Mention::new(username.nickname().into(), Some(username.tripcode().into()));
+
+        assert!(mention.matches(&username));
+    }
+
+    #[test]
+    fn does_not_match_different_tripcode() {
+        let username = Username::new(Word().fake(), "uQ8unuo3Mk".into());
+        let mention = Mention::new(username.nickname().into(), Some("0000000000".into()));
+
+        assert!(!mention.matches(&username));
+    }
+}
@@ -13,9 +13,9 @@ impl TryFrom<Frame> for Message {
         let value = value.message();
 
         Ok(match value.chars().next() {
-            Some('/') => Self::Cmd(
-                Command::try_from(&value[1..]).map_err(|err| MessageError::CommandFailure(err))?,
-            ),
+            Some('/') => {
+                Self::Cmd(Command::try_from(&value[1..]).map_err(MessageError::CommandFailure)?)
+            }
             Some(_) => Self::Raw(value),
             _ => Err(MessageError::ParseFailure)?,
         })
@@ -35,7 +35,7 @@ mod test {
         let value = Frame::Message(String::from("/help"));
         let message = Message::try_from(value);
 
-        assert_eq!(message, Ok(Message::Cmd(Command::Help(Help {}))));
+        assert_eq!(message, Ok(Message::Cmd(Command::Help(Help::new(None)))));
     }
 
     #[test]
@@ -1,15 +1,45 @@
+mod accounts;
+mod alias_store;
+mod alias_table;
+mod capabilities;
 mod connection;
+mod handshake;
+mod history;
+mod last_seen;
+mod mention;
 mod message;
 mod peer;
 mod peer_connection;
+mod permission;
+mod plugin_context;
+mod plugin_event;
+mod plugins;
+mod presence;
+mod reaction;
+mod sessions;
 mod shared;
 mod tripcode;
 mod username;
 
+pub use accounts::*;
+pub use alias_store::*;
+pub use alias_table::*;
+pub use capabilities::*;
 pub use connection::*;
+pub use handshake::*;
+pub use history::*;
+pub use last_seen::*;
+pub use mention::*;
 pub use message::*;
 pub use peer::*;
 pub use peer_connection::*;
+pub use permission::*;
+pub use plugin_context::*;
+pub use plugin_event::*;
+pub use plugins::*;
+pub use presence::*;
+pub use reaction::*;
+pub use sessions::*;
 pub use shared::*;
 pub use tripcode::*;
 pub use username::*;
@@ -1,6 +1,6 @@
-use crate::frame::Frame;
+use crate::{errors::UsernameError, frame::Frame};
 
-use super::{PeerConnection, State, Username};
+use super::{Capabilities, PeerConnection, State, Username};
 use std::net::SocketAddr;
 use tokio::sync::mpsc;
 
@@ -16,7 +16,15 @@ pub struct Peer {
 }
 
 impl Peer {
-    pub async fn new(username: Username, addr: SocketAddr, state: State) -> Self {
+    /// Adds a peer to the connected peers, replacing any detached session for
+    /// the same user. Returns an error if the user is already connected, or if
+    /// another user with a lookalike nickname is online.
+    pub async fn new(
+        username: Username,
+        addr: SocketAddr,
+        capabilities: Capabilities,
+        state: State,
+    ) -> Result<Self, UsernameError> {
         let (tx, rx) = mpsc::channel(CHANNEL_BUFFER);
 
         let peer = Self {
@@ -26,10 +34,31 @@ impl Peer {
         };
 
         let mut state = state.lock().await;
-        let peer_connection = PeerConnection::new(addr, tx);
+
+        let confusable = state
+            .peers
+            .keys()
+            .find(|other| username.is_confusable_with(other));
+
+        if let Some(other) = confusable {
+            return Err(UsernameError::ConfusableNickname(
+                username.nickname().into(),
+                other.nickname().into(),
+            ));
+        }
+
+        // Each identity may only have one connection, though connecting again
+        // without resuming replaces a detached session.
+        if state.peers.contains_key(&username) && !state.sessions.is_detached(&username) {
+            return Err(UsernameError::AlreadyConnected(username.to_string()));
+        }
+
+        state.sessions.discard(&username);
+
+        let peer_connection = PeerConnection::new(addr, tx, capabilities);
 
         state.peers.insert(username, peer_connection);
 
-        peer
+        Ok(peer)
     }
 }
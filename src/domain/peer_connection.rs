@@ -1,3 +1,4 @@
+use super::Capabilities;
 use crate::frame::Frame;
 use std::net::SocketAddr;
 use tokio::sync::mpsc;
@@ -8,10 +9,15 @@ pub type Tx = mpsc::Sender<Frame>;
 pub struct PeerConnection {
     pub addr: SocketAddr,
     pub tx: Tx,
+    pub capabilities: Capabilities,
 }
 
 impl PeerConnection {
-    pub fn new(addr: SocketAddr, tx: Tx) -> Self {
-        Self { addr, tx }
+    pub fn new(addr: SocketAddr, tx: Tx, capabilities: Capabilities) -> Self {
+        Self {
+            addr,
+            tx,
+            capabilities,
+        }
     }
 }
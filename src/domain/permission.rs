@@ -0,0 +1,117 @@
+use super::Username;
+use serde::Deserialize;
+use std::fmt::Display;
+
+/// The permission levels that users can be granted, from least to most
+/// privileged. Each level includes every permission of the levels below it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    #[default]
+    User,
+    /// Trusted to keep talking: their messages are still filtered, but a
+    /// filter can't mute them.
+    Voice,
+    /// Can run moderation commands, and isn't filtered at all.
+    Moderator,
+    Operator,
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Permission::User => "user",
+            Permission::Voice => "voice",
+            Permission::Moderator => "moderator",
+            Permission::Operator => "operator",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The users that have been granted a permission level above `User`, each
+/// identified by their full username, e.g. `some_user!uQ8unuo3Mk`.
+///
+/// Levels are granted for the whole server, as it has no rooms to scope them
+/// to. Per-room levels are left until rooms exist.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Permissions {
+    pub voice: Vec<String>,
+    pub moderators: Vec<String>,
+    pub operators: Vec<String>,
+}
+
+impl Permissions {
+    /// The highest permission level granted to a user.
+    pub fn level(&self, username: &Username) -> Permission {
+        let username = username.to_string();
+        let granted = |usernames: &[String]| usernames.contains(&username);
+
+        if granted(&self.operators) {
+            Permission::Operator
+        } else if granted(&self.moderators) {
+            Permission::Moderator
+        } else if granted(&self.voice) {
+            Permission::Voice
+        } else {
+            Permission::User
+        }
+    }
+
+    /// Every username that has been granted a permission level.
+    pub fn usernames(&self) -> impl Iterator<Item = &String> {
+        self.voice
+            .iter()
+            .chain(&self.moderators)
+            .chain(&self.operators)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    fn username() -> Username {
+        Username::new(Word().fake(), Word().fake())
+    }
+
+    #[test]
+    fn levels_are_ordered_by_privilege() {
+        assert!(Permission::User < Permission::Voice);
+        assert!(Permission::Voice < Permission::Moderator);
+        assert!(Permission::Moderator < Permission::Operator);
+    }
+
+    #[test]
+    fn level_defaults_to_user() {
+        let permissions = Permissions::default();
+
+        assert_eq!(permissions.level(&username()), Permission::User);
+    }
+
+    #[test]
+    fn level_returns_highest_granted_level() {
+        let username = username();
+        let permissions = Permissions {
+            voice: vec![username.to_string()],
+            moderators: vec![username.to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(permissions.level(&username), Permission::Moderator);
+    }
+
+    #[test]
+    fn level_requires_matching_tripcode() {
+        let username = username();
+        let permissions = Permissions {
+            operators: vec![format!("{}!0000000000", username.nickname())],
+            ..Default::default()
+        };
+
+        assert_eq!(permissions.level(&username), Permission::User);
+    }
+}
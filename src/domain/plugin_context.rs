@@ -0,0 +1,56 @@
+use super::{State, Username};
+use crate::frame::Frame;
+
+/// The handle that a plugin uses to interact with the chat.
+#[derive(Debug, Clone)]
+pub struct PluginContext {
+    name: String,
+    state: State,
+}
+
+impl PluginContext {
+    pub fn new(name: String, state: State) -> Self {
+        Self { name, state }
+    }
+
+    /// Sends a message from the plugin to everyone in the chat.
+    pub async fn say(&self, message: &str) {
+        let message = format!("[{}] {}", self.name, message);
+
+        self.broadcast(Frame::ServerMessage(message)).await;
+    }
+
+    /// Sends a message from the plugin to a single user. Returns `false` if
+    /// the user isn't connected.
+    pub async fn say_to(&self, username: &Username, message: &str) -> bool {
+        let message = format!("[{}] {}", self.name, message);
+
+        self.send_to(username, Frame::ServerMessage(message)).await
+    }
+
+    /// Sends a frame to everyone in the chat.
+    pub async fn broadcast(&self, frame: Frame) {
+        self.state.lock().await.broadcast_all(frame).await;
+    }
+
+    /// Sends a frame to a single user. Returns `false` if the user isn't connected.
+    pub async fn send_to(&self, username: &Username, frame: Frame) -> bool {
+        let Some(tx) = self
+            .state
+            .lock()
+            .await
+            .peers
+            .get(username)
+            .map(|peer| peer.tx.clone())
+        else {
+            return false;
+        };
+
+        tx.send(frame).await.is_ok()
+    }
+
+    /// The users that are currently connected.
+    pub async fn online(&self) -> Vec<Username> {
+        self.state.lock().await.peers.keys().cloned().collect()
+    }
+}
@@ -0,0 +1,12 @@
+use super::{HistoryEntry, Username};
+
+/// Something that happened in the chat, which plugins are notified of.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginEvent {
+    /// A message was sent to the chat, including replies.
+    Message(HistoryEntry),
+    /// A user joined the chat.
+    Join(Username),
+    /// A user left the chat.
+    Leave(Username),
+}
@@ -0,0 +1,173 @@
+use super::{PluginContext, PluginEvent, State, Username};
+use crate::{args::Args, commands::CommandInfo, errors::CommandError, traits::Plugin};
+use futures::FutureExt;
+use std::{fmt::Debug, panic::AssertUnwindSafe, sync::Arc};
+
+/// The plugins registered with the server.
+#[derive(Clone, Default)]
+pub struct Plugins(Vec<Arc<dyn Plugin>>);
+
+impl Debug for Plugins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|plugin| plugin.name()))
+            .finish()
+    }
+}
+
+impl Plugins {
+    pub fn register(&mut self, plugin: impl Plugin + 'static) {
+        self.0.push(Arc::new(plugin));
+    }
+
+    /// Every command provided by the registered plugins, except for those
+    /// that have the same name as a built-in command.
+    pub fn commands(&self) -> Vec<CommandInfo> {
+        self.0
+            .iter()
+            .flat_map(|plugin| plugin.commands())
+            .filter(|command| CommandInfo::find(command.name).is_none())
+            .collect()
+    }
+
+    /// Finds the plugin that provides a command, along with the command.
+    pub fn find_command(&self, name: &str) -> Option<(Arc<dyn Plugin>, CommandInfo)> {
+        if CommandInfo::find(name).is_some() {
+            return None;
+        }
+
+        self.0.iter().find_map(|plugin| {
+            plugin
+                .commands()
+                .into_iter()
+                .find(|command| command.name == name || command.aliases.contains(&name))
+                .map(|command| (plugin.clone(), command))
+        })
+    }
+
+    /// Runs a plugin's command, converting a panic into an error.
+    pub async fn apply(
+        plugin: Arc<dyn Plugin>,
+        state: State,
+        sender: &Username,
+        name: &str,
+        args: Args,
+    ) -> Result<(), CommandError> {
+        let ctx = PluginContext::new(plugin.name().into(), state);
+
+        AssertUnwindSafe(plugin.on_command(&ctx, sender, name, args))
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|_| {
+                tracing::error!("Plugin {} panicked while running /{}", plugin.name(), name);
+
+                Err(CommandError::ExecutionError(format!(
+                    "/{} failed unexpectedly",
+                    name
+                )))
+            })
+    }
+
+    /// Notifies every plugin of an event. Each plugin handles the event in its
+    /// own task, so that slow or panicking plugins don't hold up the sender.
+    pub fn notify(&self, state: &State, event: PluginEvent) {
+        for plugin in &self.0 {
+            let plugin = plugin.clone();
+            let ctx = PluginContext::new(plugin.name().into(), state.clone());
+            let event = event.clone();
+
+            tokio::spawn(async move {
+                let result = AssertUnwindSafe(plugin.on_event(&ctx, &event))
+                    .catch_unwind()
+                    .await;
+
+                if result.is_err() {
+                    tracing::error!("Plugin {} panicked while handling an event", plugin.name());
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::{Permission, Shared};
+    use async_trait::async_trait;
+    use fake::{faker::lorem::en::Word, Fake};
+    use tokio::sync::Mutex;
+
+    struct TestPlugin;
+
+    #[async_trait]
+    impl Plugin for TestPlugin {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn commands(&self) -> Vec<CommandInfo> {
+            ["panic", "help"]
+                .into_iter()
+                .map(|name| CommandInfo {
+                    name,
+                    usage: "",
+                    about: "",
+                    aliases: &[],
+                    permission: Permission::User,
+                })
+                .collect()
+        }
+
+        async fn on_command(
+            &self,
+            _ctx: &PluginContext,
+            _sender: &Username,
+            name: &str,
+            _args: Args,
+        ) -> Result<(), CommandError> {
+            if name == "panic" {
+                panic!("plugin failure");
+            }
+
+            Ok(())
+        }
+    }
+
+    fn plugins() -> Plugins {
+        let mut plugins = Plugins::default();
+        plugins.register(TestPlugin);
+
+        plugins
+    }
+
+    #[test]
+    fn commands_exclude_built_in_commands() {
+        let names: Vec<&str> = plugins()
+            .commands()
+            .iter()
+            .map(|command| command.name)
+            .collect();
+
+        assert_eq!(names, ["panic"]);
+    }
+
+    #[test]
+    fn find_command_ignores_built_in_commands() {
+        let plugins = plugins();
+
+        assert!(plugins.find_command("panic").is_some());
+        assert!(plugins.find_command("help").is_none());
+    }
+
+    #[tokio::test]
+    async fn apply_converts_panic_into_error() {
+        let (plugin, _) = plugins().find_command("panic").unwrap();
+        let state = Arc::new(Mutex::new(Shared::new()));
+        let sender = Username::new(Word().fake(), Word().fake());
+        let args = Args::from("");
+
+        let result = Plugins::apply(plugin, state, &sender, "panic", args).await;
+
+        assert!(matches!(result, Err(CommandError::ExecutionError(_))));
+    }
+}
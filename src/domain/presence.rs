@@ -0,0 +1,160 @@
+use crate::codec::MAX_LENGTH;
+use std::{fmt::Display, str::FromStr};
+
+/// An update to the list of online users, sent in a `Frame::Presence`.
+///
+/// The list of online users is written as `=` followed by their space-separated
+/// usernames, and users joining or leaving as `+` or `-` followed by their
+/// username, for example:
+/// `=alice!uQ8unuo3Mk bob!0000000000`, `+carol!uQ8unuo3Mk`
+///
+/// A list too long for a single frame is continued in further updates written
+/// as `&` followed by more usernames.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Presence {
+    Online(Vec<String>),
+    /// More online users, continuing the list from the previous `Online`.
+    More(Vec<String>),
+    Joined(String),
+    Left(String),
+}
+
+impl Presence {
+    /// Splits a list of online users into an `Online` update followed by as
+    /// many `More` updates as it takes for each to fit in a frame.
+    pub fn online(usernames: Vec<String>) -> Vec<Presence> {
+        let mut chunks: Vec<Vec<String>> = vec![vec![]];
+        // The length of the last chunk once formatted, including its prefix.
+        let mut length = 1;
+
+        for username in usernames {
+            let last = chunks.last_mut().expect("there is always a chunk");
+            let separator = usize::from(!last.is_empty());
+
+            if length + separator + username.len() <= MAX_LENGTH {
+                length += separator + username.len();
+                last.push(username);
+            } else {
+                length = 1 + username.len();
+                chunks.push(vec![username]);
+            }
+        }
+
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| match index {
+                0 => Presence::Online(chunk),
+                _ => Presence::More(chunk),
+            })
+            .collect()
+    }
+}
+
+impl Display for Presence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Presence::Online(usernames) => write!(f, "={}", usernames.join(" ")),
+            Presence::More(usernames) => write!(f, "&{}", usernames.join(" ")),
+            Presence::Joined(username) => write!(f, "+{}", username),
+            Presence::Left(username) => write!(f, "-{}", username),
+        }
+    }
+}
+
+impl FromStr for Presence {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut chars = value.chars();
+        let kind = chars.next();
+        let usernames = chars.as_str();
+
+        match kind {
+            Some('=') => Ok(Presence::Online(
+                usernames.split_whitespace().map(String::from).collect(),
+            )),
+            Some('&') => Ok(Presence::More(
+                usernames.split_whitespace().map(String::from).collect(),
+            )),
+            Some('+') if !usernames.is_empty() => Ok(Presence::Joined(usernames.into())),
+            Some('-') if !usernames.is_empty() => Ok(Presence::Left(usernames.into())),
+            _ => Err(format!("invalid presence update: {value}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    #[test]
+    fn formats_and_parses_online_users() {
+        let presence = Presence::Online(vec![Word().fake(), Word().fake()]);
+
+        assert_eq!(presence.to_string().parse(), Ok(presence));
+    }
+
+    #[test]
+    fn formats_and_parses_joined_and_left_users() {
+        let username: String = Word().fake();
+
+        for presence in [
+            Presence::Joined(username.clone()),
+            Presence::Left(username.clone()),
+        ] {
+            assert_eq!(presence.to_string().parse(), Ok(presence));
+        }
+    }
+
+    #[test]
+    fn formats_and_parses_more_online_users() {
+        let presence = Presence::More(vec![Word().fake(), Word().fake()]);
+
+        assert_eq!(presence.to_string().parse(), Ok(presence));
+    }
+
+    #[test]
+    fn splits_online_users_into_updates_that_fit_in_a_frame() {
+        let usernames: Vec<String> = (0..1000)
+            .map(|index| format!("user{index:0>4}!uQ8unuo3Mk"))
+            .collect();
+
+        let updates = Presence::online(usernames.clone());
+
+        assert!(updates.len() > 1);
+        assert!(matches!(updates[0], Presence::Online(_)));
+
+        let mut received = vec![];
+
+        for update in updates {
+            assert!(update.to_string().len() <= MAX_LENGTH);
+
+            match update.to_string().parse() {
+                Ok(Presence::Online(chunk)) => received = chunk,
+                Ok(Presence::More(chunk)) => received.extend(chunk),
+                update => panic!("unexpected update: {update:?}"),
+            }
+        }
+
+        assert_eq!(received, usernames);
+    }
+
+    #[test]
+    fn sends_single_update_when_nobody_is_online() {
+        assert_eq!(Presence::online(vec![]), [Presence::Online(vec![])]);
+    }
+
+    #[test]
+    fn parses_empty_list_of_online_users() {
+        assert_eq!("=".parse(), Ok(Presence::Online(vec![])));
+    }
+
+    #[test]
+    fn returns_error_for_invalid_update() {
+        for value in ["", "+", "alice"] {
+            assert!(value.parse::<Presence>().is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use super::Username;
+use std::fmt::Display;
+
+// Maximum number of characters in an emoji reaction. Some emoji are made up
+// of several code points (flags, skin tones, ZWJ sequences), so this is more
+// generous than a single character.
+const MAX_EMOJI_LENGTH: usize = 10;
+
+// Built-in table of shortcodes that may be used in place of an emoji.
+const SHORTCODES: &[(&str, &str)] = &[
+    ("thumbsup", "👍"),
+    ("+1", "👍"),
+    ("thumbsdown", "👎"),
+    ("-1", "👎"),
+    ("heart", "❤️"),
+    ("smile", "😄"),
+    ("laughing", "😆"),
+    ("joy", "😂"),
+    ("wink", "😉"),
+    ("thinking", "🤔"),
+    ("cry", "😢"),
+    ("scream", "😱"),
+    ("angry", "😠"),
+    ("tada", "🎉"),
+    ("fire", "🔥"),
+    ("rocket", "🚀"),
+    ("eyes", "👀"),
+    ("clap", "👏"),
+    ("pray", "🙏"),
+    ("wave", "👋"),
+    ("ok_hand", "👌"),
+    ("muscle", "💪"),
+    ("100", "💯"),
+    ("check", "✅"),
+    ("x", "❌"),
+    ("warning", "⚠️"),
+    ("question", "❓"),
+    ("star", "⭐"),
+    ("coffee", "☕"),
+    ("bug", "🐛"),
+];
+
+// Joins emoji into a single emoji, e.g. 👩‍💻.
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+// Turns the preceding digit, `#` or `*` into a keycap, e.g. 1️⃣.
+const COMBINING_KEYCAP: char = '\u{20E3}';
+
+// Choose whether the preceding character is displayed as text or as an emoji,
+// e.g. ❤︎ or ❤️, without changing which emoji it is.
+const VARIATION_SELECTORS: &[char] = &['\u{FE0E}', '\u{FE0F}'];
+
+/// Whether a value is a single emoji: a pictograph, flag or keycap, which may
+/// be followed by modifiers such as a skin tone, and joined to other emoji
+/// with zero width joiners.
+fn is_emoji(value: &str) -> bool {
+    let mut chars = value.chars().peekable();
+
+    loop {
+        match chars.next() {
+            Some(c) if is_regional_indicator(c) => {
+                // Flags are made up of a pair of regional indicators.
+                if !chars.next().is_some_and(is_regional_indicator) {
+                    return false;
+                }
+            }
+            Some(c) if is_pictograph(c) => {}
+            Some('0'..='9' | '#' | '*') => {
+                chars.next_if_eq(&'\u{FE0F}');
+
+                if chars.next() != Some(COMBINING_KEYCAP) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        while chars.next_if(|c| is_modifier(*c)).is_some() {}
+
+        match chars.next() {
+            None => return true,
+            Some(ZERO_WIDTH_JOINER) => continue,
+            Some(_) => return false,
+        }
+    }
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// Whether a character is in one of the blocks that emoji are drawn from.
+fn is_pictograph(c: char) -> bool {
+    matches!(
+        c,
+        '\u{A9}'
+            | '\u{AE}'
+            | '\u{203C}'
+            | '\u{2049}'
+            | '\u{2122}'
+            | '\u{2139}'
+            | '\u{2194}'..='\u{21AA}'
+            | '\u{231A}'..='\u{23FF}'
+            | '\u{24C2}'
+            | '\u{25AA}'..='\u{25FE}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2934}'..='\u{2935}'
+            | '\u{2B05}'..='\u{2B55}'
+            | '\u{3030}'
+            | '\u{303D}'
+            | '\u{3297}'
+            | '\u{3299}'
+            | '\u{1F000}'..='\u{1F1E5}'
+            | '\u{1F200}'..='\u{1F3FA}'
+            | '\u{1F400}'..='\u{1FAFF}'
+    )
+}
+
+/// Whether a character modifies the emoji before it: variation selectors,
+/// skin tones, and the tags used by subdivision flags such as 🏴󠁧󠁢󠁳󠁣󠁴󠁿.
+fn is_modifier(c: char) -> bool {
+    matches!(
+        c,
+        '\u{FE0E}' | '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// An emoji that a user has reacted with. Emoji that only differ in their
+/// variation selectors, such as `❤` and `❤️`, are equal, but each is displayed
+/// as it was written.
+#[derive(Debug, Clone)]
+pub struct Emoji(String);
+
+impl Emoji {
+    /// Resolves a reaction provided by a user into an emoji. The value may
+    /// either be a shortcode such as `:thumbsup:`, or a single emoji itself.
+    /// Returns `None` if the shortcode is unknown, or the value isn't an emoji.
+    pub fn resolve(value: &str) -> Option<Self> {
+        if let Some(shortcode) = value
+            .strip_prefix(':')
+            .and_then(|value| value.strip_suffix(':'))
+        {
+            return SHORTCODES
+                .iter()
+                .find(|(name, _)| *name == shortcode)
+                .map(|(_, emoji)| Self(emoji.to_string()));
+        }
+
+        let length = value.chars().count();
+
+        (length <= MAX_EMOJI_LENGTH && is_emoji(value)).then(|| Self(value.to_string()))
+    }
+
+    /// The characters that identify the emoji, ignoring variation selectors.
+    fn key(&self) -> impl Iterator<Item = char> + '_ {
+        self.0.chars().filter(|c| !VARIATION_SELECTORS.contains(c))
+    }
+}
+
+impl PartialEq for Emoji {
+    fn eq(&self, other: &Self) -> bool {
+        self.key().eq(other.key())
+    }
+}
+
+impl Eq for Emoji {}
+
+impl Display for Emoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The reactions to a single message, grouped by emoji in the order that
+/// they were first used.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reactions(Vec<(Emoji, Vec<Username>)>);
+
+impl Reactions {
+    /// Records a reaction from a user. Each user may only react with each
+    /// emoji once, so this returns `false` if the user has already reacted
+    /// with the provided emoji.
+    pub fn add(&mut self, emoji: Emoji, username: Username) -> bool {
+        match self.0.iter_mut().find(|(existing, _)| *existing == emoji) {
+            Some((_, users)) if users.contains(&username) => false,
+            Some((_, users)) => {
+                users.push(username);
+                true
+            }
+            None => {
+                self.0.push((emoji, vec![username]));
+                true
+            }
+        }
+    }
+
+    /// Formats a summary of the reactions, for example: `👍 2 · 🎉 1`
+    pub fn format(&self) -> String {
+        self.0
+            .iter()
+            .map(|(emoji, users)| format!("{} {}", emoji, users.len()))
+            .collect::<Vec<String>>()
+            .join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    fn username() -> Username {
+        Username::new(Word().fake(), Word().fake())
+    }
+
+    fn resolve_emoji(value: &str) -> Option<String> {
+        Emoji::resolve(value).map(|emoji| emoji.to_string())
+    }
+
+    fn emoji(value: &str) -> Emoji {
+        Emoji::resolve(value).unwrap()
+    }
+
+    #[test]
+    fn resolves_known_shortcode() {
+        assert_eq!(resolve_emoji(":thumbsup:"), Some("👍".into()));
+    }
+
+    #[test]
+    fn returns_none_for_unknown_shortcode() {
+        let shortcode = format!(":{}:", Word().fake::<String>());
+
+        assert_eq!(resolve_emoji(&shortcode), None);
+    }
+
+    #[test]
+    fn resolves_emoji() {
+        assert_eq!(resolve_emoji("🎉"), Some("🎉".into()));
+    }
+
+    #[test]
+    fn returns_none_for_plain_text() {
+        let word: String = Word().fake();
+
+        assert_eq!(resolve_emoji(&word), None);
+    }
+
+    #[test]
+    fn resolves_emoji_sequences() {
+        for emoji in [
+            "❤️",
+            "👍🏽",
+            "🇬🇧",
+            "1️⃣",
+            "👩‍💻",
+            "👨‍👩‍👧‍👦",
+            "🏳️‍🌈",
+            "🏴\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}",
+        ] {
+            assert_eq!(resolve_emoji(emoji), Some(emoji.into()), "{emoji}");
+        }
+    }
+
+    #[test]
+    fn returns_none_for_non_emoji_text() {
+        for value in [
+            "", "é", "日本", "ñandú", "→x", "👍👍", "👍 ", "👩‍", "🇬", "1", "\u{200B}", "\u{FE0F}",
+        ] {
+            assert_eq!(resolve_emoji(value), None, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn returns_none_for_overlong_sequence() {
+        let emoji = ["👩"; 6].join("\u{200D}");
+
+        assert_eq!(resolve_emoji(&emoji), None);
+    }
+
+    #[test]
+    fn ignores_variation_selectors_when_comparing() {
+        assert_eq!(emoji("\u{2764}"), emoji(":heart:"));
+        assert_eq!(emoji("\u{2764}\u{FE0E}"), emoji("\u{2764}\u{FE0F}"));
+        assert_ne!(emoji("\u{2764}"), emoji("💔"));
+    }
+
+    #[test]
+    fn add_groups_reactions_by_emoji() {
+        let mut reactions = Reactions::default();
+
+        reactions.add(emoji("👍"), username());
+        reactions.add(emoji("🎉"), username());
+        reactions.add(emoji("👍"), username());
+
+        assert_eq!(reactions.format(), "👍 2 · 🎉 1");
+    }
+
+    #[test]
+    fn add_rejects_duplicate_reaction_from_user() {
+        let mut reactions = Reactions::default();
+        let username = username();
+
+        assert!(reactions.add(emoji("👍"), username.clone()));
+        assert!(!reactions.add(emoji("👍"), username.clone()));
+        assert!(reactions.add(emoji("🎉"), username));
+    }
+
+    #[test]
+    fn add_treats_variations_of_emoji_as_the_same() {
+        let mut reactions = Reactions::default();
+        let username = username();
+
+        assert!(reactions.add(emoji(":heart:"), username.clone()));
+        assert!(!reactions.add(emoji("\u{2764}"), username));
+        assert_eq!(reactions.format(), "\u{2764}\u{FE0F} 1");
+    }
+}
@@ -0,0 +1,339 @@
+use super::{Peer, State, Username};
+use crate::frame::Frame;
+use futures::FutureExt;
+use rand::{rngs::OsRng, RngCore};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+use tokio::{sync::oneshot, task::JoinSet, time::sleep};
+
+// How long a session is kept for after its connection drops, by default.
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+// The most frames kept for a detached session. Older frames are dropped first.
+const MAX_BUFFERED_FRAMES: usize = 256;
+
+// The number of random bytes in a resume token.
+const TOKEN_LENGTH: usize = 32;
+
+/// Generates a random token that can be used to resume a session.
+pub fn generate_token() -> String {
+    let mut bytes = [0; TOKEN_LENGTH];
+    OsRng.fill_bytes(&mut bytes);
+
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The frames sent to a session while it was detached.
+#[derive(Debug, Default)]
+pub struct Buffered {
+    pub frames: VecDeque<Frame>,
+    /// The number of frames that were dropped, as too many were sent.
+    pub dropped: usize,
+}
+
+impl Buffered {
+    fn push(&mut self, frame: Frame) {
+        if self.frames.len() == MAX_BUFFERED_FRAMES {
+            self.frames.pop_front();
+            self.dropped += 1;
+        }
+
+        self.frames.push_back(frame);
+    }
+}
+
+// Used to hand a detached session over to the connection resuming it.
+type Handover = oneshot::Sender<(Peer, Buffered)>;
+
+/// A session whose connection has dropped. The session's peer stays connected
+/// to the chat, and the frames sent to it are buffered until it's resumed.
+#[derive(Debug)]
+pub struct DetachedSession {
+    username: Username,
+    resume: oneshot::Sender<Handover>,
+}
+
+impl DetachedSession {
+    /// Takes over the session's peer, along with the frames sent to it while
+    /// it was detached.
+    pub async fn resume(self) -> Option<(Peer, Buffered)> {
+        let (handover, receiver) = oneshot::channel();
+        self.resume.send(handover).ok()?;
+
+        receiver.await.ok()
+    }
+}
+
+/// The sessions that can be resumed, keyed by their resume tokens.
+#[derive(Debug)]
+pub struct Sessions {
+    pub grace_period: Duration,
+    detached: HashMap<String, DetachedSession>,
+    // The tasks holding each detached session until it's resumed or expires.
+    tasks: JoinSet<()>,
+}
+
+impl Default for Sessions {
+    fn default() -> Self {
+        Self::new(DEFAULT_GRACE_PERIOD)
+    }
+}
+
+impl Sessions {
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            grace_period,
+            detached: HashMap::new(),
+            tasks: JoinSet::new(),
+        }
+    }
+
+    /// Keeps a peer's session alive for the grace period, after its connection
+    /// dropped. The peer leaves the chat if the session isn't resumed in time.
+    pub fn detach(&mut self, token: String, peer: Peer, state: State) {
+        let (resume, requests) = oneshot::channel();
+
+        let session = DetachedSession {
+            username: peer.username.clone(),
+            resume,
+        };
+
+        // Clean up after sessions that have already been resumed or expired.
+        while let Some(Some(_)) = self.tasks.join_next().now_or_never() {}
+
+        self.detached.insert(token.clone(), session);
+        self.tasks
+            .spawn(hold(token, peer, state, requests, self.grace_period));
+    }
+
+    /// The number of detached sessions.
+    pub fn len(&self) -> usize {
+        self.detached.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.detached.is_empty()
+    }
+
+    /// Whether a user has a detached session.
+    pub fn is_detached(&self, username: &Username) -> bool {
+        self.detached
+            .values()
+            .any(|session| session.username == *username)
+    }
+
+    /// Removes a detached session so that it can be resumed.
+    pub fn take(&mut self, token: &str) -> Option<DetachedSession> {
+        self.detached.remove(token)
+    }
+
+    /// Discards any detached sessions for a user, without them leaving the
+    /// chat, as they've connected again without resuming.
+    pub fn discard(&mut self, username: &Username) {
+        self.detached
+            .retain(|_, session| session.username != *username);
+    }
+
+    /// Discards every detached session, e.g. as the server is shutting down.
+    /// Returns the tasks that were holding them, which finish promptly once
+    /// their sessions are discarded.
+    pub fn close(&mut self) -> JoinSet<()> {
+        self.detached.clear();
+
+        std::mem::replace(&mut self.tasks, JoinSet::new())
+    }
+}
+
+/// Buffers the frames sent to a detached session's peer, until the session is
+/// resumed, discarded, or its grace period ends.
+async fn hold(
+    token: String,
+    mut peer: Peer,
+    state: State,
+    mut requests: oneshot::Receiver<Handover>,
+    grace_period: Duration,
+) {
+    let mut buffered = Buffered::default();
+    let expiry = sleep(grace_period);
+    tokio::pin!(expiry);
+
+    loop {
+        tokio::select! {
+            Some(frame) = peer.rx.recv() => buffered.push(frame),
+            request = &mut requests => {
+                // The request fails if the session was discarded.
+                if let Ok(handover) = request {
+                    hand_over(peer, buffered, handover);
+                }
+
+                return;
+            },
+            _ = &mut expiry => break,
+        }
+    }
+
+    let mut shared = state.lock().await;
+
+    if shared.sessions.take(&token).is_some() {
+        shared.leave(&state, &peer.username, peer.addr).await;
+        return;
+    }
+
+    drop(shared);
+
+    // The session was taken just as it expired, so it's handed over anyway.
+    if let Ok(handover) = requests.await {
+        hand_over(peer, buffered, handover);
+    }
+}
+
+/// Hands a detached session over to the connection resuming it, including any
+/// frames that are still queued for its peer.
+fn hand_over(mut peer: Peer, mut buffered: Buffered, handover: Handover) {
+    while let Ok(frame) = peer.rx.try_recv() {
+        buffered.push(frame);
+    }
+
+    let _ = handover.send((peer, buffered));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::{Capabilities, Shared};
+    use fake::{faker::lorem::en::Word, Fake};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    async fn connected_peer(state: &State) -> Peer {
+        let username = Username::new(Word().fake(), Word().fake());
+        let addr = "127.0.0.1:8080".parse().unwrap();
+
+        Peer::new(username, addr, Capabilities::default(), state.clone())
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn generates_unique_tokens() {
+        let first = generate_token();
+        let second = generate_token();
+
+        assert_eq!(first.len(), TOKEN_LENGTH * 2);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn drops_oldest_frames_when_buffer_is_full() {
+        let mut buffered = Buffered::default();
+
+        for i in 0..MAX_BUFFERED_FRAMES + 2 {
+            buffered.push(Frame::Message(i.to_string()));
+        }
+
+        assert_eq!(buffered.frames.len(), MAX_BUFFERED_FRAMES);
+        assert_eq!(buffered.frames[0], Frame::Message("2".into()));
+        assert_eq!(buffered.dropped, 2);
+    }
+
+    #[tokio::test]
+    async fn resumes_session_with_buffered_frames() {
+        let state = Arc::new(Mutex::new(Shared::new()));
+        let peer = connected_peer(&state).await;
+        let username = peer.username.clone();
+
+        let mut shared = state.lock().await;
+        shared.sessions.detach("token".into(), peer, state.clone());
+        shared
+            .broadcast_all(Frame::ServerMessage("hello".into()))
+            .await;
+        drop(shared);
+
+        let session = state.lock().await.sessions.take("token").unwrap();
+        let (peer, buffered) = session.resume().await.unwrap();
+
+        assert_eq!(peer.username, username);
+        assert_eq!(buffered.frames, [Frame::ServerMessage("hello".into())]);
+        assert!(state.lock().await.peers.contains_key(&username));
+    }
+
+    #[tokio::test]
+    async fn leaves_chat_when_grace_period_ends() {
+        let state = Arc::new(Mutex::new(Shared::new()));
+        state.lock().await.sessions.grace_period = Duration::from_millis(10);
+
+        let peer = connected_peer(&state).await;
+        let username = peer.username.clone();
+
+        let mut shared = state.lock().await;
+        shared.sessions.detach("token".into(), peer, state.clone());
+        drop(shared);
+
+        sleep(Duration::from_millis(100)).await;
+
+        let shared = state.lock().await;
+        assert!(!shared.peers.contains_key(&username));
+        assert!(shared.sessions.detached.is_empty());
+    }
+
+    #[tokio::test]
+    async fn connecting_again_replaces_detached_session() {
+        let state = Arc::new(Mutex::new(Shared::new()));
+        let peer = connected_peer(&state).await;
+        let username = peer.username.clone();
+        let addr = peer.addr;
+
+        Peer::new(
+            username.clone(),
+            addr,
+            Capabilities::default(),
+            state.clone(),
+        )
+        .await
+        .unwrap_err();
+
+        let mut shared = state.lock().await;
+        shared.sessions.detach("token".into(), peer, state.clone());
+        drop(shared);
+
+        Peer::new(username, addr, Capabilities::default(), state.clone())
+            .await
+            .unwrap();
+
+        assert!(state.lock().await.sessions.take("token").is_none());
+    }
+
+    #[tokio::test]
+    async fn discarded_session_cannot_be_resumed() {
+        let state = Arc::new(Mutex::new(Shared::new()));
+        let peer = connected_peer(&state).await;
+        let username = peer.username.clone();
+
+        let mut shared = state.lock().await;
+        shared.sessions.detach("token".into(), peer, state.clone());
+        shared.sessions.discard(&username);
+
+        assert!(shared.sessions.take("token").is_none());
+    }
+
+    #[tokio::test]
+    async fn closing_stops_holding_detached_sessions() {
+        let state = Arc::new(Mutex::new(Shared::new()));
+        let peer = connected_peer(&state).await;
+
+        let mut shared = state.lock().await;
+        shared.sessions.detach("token".into(), peer, state.clone());
+        let mut tasks = shared.sessions.close();
+        drop(shared);
+
+        let finished = tokio::time::timeout(Duration::from_secs(1), async {
+            while tasks.join_next().await.is_some() {}
+        })
+        .await;
+
+        assert!(finished.is_ok());
+        assert!(state.lock().await.sessions.is_empty());
+    }
+}
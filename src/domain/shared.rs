@@ -1,29 +1,299 @@
-use super::{PeerConnection, Username};
-use crate::frame::Frame;
+use super::{
+    Accounts, AliasStore, AliasTable, History, LastSeen, Mention, PeerConnection, Permissions,
+    PluginEvent, Plugins, Presence, Sessions, Username,
+};
+use crate::{filters::FilterPipeline, frame::Frame};
 use futures::future::join_all;
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
-use tokio::sync::Mutex;
+use tokio::{sync::Mutex, time::Instant};
 
 pub type State = Arc<Mutex<Shared>>;
 
-#[derive(Debug)]
+// Most identities that activity is tracked for. The least recently active of
+// those that are offline are forgotten first.
+const MAX_LAST_SEEN: usize = 10_000;
+
+// Most identities whose aliases are kept, as each may define up to 50. The
+// aliases of the least recently active of those that are offline are
+// forgotten first.
+const MAX_ALIAS_TABLES: usize = 1_000;
+
+#[derive(Debug, Default)]
 pub struct Shared {
     pub peers: HashMap<Username, PeerConnection>,
+    pub history: History,
+    pub last_seen: HashMap<Username, LastSeen>,
+    pub aliases: AliasStore,
+    pub permissions: Permissions,
+    pub plugins: Plugins,
+    pub filters: FilterPipeline,
+    /// Users that have been muted by a filter, and when their mute ends.
+    pub mutes: HashMap<Username, Instant>,
+    pub accounts: Accounts,
+    pub sessions: Sessions,
 }
 
 impl Shared {
     pub fn new() -> Self {
         Shared {
             peers: HashMap::new(),
+            history: History::default(),
+            last_seen: HashMap::new(),
+            aliases: AliasStore::default(),
+            permissions: Permissions::default(),
+            plugins: Plugins::default(),
+            filters: FilterPipeline::default(),
+            mutes: HashMap::new(),
+            accounts: Accounts::default(),
+            sessions: Sessions::default(),
         }
     }
 
     pub async fn broadcast(&mut self, sender: SocketAddr, frame: Frame) {
-        // TODO: Maybe allow the caller to specify if the sender should also receive the message?
         let filtered_peers = self.peers.iter().filter(|peer| peer.1.addr != sender);
 
         // TODO: Remove this clone call when passing the frame to the peer's sender
         let futs = filtered_peers.map(|peer| peer.1.tx.send(frame.clone()));
         join_all(futs).await;
     }
+
+    /// Broadcasts a chat message to every connected peer, except for the sender,
+    /// in the frame provided, e.g. `Frame::Message`. Peers that are mentioned
+    /// in the message receive it in the mention frame instead, e.g.
+    /// `Frame::Mention`, so that it can be highlighted.
+    pub async fn broadcast_message(
+        &mut self,
+        sender: SocketAddr,
+        frame: fn(String) -> Frame,
+        mention_frame: fn(String) -> Frame,
+        message: String,
+        mentions: &[Username],
+    ) {
+        let filtered_peers = self.peers.iter().filter(|peer| peer.1.addr != sender);
+
+        let futs = filtered_peers.map(|(username, peer)| {
+            let frame = match mentions.contains(username) {
+                true => mention_frame(message.clone()),
+                false => frame(message.clone()),
+            };
+
+            peer.tx.send(frame)
+        });
+
+        join_all(futs).await;
+    }
+
+    /// Marks a user as active now.
+    pub fn touch(&mut self, username: &Username) {
+        self.last_seen_mut(username).touch();
+    }
+
+    /// The activity tracked for a user, which starts being tracked if it isn't
+    /// already, forgetting the least recently active offline user if too many
+    /// are tracked.
+    pub fn last_seen_mut(&mut self, username: &Username) -> &mut LastSeen {
+        if !self.last_seen.contains_key(username) && self.last_seen.len() >= MAX_LAST_SEEN {
+            let least_recent = self
+                .last_seen
+                .iter()
+                .filter(|(username, _)| !self.peers.contains_key(*username))
+                .min_by_key(|(_, last_seen)| last_seen.last_active)
+                .map(|(username, _)| username.clone());
+
+            if let Some(least_recent) = least_recent {
+                self.last_seen.remove(&least_recent);
+            }
+        }
+
+        self.last_seen.entry(username.clone()).or_default()
+    }
+
+    /// Replaces the aliases a user has defined, forgetting those of the least
+    /// recently active offline user if too many users have aliases. Use with
+    /// `AliasStore::update` to save them.
+    pub fn set_aliases(&mut self, username: &Username, aliases: AliasTable) {
+        if aliases.is_empty() {
+            self.aliases.remove(username);
+            return;
+        }
+
+        if self.aliases.get(username).is_none() && self.aliases.len() >= MAX_ALIAS_TABLES {
+            // Users whose activity isn't tracked are forgotten first.
+            let least_recent = self
+                .aliases
+                .usernames()
+                .filter(|username| !self.peers.contains_key(*username))
+                .min_by_key(|username| {
+                    self.last_seen
+                        .get(*username)
+                        .map(|last_seen| last_seen.last_active)
+                })
+                .cloned();
+
+            if let Some(least_recent) = least_recent {
+                self.aliases.remove(&least_recent);
+            }
+        }
+
+        self.aliases.insert(username.clone(), aliases);
+    }
+
+    /// Counts the messages sent by other users since a user last read the chat.
+    /// Returns `None` if the user hasn't read any messages before.
+    pub fn unread_count(&self, username: &Username) -> Option<usize> {
+        let last_read = self.last_seen.get(username)?.last_read?;
+
+        let count = self
+            .history
+            .iter_recent()
+            .take_while(|entry| entry.id > last_read)
+            .filter(|entry| entry.author != *username)
+            .count();
+
+        Some(count)
+    }
+
+    /// Resolves the connected peers that are mentioned in a message.
+    pub fn mentioned_peers(&self, mentions: &[Mention]) -> Vec<Username> {
+        self.peers
+            .keys()
+            .filter(|username| mentions.iter().any(|mention| mention.matches(username)))
+            .cloned()
+            .collect()
+    }
+
+    /// Broadcasts a typing indicator to every connected peer that has the typing
+    /// capability enabled, except for the sender. Typing indicators are only
+    /// informational, so they're dropped for any peer whose queue is full.
+    pub fn broadcast_typing(&mut self, sender: SocketAddr, frame: Frame) {
+        let filtered_peers = self
+            .peers
+            .values()
+            .filter(|peer| peer.addr != sender && peer.capabilities.typing);
+
+        for peer in filtered_peers {
+            let _ = peer.tx.try_send(frame.clone());
+        }
+    }
+
+    /// Broadcasts a presence update to every connected peer that has the
+    /// presence capability enabled, except for the sender.
+    pub async fn broadcast_presence(&mut self, sender: SocketAddr, presence: Presence) {
+        let frame = Frame::Presence(presence.to_string());
+
+        let futs = self
+            .peers
+            .values()
+            .filter(|peer| peer.addr != sender && peer.capabilities.presence)
+            .map(|peer| peer.tx.send(frame.clone()));
+
+        join_all(futs).await;
+    }
+
+    /// Broadcasts a `/me` action to every connected peer, except for the sender.
+    /// Peers without the actions capability receive it as a server message.
+    pub async fn broadcast_action(&mut self, sender: SocketAddr, action: String) {
+        let futs = self
+            .peers
+            .values()
+            .filter(|peer| peer.addr != sender)
+            .map(|peer| {
+                let frame = match peer.capabilities.actions {
+                    true => Frame::Action(action.clone()),
+                    false => Frame::ServerMessage(action.clone()),
+                };
+
+                peer.tx.send(frame)
+            });
+
+        join_all(futs).await;
+    }
+
+    /// The list of online users sorted by username, split into as many
+    /// updates as it takes to fit in frames.
+    pub fn online(&self) -> Vec<Presence> {
+        let mut usernames: Vec<String> = self.peers.keys().map(Username::to_string).collect();
+        usernames.sort();
+
+        Presence::online(usernames)
+    }
+
+    /// Broadcasts a frame to every connected peer, including the sender.
+    pub async fn broadcast_all(&mut self, frame: Frame) {
+        let futs = self.peers.values().map(|peer| peer.tx.send(frame.clone()));
+        join_all(futs).await;
+    }
+
+    /// Removes a user from the chat, and lets everyone else know that they've
+    /// left. The user is only removed from the connected peers if they haven't
+    /// connected again from another address.
+    pub async fn leave(&mut self, state: &State, username: &Username, addr: SocketAddr) {
+        self.touch(username);
+
+        if self
+            .peers
+            .get(username)
+            .is_some_and(|peer| peer.addr == addr)
+        {
+            self.peers.remove(username);
+        }
+
+        let frame = Frame::ServerMessage(format!("{} has left the chat", username));
+        self.broadcast(addr, frame).await;
+        self.broadcast_presence(addr, Presence::Left(username.to_string()))
+            .await;
+
+        let event = PluginEvent::Leave(username.clone());
+        self.plugins.notify(state, event);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn username(index: usize) -> Username {
+        Username::new(format!("user{index}"), "tripcode".into())
+    }
+
+    #[test]
+    fn forgets_least_recently_active_user_when_full() {
+        let mut shared = Shared::new();
+        let now = SystemTime::now();
+
+        for index in 0..MAX_LAST_SEEN {
+            shared.last_seen_mut(&username(index)).last_active =
+                now - Duration::from_secs(index as u64);
+        }
+
+        shared.touch(&username(MAX_LAST_SEEN));
+
+        assert_eq!(shared.last_seen.len(), MAX_LAST_SEEN);
+        assert!(!shared.last_seen.contains_key(&username(MAX_LAST_SEEN - 1)));
+        assert!(shared.last_seen.contains_key(&username(MAX_LAST_SEEN)));
+    }
+
+    #[test]
+    fn forgets_aliases_of_least_recently_active_user_when_full() {
+        let mut shared = Shared::new();
+        let now = SystemTime::now();
+        let mut aliases = AliasTable::default();
+        aliases.insert("w".into(), "whisper $*".into()).unwrap();
+
+        for index in 0..MAX_ALIAS_TABLES {
+            shared.last_seen_mut(&username(index)).last_active =
+                now - Duration::from_secs(index as u64);
+            shared.set_aliases(&username(index), aliases.clone());
+        }
+
+        shared.set_aliases(&username(MAX_ALIAS_TABLES), aliases);
+
+        assert_eq!(shared.aliases.len(), MAX_ALIAS_TABLES);
+        assert!(shared
+            .aliases
+            .get(&username(MAX_ALIAS_TABLES - 1))
+            .is_none());
+        assert!(shared.aliases.get(&username(MAX_ALIAS_TABLES)).is_some());
+    }
 }
@@ -1,10 +1,13 @@
 use argon2::password_hash::{Error, PasswordHasher, SaltString};
 use argon2::Argon2;
-use rand::rngs::OsRng;
 use std::fmt::Display;
 
 const TRIPCODE_LENGTH: usize = 10;
 
+// Tripcodes must be the same each time a user connects with the same password,
+// so every password is hashed with the same salt.
+const TRIPCODE_SALT: &str = "cmVhbHRpbWUtY2hhdC10Yw";
+
 /// A Tripcode is generated by hashing a user provided password,
 /// and taking the last 10 characters of the hash.
 ///
@@ -25,7 +28,7 @@ impl TryFrom<String> for Tripcode {
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         let bytes = value.as_bytes();
-        let salt = SaltString::generate(&mut OsRng);
+        let salt = SaltString::from_b64(TRIPCODE_SALT)?;
 
         let argon2 = Argon2::default();
         let hash = argon2.hash_password(bytes, &salt)?.to_string();
@@ -40,3 +43,35 @@ impl TryFrom<String> for Tripcode {
         Ok(Self(last_ten_chars))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::internet::en::Password, Fake};
+
+    #[test]
+    fn generates_same_tripcode_for_same_password() {
+        let password: String = Password(8..16).fake();
+
+        let first = Tripcode::try_from(password.clone()).unwrap();
+        let second = Tripcode::try_from(password).unwrap();
+
+        assert_eq!(first.to_string(), second.to_string());
+    }
+
+    #[test]
+    fn generates_different_tripcodes_for_different_passwords() {
+        let first = Tripcode::try_from(String::from("password123")).unwrap();
+        let second = Tripcode::try_from(String::from("password124")).unwrap();
+
+        assert_ne!(first.to_string(), second.to_string());
+    }
+
+    #[test]
+    fn generates_tripcode_of_fixed_length() {
+        let password: String = Password(8..16).fake();
+        let tripcode = Tripcode::try_from(password).unwrap();
+
+        assert_eq!(tripcode.to_string().len(), TRIPCODE_LENGTH);
+    }
+}
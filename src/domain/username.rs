@@ -1,8 +1,18 @@
 use crate::errors::UsernameError;
 
-use super::{Messages, Tripcode};
-use futures::StreamExt;
+use super::Tripcode;
 use std::fmt::Display;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
+
+// The maximum length of a nickname, in characters.
+const MAX_NICKNAME_LENGTH: usize = 24;
+
+// Punctuation allowed in a nickname, in addition to letters and numbers.
+const NICKNAME_PUNCTUATION: &[char] = &['_', '-', '.'];
+
+// Nicknames that could be mistaken for messages from the server itself.
+const RESERVED_NICKNAMES: &[&str] = &["server", "system", "everyone"];
 
 /// A Username is made up of two parts, the nickname portion, and
 /// the tripcode portion.
@@ -12,7 +22,7 @@ use std::fmt::Display;
 /// some_user!uQ8unuo3Mk
 ///
 /// A Username should not be constructed directly, but rather via
-/// the `from_frame` method.
+/// the `from_credentials` method.
 ///
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Username {
@@ -21,29 +31,88 @@ pub struct Username {
 }
 
 impl Username {
-    pub async fn from_frame(messages: &mut Messages) -> Result<Self, UsernameError> {
-        // Pull the message off of the stream. The message will
-        // have the format nickname,password e.g. some_user,password123
-        let message = match messages.next().await {
-            Some(Ok(frame)) => frame.message(),
-            Some(Err(err)) => Err(UsernameError::ReadFailure(err))?,
-            _ => Err(UsernameError::NoData)?,
-        };
-
-        // Extract nickname and password from comma-separated pair
-        let (nickname, password) = message
-            .split_once(',')
-            .ok_or_else(|| UsernameError::ParseFailure)?;
-
-        // Generate tripcode from the password provided by the client
-        let tripcode = Tripcode::try_from(password.to_owned())
-            .map_err(|err| UsernameError::GenTripcodeFailure(err))?;
+    #[cfg(test)]
+    pub(crate) fn new(nickname: String, tripcode: String) -> Self {
+        Self { nickname, tripcode }
+    }
+
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    pub fn tripcode(&self) -> &str {
+        &self.tripcode
+    }
+
+    /// Constructs a Username from the nickname and password sent by a client
+    /// in its handshake, generating the tripcode from the password.
+    pub fn from_credentials(nickname: &str, password: &str) -> Result<Self, UsernameError> {
+        let tripcode =
+            Tripcode::try_from(password.to_owned()).map_err(UsernameError::GenTripcodeFailure)?;
 
         Ok(Self {
-            nickname: nickname.to_owned(),
+            nickname: Self::validate_nickname(nickname)?,
             tripcode: tripcode.to_string(),
         })
     }
+
+    /// Parses a username as it's displayed, e.g. `some_user!uQ8unuo3Mk`, such
+    /// as one the server has saved. Returns `None` if it isn't valid.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let (nickname, tripcode) = value
+            .split_once('!')
+            .filter(|(_, tripcode)| !tripcode.is_empty())?;
+
+        Some(Self {
+            nickname: Self::validate_nickname(nickname).ok()?,
+            tripcode: tripcode.to_owned(),
+        })
+    }
+
+    /// Validates a nickname, returning it in Unicode normalization form C, so
+    /// that nicknames which are written differently but look the same are equal.
+    fn validate_nickname(nickname: &str) -> Result<String, UsernameError> {
+        let nickname: String = nickname.nfc().collect();
+
+        if nickname.is_empty() {
+            return Err(UsernameError::EmptyNickname);
+        }
+
+        if nickname.chars().count() > MAX_NICKNAME_LENGTH {
+            return Err(UsernameError::NicknameTooLong(MAX_NICKNAME_LENGTH));
+        }
+
+        let invalid = nickname
+            .chars()
+            .find(|c| !c.is_alphanumeric() && !NICKNAME_PUNCTUATION.contains(c));
+
+        if let Some(c) = invalid {
+            return Err(UsernameError::InvalidCharacter(c));
+        }
+
+        let is_reserved = RESERVED_NICKNAMES
+            .iter()
+            .any(|reserved| nickname_skeleton(reserved) == nickname_skeleton(&nickname));
+
+        if is_reserved {
+            return Err(UsernameError::ReservedNickname(nickname));
+        }
+
+        Ok(nickname)
+    }
+
+    /// Whether the nickname is different to another user's, but could be
+    /// mistaken for it, e.g. `admin` and `аdmin`, where the `а` is Cyrillic.
+    pub fn is_confusable_with(&self, other: &Username) -> bool {
+        self.nickname != other.nickname
+            && nickname_skeleton(&self.nickname) == nickname_skeleton(&other.nickname)
+    }
+}
+
+/// The skeleton of a nickname, which is the same for any nicknames that
+/// look alike, ignoring case.
+pub(crate) fn nickname_skeleton(nickname: &str) -> String {
+    skeleton(&nickname.to_lowercase()).collect()
 }
 
 impl Display for Username {
@@ -51,3 +120,116 @@ impl Display for Username {
         write!(f, "{}!{}", self.nickname, self.tripcode)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    fn from_nickname(nickname: &str) -> Result<Username, UsernameError> {
+        Username::from_credentials(nickname, "password")
+    }
+
+    #[test]
+    fn parses_displayed_username() {
+        let username = from_nickname(&Word().fake::<String>()).unwrap();
+
+        assert_eq!(Username::parse(&username.to_string()), Some(username));
+        assert_eq!(Username::parse("no_tripcode!"), None);
+        assert_eq!(Username::parse("not valid!uQ8unuo3Mk"), None);
+    }
+
+    #[test]
+    fn parses_nickname_and_tripcode() {
+        let nickname: String = Word().fake();
+
+        let username = from_nickname(&nickname).unwrap();
+
+        assert_eq!(username.nickname(), nickname);
+        assert_eq!(username.tripcode().len(), 10);
+    }
+
+    #[test]
+    fn allows_unicode_letters_and_punctuation() {
+        for nickname in ["zoë", "日本語", "some_user-1.2"] {
+            assert!(from_nickname(nickname).is_ok(), "{nickname}");
+        }
+    }
+
+    #[test]
+    fn normalizes_nickname() {
+        // An `e` followed by a combining diaeresis.
+        let username = from_nickname("zoe\u{0308}").unwrap();
+
+        assert_eq!(username.nickname(), "zo\u{00EB}");
+    }
+
+    #[test]
+    fn returns_error_if_nickname_is_empty() {
+        assert!(matches!(
+            from_nickname(""),
+            Err(UsernameError::EmptyNickname)
+        ));
+    }
+
+    #[test]
+    fn returns_error_if_nickname_is_too_long() {
+        let nickname = "a".repeat(MAX_NICKNAME_LENGTH + 1);
+
+        assert!(matches!(
+            from_nickname(&nickname),
+            Err(UsernameError::NicknameTooLong(MAX_NICKNAME_LENGTH))
+        ));
+    }
+
+    #[test]
+    fn returns_error_for_invalid_characters() {
+        for (nickname, invalid) in [
+            ("some user", ' '),
+            ("line\nbreak", '\n'),
+            ("bell\u{7}", '\u{7}'),
+            ("hidden\u{200B}", '\u{200B}'),
+            ("@user", '@'),
+            ("user!trip", '!'),
+        ] {
+            assert!(
+                matches!(from_nickname(nickname), Err(UsernameError::InvalidCharacter(c)) if c == invalid),
+                "{nickname:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn returns_error_for_reserved_nicknames() {
+        // The `е` in the last nickname is Cyrillic.
+        for nickname in ["server", "System", "s\u{0435}rver"] {
+            assert!(
+                matches!(
+                    from_nickname(nickname),
+                    Err(UsernameError::ReservedNickname(_))
+                ),
+                "{nickname}"
+            );
+        }
+    }
+
+    #[test]
+    fn detects_confusable_nicknames() {
+        let admin = Username::new("admin".into(), Word().fake());
+        let cyrillic = Username::new("\u{0430}dmin".into(), Word().fake());
+        let uppercase = Username::new("Admin".into(), Word().fake());
+
+        assert!(admin.is_confusable_with(&cyrillic));
+        assert!(admin.is_confusable_with(&uppercase));
+    }
+
+    #[test]
+    fn same_or_distinct_nicknames_are_not_confusable() {
+        let admin = Username::new("admin".into(), Word().fake());
+        let other = Username::new("admin".into(), Word().fake());
+        let distinct = Username::new("admins".into(), Word().fake());
+
+        assert!(!admin.is_confusable_with(&other));
+        assert!(!admin.is_confusable_with(&distinct));
+    }
+}
@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AccountError {
+    #[error("Failed to read accounts file {0}: {1}")]
+    ReadFailure(String, std::io::Error),
+    #[error("Failed to parse accounts file: {0}")]
+    ParseFailure(#[from] toml::de::Error),
+    #[error("Failed to save accounts file {0}: {1}")]
+    WriteFailure(String, std::io::Error),
+    #[error("Failed to hash password: {0}")]
+    HashFailure(argon2::password_hash::Error),
+    #[error("Nickname {0} is already registered.")]
+    AlreadyRegistered(String),
+    #[error("Nickname {0} isn't registered.")]
+    NotRegistered(String),
+    #[error("Incorrect password for the registered nickname {0}.")]
+    IncorrectPassword(String),
+    #[error("Nickname {0} is too similar to the registered nickname {1}.")]
+    ConfusableNickname(String, String),
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AliasError {
+    #[error("Failed to read aliases file {0}: {1}")]
+    ReadFailure(String, std::io::Error),
+    #[error("Failed to parse aliases file: {0}")]
+    ParseFailure(#[from] toml::de::Error),
+    #[error("Invalid username {0} in aliases file: usernames must include a tripcode, e.g. some_user!uQ8unuo3Mk")]
+    InvalidUsername(String),
+    #[error("Failed to save aliases file {0}: {1}")]
+    WriteFailure(String, std::io::Error),
+}
@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    /// The server couldn't be reached, or the connection to it failed.
+    #[error("{0}")]
+    ConnectionFailure(#[from] std::io::Error),
+    /// The server rejected the handshake, e.g. because the password is wrong.
+    #[error("{0}")]
+    Rejected(String),
+    #[error("The server closed the connection during the handshake.")]
+    Closed,
+    #[error("The server didn't complete the handshake within {0:?}.")]
+    TimedOut(std::time::Duration),
+}
@@ -1,3 +1,5 @@
+use super::{AccountError, AliasError};
+use crate::domain::Permission;
 use thiserror::Error;
 
 const HELP_MSG: &str = "See /help for a list of all commands.";
@@ -10,8 +12,41 @@ pub enum CommandError {
     MissingName,
     #[error("Missing command argument: {0}.")]
     MissingArgument(String),
+    #[error("Unknown command option: {0}.")]
+    UnknownOption(String),
+    #[error("Unterminated quote in command.")]
+    UnterminatedQuote,
+    #[error("Invalid command argument {0}: {1}.")]
+    InvalidArgument(String, String),
     #[error("Failed to execute command: {0}.")]
     ExecutionError(String),
     #[error("Unknown command: {0}. {HELP_MSG}")]
     UnknownCommand(String),
+    #[error("Alias {0} expands to itself.")]
+    RecursiveAlias(String),
+    #[error("Alias {0} expands to a command longer than {1} bytes.")]
+    AliasTooLong(String, usize),
+    #[error("Message is too long: it must fit in {0} bytes along with its ID and your username.")]
+    MessageTooLong(usize),
+    #[error("Permission denied: this command requires the {0} permission level.")]
+    PermissionDenied(Permission),
+    #[error("{0}")]
+    AccountFailure(String),
+    #[error("{0}")]
+    AliasFailure(String),
+    /// The text of a message was blocked by a filter, or its sender is muted.
+    #[error("{0}")]
+    Filtered(String),
+}
+
+impl From<AccountError> for CommandError {
+    fn from(err: AccountError) -> Self {
+        Self::AccountFailure(err.to_string())
+    }
+}
+
+impl From<AliasError> for CommandError {
+    fn from(err: AliasError) -> Self {
+        Self::AliasFailure(err.to_string())
+    }
 }
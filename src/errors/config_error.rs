@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file {0}: {1}")]
+    ReadFailure(String, std::io::Error),
+    #[error("Failed to parse config file: {0}")]
+    ParseFailure(#[from] toml::de::Error),
+    #[error("Invalid username {0}: usernames in the config must include a tripcode, e.g. some_user!uQ8unuo3Mk")]
+    InvalidUsername(String),
+    #[error("Unknown profile {0}: profiles must be listed in the config file")]
+    UnknownProfile(String),
+    #[error("Failed to read password from {0}: {1}")]
+    PasswordReadFailure(String, std::io::Error),
+    #[error("Environment variable {0} for the password is not set")]
+    MissingEnvVar(String),
+    #[error("Password file {0} must only be accessible by its owner, e.g. chmod 600 {0}")]
+    InsecurePasswordFile(String),
+}
@@ -0,0 +1,20 @@
+use super::{AccountError, UsernameError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HandshakeError {
+    #[error("Failed to read handshake from frame: {0}")]
+    ReadFailure(#[from] std::io::Error),
+    #[error("No handshake read from frame.")]
+    NoData,
+    #[error("The handshake wasn't completed within {0:?}.")]
+    TimedOut(std::time::Duration),
+    #[error("Capabilities can only be requested once, at the start of the handshake.")]
+    RepeatedCapabilities,
+    #[error(transparent)]
+    UsernameFailure(#[from] UsernameError),
+    #[error(transparent)]
+    AccountFailure(#[from] AccountError),
+    #[error("The session can't be resumed, as the token is invalid or has expired.")]
+    InvalidResumeToken,
+}
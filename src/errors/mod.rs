@@ -1,7 +1,19 @@
+mod account_error;
+mod alias_error;
+mod client_error;
 mod command_error;
+mod config_error;
+mod handshake_error;
 mod message_error;
+mod server_error;
 mod username_error;
 
+pub use account_error::*;
+pub use alias_error::*;
+pub use client_error::*;
 pub use command_error::*;
+pub use config_error::*;
+pub use handshake_error::*;
 pub use message_error::*;
+pub use server_error::*;
 pub use username_error::*;
@@ -0,0 +1,16 @@
+use super::{AccountError, AliasError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ServerError {
+    #[error("Failed to listen on {0}: {1}")]
+    BindFailure(String, std::io::Error),
+    #[error("Failed to read the address of a listener: {0}")]
+    ListenerFailure(#[from] std::io::Error),
+    #[error("The server has no addresses or listeners to accept connections on.")]
+    NoListeners,
+    #[error(transparent)]
+    AccountFailure(#[from] AccountError),
+    #[error(transparent)]
+    AliasFailure(#[from] AliasError),
+}
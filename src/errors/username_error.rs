@@ -2,12 +2,22 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum UsernameError {
-    #[error("Failed to read username/password pair from frame: {0}")]
-    ReadFailure(#[from] std::io::Error),
-    #[error("No username/password read from frame.")]
-    NoData,
     #[error("Failed to parse username.")]
     ParseFailure,
     #[error("Failed to generate tripcode: {0}")]
     GenTripcodeFailure(argon2::password_hash::Error),
+    #[error("Nickname must not be empty.")]
+    EmptyNickname,
+    #[error("Nickname must be at most {0} characters long.")]
+    NicknameTooLong(usize),
+    #[error(
+        "Nickname must not contain {0:?}, only letters, numbers, '_', '-' and '.' are allowed."
+    )]
+    InvalidCharacter(char),
+    #[error("Nickname {0} is reserved.")]
+    ReservedNickname(String),
+    #[error("Nickname {0} is too similar to {1}, who is already online.")]
+    ConfusableNickname(String, String),
+    #[error("{0} is already connected. Resume its session, or disconnect it first.")]
+    AlreadyConnected(String),
 }
@@ -0,0 +1,5 @@
+mod normalized_text;
+mod pipeline;
+
+pub use normalized_text::*;
+pub use pipeline::*;
@@ -0,0 +1,189 @@
+use regex::Regex;
+use std::ops::Range;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_security::skeleton;
+
+/// Whether a character is invisible, and could be used to break up a word
+/// without changing how it looks, such as a zero-width space.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{061C}'
+            | '\u{115F}'..='\u{1160}'
+            | '\u{17B4}'..='\u{17B5}'
+            | '\u{180B}'..='\u{180F}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{E0000}'..='\u{E0FFF}'
+    )
+}
+
+/// Normalizes a single character, which may produce several characters,
+/// or none at all.
+fn normalize_char(c: char) -> Vec<char> {
+    if c.is_ascii() {
+        return vec![c];
+    }
+
+    if is_invisible(c) {
+        return vec![];
+    }
+
+    // Decomposing the character separates any accents, which are removed, and
+    // replaces compatibility characters, e.g. full width letters and ligatures.
+    std::iter::once(c)
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(|c| match c.is_ascii() {
+            true => vec![c],
+            false => skeleton(&c.to_string())
+                .filter(|c| !is_combining_mark(*c))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Text that has been normalized, so that filters can't be evaded by disguising
+/// characters. Invisible characters and accents are removed, and any other
+/// characters that look like ASCII characters are replaced with them, such as
+/// the Cyrillic `а`, or the full width `ａ`. ASCII characters are left as is.
+#[derive(Debug)]
+pub struct NormalizedText<'a> {
+    original: &'a str,
+    pub text: String,
+    // The byte offset of each character in `text`, along with the range of the
+    // character in the original text that it was derived from.
+    origins: Vec<(usize, Range<usize>)>,
+}
+
+impl<'a> NormalizedText<'a> {
+    pub fn new(original: &'a str) -> Self {
+        let mut text = String::new();
+        let mut origins = vec![];
+
+        for (start, c) in original.char_indices() {
+            let range = start..start + c.len_utf8();
+
+            for c in normalize_char(c) {
+                origins.push((text.len(), range.clone()));
+                text.push(c);
+            }
+        }
+
+        Self {
+            original,
+            text,
+            origins,
+        }
+    }
+
+    /// Masks each match of a pattern in the normalized text with asterisks,
+    /// returning the original text with the matches masked.
+    pub fn mask(&self, pattern: &Regex) -> String {
+        let mut masked = String::new();
+        let mut last = 0;
+
+        for found in pattern.find_iter(&self.text) {
+            let Some(range) = self.original_range(found.range()) else {
+                continue;
+            };
+
+            // Several normalized characters may come from the same original
+            // character, so matches can overlap once mapped back.
+            if range.start < last {
+                continue;
+            }
+
+            masked.push_str(&self.original[last..range.start]);
+            masked.push_str(&"*".repeat(found.as_str().chars().count()));
+            last = range.end;
+        }
+
+        masked.push_str(&self.original[last..]);
+        masked
+    }
+
+    /// Maps a range of the normalized text to the range of the original text
+    /// that it was derived from.
+    fn original_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        let first = self
+            .origins
+            .partition_point(|(offset, _)| *offset < range.start);
+        let last = self
+            .origins
+            .partition_point(|(offset, _)| *offset < range.end)
+            .checked_sub(1)?;
+
+        Some(self.origins.get(first)?.1.start..self.origins.get(last)?.1.end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn normalize(value: &str) -> String {
+        NormalizedText::new(value).text
+    }
+
+    #[test]
+    fn leaves_ascii_unchanged() {
+        assert_eq!(normalize("Hello, world! 123"), "Hello, world! 123");
+    }
+
+    #[test]
+    fn replaces_homoglyphs() {
+        // Cyrillic а, е and о, and the Greek ο.
+        assert_eq!(
+            normalize("b\u{0430}d w\u{0435}\u{043E}rd g\u{03BF}"),
+            "bad weord go"
+        );
+    }
+
+    #[test]
+    fn replaces_full_width_characters() {
+        assert_eq!(normalize("ｂａｄ"), "bad");
+    }
+
+    #[test]
+    fn removes_invisible_characters() {
+        assert_eq!(normalize("b\u{200B}a\u{200D}d\u{FEFF}"), "bad");
+    }
+
+    #[test]
+    fn removes_accents_and_combining_marks() {
+        assert_eq!(normalize("bád b\u{0336}a\u{0336}d\u{0336}"), "bad bad");
+    }
+
+    #[test]
+    fn masks_matches_in_original_text() {
+        let pattern = Regex::new("bad").unwrap();
+        let text = NormalizedText::new("so b\u{0430}\u{200B}d, ｂａｄ!");
+
+        assert_eq!(text.mask(&pattern), "so ***, ***!");
+    }
+
+    proptest! {
+        #[test]
+        fn masking_nothing_preserves_original(value in any::<String>()) {
+            let pattern = Regex::new("$^").unwrap();
+
+            prop_assert_eq!(NormalizedText::new(&value).mask(&pattern), value);
+        }
+
+        #[test]
+        fn masking_never_panics(value in any::<String>()) {
+            let pattern = Regex::new("[a-z]+").unwrap();
+
+            let _ = NormalizedText::new(&value).mask(&pattern);
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use super::NormalizedText;
+use crate::args::FromArg;
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use std::time::Duration;
+
+// How long a sender is muted for by a rule that doesn't specify a duration.
+const DEFAULT_MUTE: Duration = Duration::from_secs(5 * 60);
+
+// Matches links written with a scheme, e.g. `https://`, or starting with `www.`
+const LINK_PATTERN: &str = r"(?i)\b(?:[a-z][a-z0-9+.-]*://|www\.)\S+";
+
+/// What happens to a message that matches a filter rule.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// The message isn't sent.
+    Block,
+    /// The matching text is replaced with asterisks.
+    Mask,
+    /// The message is sent, and the sender is warned.
+    Warn,
+    /// The message isn't sent, and the sender is muted for a while.
+    Mute,
+}
+
+/// A rule that takes an action when a message matches a regular expression.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterRule {
+    pub pattern: String,
+    pub action: FilterAction,
+    /// The reason given to the sender when the rule matches.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// How long the sender is muted for by a `mute` rule, e.g. `10m`.
+    #[serde(default)]
+    pub mute_for: Option<String>,
+}
+
+/// The filters applied to messages, as written in the server config. The
+/// server has no rooms, so a single set of filters applies to every message.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    /// Words that are masked wherever they appear as a whole word.
+    pub words: Vec<String>,
+    /// Whether messages containing links are blocked.
+    pub block_links: bool,
+    /// Rules that are applied in order, after words have been masked.
+    pub rules: Vec<FilterRule>,
+}
+
+/// What should happen to a message once it has been filtered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// The message may be sent, possibly masked, and the sender is given any warnings.
+    Allow {
+        message: String,
+        warnings: Vec<String>,
+    },
+    /// The message is blocked, for the given reason.
+    Block(String),
+    /// The message is blocked, and the sender is muted for the given duration.
+    Mute(String, Duration),
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    pattern: Regex,
+    action: FilterAction,
+    reason: String,
+    mute_for: Duration,
+}
+
+/// The compiled filters, which are applied to every message before it's sent.
+/// Messages are normalized before they're matched, so that filters can't be
+/// evaded with lookalike or invisible characters.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "FilterConfig")]
+pub struct FilterPipeline {
+    config: FilterConfig,
+    words: Option<Regex>,
+    links: Option<Regex>,
+    rules: Vec<CompiledRule>,
+}
+
+impl FilterPipeline {
+    pub fn apply(&self, message: &str) -> Verdict {
+        let mut message = message.to_owned();
+        let mut warnings = vec![];
+
+        if let Some(words) = &self.words {
+            message = NormalizedText::new(&message).mask(words);
+        }
+
+        if let Some(links) = &self.links {
+            if links.is_match(&NormalizedText::new(&message).text) {
+                return Verdict::Block(String::from("Links aren't allowed"));
+            }
+        }
+
+        for rule in &self.rules {
+            let normalized = NormalizedText::new(&message);
+
+            if !rule.pattern.is_match(&normalized.text) {
+                continue;
+            }
+
+            match rule.action {
+                FilterAction::Block => return Verdict::Block(rule.reason.clone()),
+                FilterAction::Mute => return Verdict::Mute(rule.reason.clone(), rule.mute_for),
+                FilterAction::Warn => warnings.push(rule.reason.clone()),
+                FilterAction::Mask => message = normalized.mask(&rule.pattern),
+            }
+        }
+
+        Verdict::Allow { message, warnings }
+    }
+}
+
+impl PartialEq for FilterPipeline {
+    fn eq(&self, other: &Self) -> bool {
+        self.config == other.config
+    }
+}
+
+impl TryFrom<FilterConfig> for FilterPipeline {
+    type Error = String;
+
+    fn try_from(config: FilterConfig) -> Result<Self, Self::Error> {
+        // Words are normalized too, in case they're written with accents.
+        let words = (!config.words.is_empty())
+            .then(|| {
+                let words: Vec<String> = config
+                    .words
+                    .iter()
+                    .map(|word| regex::escape(&NormalizedText::new(word).text))
+                    .collect();
+
+                RegexBuilder::new(&format!(r"\b(?:{})\b", words.join("|")))
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| e.to_string())
+            })
+            .transpose()?;
+
+        let links = config
+            .block_links
+            .then(|| Regex::new(LINK_PATTERN).map_err(|e| e.to_string()))
+            .transpose()?;
+
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let pattern = Regex::new(&rule.pattern)
+                    .map_err(|e| format!("invalid filter pattern {}: {}", rule.pattern, e))?;
+
+                let mute_for = match &rule.mute_for {
+                    Some(value) => Duration::from_arg(value)?,
+                    None => DEFAULT_MUTE,
+                };
+
+                let reason = match &rule.reason {
+                    Some(reason) => reason.trim_end_matches('.').to_owned(),
+                    None => String::from("Your message matched a filter"),
+                };
+
+                Ok(CompiledRule {
+                    pattern,
+                    action: rule.action,
+                    reason,
+                    mute_for,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            config,
+            words,
+            links,
+            rules,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pipeline(config: &str) -> FilterPipeline {
+        toml::from_str(config).unwrap()
+    }
+
+    fn allowed(message: &str) -> Verdict {
+        Verdict::Allow {
+            message: message.into(),
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn allows_everything_by_default() {
+        let verdict = FilterPipeline::default().apply("hello https://example.com");
+
+        assert_eq!(verdict, allowed("hello https://example.com"));
+    }
+
+    #[test]
+    fn masks_words() {
+        let verdict = pipeline(r#"words = ["darn"]"#).apply("Darn it, darn!");
+
+        assert_eq!(verdict, allowed("**** it, ****!"));
+    }
+
+    #[test]
+    fn masks_only_whole_words() {
+        let verdict = pipeline(r#"words = ["ass"]"#).apply("first class");
+
+        assert_eq!(verdict, allowed("first class"));
+    }
+
+    #[test]
+    fn masks_words_disguised_with_homoglyphs() {
+        // The `а` is Cyrillic.
+        let verdict = pipeline(r#"words = ["darn"]"#).apply("d\u{0430}rn");
+
+        assert_eq!(verdict, allowed("****"));
+    }
+
+    #[test]
+    fn masks_words_disguised_with_invisible_characters() {
+        let verdict = pipeline(r#"words = ["darn"]"#).apply("d\u{200B}ar\u{200D}n it");
+
+        assert_eq!(verdict, allowed("**** it"));
+    }
+
+    #[test]
+    fn masks_words_disguised_with_full_width_letters_and_accents() {
+        let verdict = pipeline(r#"words = ["darn"]"#).apply("ｄａｒｎ dárn");
+
+        assert_eq!(verdict, allowed("**** ****"));
+    }
+
+    #[test]
+    fn blocks_links() {
+        let filters = pipeline("block_links = true");
+
+        for message in ["see https://example.com", "www.example.com", "ｈｔｔｐ://x"] {
+            assert_eq!(
+                filters.apply(message),
+                Verdict::Block("Links aren't allowed".into())
+            );
+        }
+
+        assert_eq!(filters.apply("a.b and c/d"), allowed("a.b and c/d"));
+    }
+
+    #[test]
+    fn applies_rule_actions() {
+        let filters = pipeline(
+            r#"
+            [[rules]]
+            pattern = "(?i)spoiler"
+            action = "warn"
+            reason = "No spoilers."
+
+            [[rules]]
+            pattern = "[0-9]{4}"
+            action = "mask"
+
+            [[rules]]
+            pattern = "buy now"
+            action = "block"
+            reason = "No advertising"
+
+            [[rules]]
+            pattern = "flood"
+            action = "mute"
+            mute_for = "10m"
+            "#,
+        );
+
+        assert_eq!(
+            filters.apply("Spoiler: pin 1234"),
+            Verdict::Allow {
+                message: "Spoiler: pin ****".into(),
+                warnings: vec!["No spoilers".into()],
+            }
+        );
+        assert_eq!(
+            filters.apply("buy now"),
+            Verdict::Block("No advertising".into())
+        );
+        assert_eq!(
+            filters.apply("fl\u{043E}od"),
+            Verdict::Mute(
+                "Your message matched a filter".into(),
+                Duration::from_secs(600)
+            )
+        );
+    }
+
+    #[test]
+    fn stops_at_first_blocking_rule() {
+        let filters = pipeline(
+            r#"
+            rules = [
+                { pattern = "a", action = "block", reason = "first" },
+                { pattern = "a", action = "mute", reason = "second" },
+            ]
+            "#,
+        );
+
+        assert_eq!(filters.apply("a"), Verdict::Block("first".into()));
+    }
+
+    #[test]
+    fn returns_error_for_invalid_pattern() {
+        let filters =
+            toml::from_str::<FilterPipeline>(r#"rules = [{ pattern = "(", action = "block" }]"#);
+
+        assert!(filters.is_err());
+    }
+
+    #[test]
+    fn returns_error_for_invalid_mute_duration() {
+        let filters = toml::from_str::<FilterPipeline>(
+            r#"rules = [{ pattern = "a", action = "mute", mute_for = "soon" }]"#,
+        );
+
+        assert!(filters.is_err());
+    }
+}
@@ -1,8 +1,43 @@
-#[derive(Debug, PartialEq, Clone)]
+use serde::Serialize;
+
+/// A frame sent between the server and a client. Frames are serialized to
+/// JSON with their kind as the `type`, and their payload as the `text`.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(tag = "type", content = "text", rename_all = "snake_case")]
 pub enum Frame {
     Message(String),
+    /// A chat message that mentions the recipient.
+    Mention(String),
     ServerMessage(String),
+    /// An action a user describes with `/me`, such as `alice!a1 is waving`.
+    /// Only sent to clients with the actions capability.
+    Action(String),
     PrivateMessage(String),
+    /// A reply to another message. The payload holds a quote of the parent
+    /// message and the reply itself, separated by a newline.
+    Reply(String),
+    /// A reply that mentions the recipient, with the same payload as a
+    /// `Frame::Reply`.
+    ReplyMention(String),
+    Reaction(String),
+    /// Sent by a client to negotiate its capabilities during the handshake,
+    /// and by the server in response with the capabilities that are enabled.
+    Capabilities(String),
+    TypingStart(String),
+    TypingStop(String),
+    /// Sent by a client with the ID of the last message it has read.
+    ReadMarker(String),
+    /// Sent by the server after the handshake with a token that can be used to
+    /// resume the session if the connection drops, and by a client in place of
+    /// its nickname/password pair to resume a session.
+    Resume(String),
+    /// Sent by the server with the list of online users, or a user that has
+    /// joined or left. See `Presence` for the format.
+    Presence(String),
+    /// Sent by the server to a client with the acks capability, once it has
+    /// accepted a message or command from the client. The payload holds the
+    /// ID of the message, or is empty for a command.
+    Ack(String),
     Error(String),
 }
 
@@ -10,8 +45,20 @@ impl Frame {
     pub fn frame_format(self) -> (u8, String, usize) {
         let (prefix, message) = match self {
             Frame::Message(msg) => (b'+', msg),
+            Frame::Mention(msg) => (b'@', msg),
             Frame::ServerMessage(msg) => (b'$', msg),
+            Frame::Action(msg) => (b'!', msg),
             Frame::PrivateMessage(msg) => (b'&', msg),
+            Frame::Reply(msg) => (b'>', msg),
+            Frame::ReplyMention(msg) => (b'<', msg),
+            Frame::Reaction(msg) => (b':', msg),
+            Frame::Capabilities(msg) => (b'%', msg),
+            Frame::TypingStart(msg) => (b'{', msg),
+            Frame::TypingStop(msg) => (b'}', msg),
+            Frame::ReadMarker(msg) => (b'^', msg),
+            Frame::Resume(msg) => (b'~', msg),
+            Frame::Presence(msg) => (b'*', msg),
+            Frame::Ack(msg) => (b'#', msg),
             Frame::Error(msg) => (b'-', msg),
         };
 
@@ -23,8 +70,20 @@ impl Frame {
     pub fn message(self) -> String {
         match self {
             Frame::Message(msg) => msg,
+            Frame::Mention(msg) => msg,
             Frame::ServerMessage(msg) => msg,
+            Frame::Action(msg) => msg,
             Frame::PrivateMessage(msg) => msg,
+            Frame::Reply(msg) => msg,
+            Frame::ReplyMention(msg) => msg,
+            Frame::Reaction(msg) => msg,
+            Frame::Capabilities(msg) => msg,
+            Frame::TypingStart(msg) => msg,
+            Frame::TypingStop(msg) => msg,
+            Frame::ReadMarker(msg) => msg,
+            Frame::Resume(msg) => msg,
+            Frame::Presence(msg) => msg,
+            Frame::Ack(msg) => msg,
             Frame::Error(msg) => msg,
         }
     }
@@ -34,12 +93,24 @@ impl Frame {
 
         Ok(match prefix {
             '+' => Self::Message(message),
+            '@' => Self::Mention(message),
             '$' => Self::ServerMessage(message),
+            '!' => Self::Action(message),
             '&' => Self::PrivateMessage(message),
+            '>' => Self::Reply(message),
+            '<' => Self::ReplyMention(message),
+            ':' => Self::Reaction(message),
+            '%' => Self::Capabilities(message),
+            '{' => Self::TypingStart(message),
+            '}' => Self::TypingStop(message),
+            '^' => Self::ReadMarker(message),
+            '~' => Self::Resume(message),
+            '*' => Self::Presence(message),
+            '#' => Self::Ack(message),
             '-' => Self::Error(message),
             _ => Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                format!("Invalid message frame"),
+                "Invalid message frame".to_string(),
             ))?,
         })
     }
@@ -61,6 +132,17 @@ mod test {
         assert_eq!(format, (b'+', message, length));
     }
 
+    #[test]
+    fn frame_format_returns_mention_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Mention(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'@', message, length));
+    }
+
     #[test]
     fn frame_format_returns_server_message_format() {
         let message = Word().fake::<String>();
@@ -72,6 +154,17 @@ mod test {
         assert_eq!(format, (b'$', message, length));
     }
 
+    #[test]
+    fn frame_format_returns_action_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Action(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'!', message, length));
+    }
+
     #[test]
     fn frame_format_returns_private_message_format() {
         let message = Word().fake::<String>();
@@ -83,6 +176,116 @@ mod test {
         assert_eq!(format, (b'&', message, length));
     }
 
+    #[test]
+    fn frame_format_returns_reply_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Reply(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'>', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_reply_mention_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::ReplyMention(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'<', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_reaction_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Reaction(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b':', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_capabilities_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Capabilities(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'%', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_typing_start_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::TypingStart(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'{', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_typing_stop_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::TypingStop(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'}', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_read_marker_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::ReadMarker(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'^', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_resume_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Resume(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'~', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_presence_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Presence(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'*', message, length));
+    }
+
+    #[test]
+    fn frame_format_returns_ack_format() {
+        let message = Word().fake::<String>();
+        let length = message.len();
+
+        let frame = Frame::Ack(message.clone());
+        let format = frame.frame_format();
+
+        assert_eq!(format, (b'#', message, length));
+    }
+
     #[test]
     fn frame_format_returns_error_format() {
         let message = Word().fake::<String>();
@@ -99,13 +302,37 @@ mod test {
         let message = Word().fake::<String>();
 
         let message_frame = Frame::Message(message.clone());
+        let mention_frame = Frame::Mention(message.clone());
         let server_message_frame = Frame::ServerMessage(message.clone());
+        let action_frame = Frame::Action(message.clone());
         let private_message_frame = Frame::PrivateMessage(message.clone());
+        let reply_frame = Frame::Reply(message.clone());
+        let reply_mention_frame = Frame::ReplyMention(message.clone());
+        let reaction_frame = Frame::Reaction(message.clone());
+        let capabilities_frame = Frame::Capabilities(message.clone());
+        let typing_start_frame = Frame::TypingStart(message.clone());
+        let typing_stop_frame = Frame::TypingStop(message.clone());
+        let read_marker_frame = Frame::ReadMarker(message.clone());
+        let resume_frame = Frame::Resume(message.clone());
+        let presence_frame = Frame::Presence(message.clone());
+        let ack_frame = Frame::Ack(message.clone());
         let error_frame = Frame::Error(message.clone());
 
         assert_eq!(message_frame.message(), message);
+        assert_eq!(mention_frame.message(), message);
         assert_eq!(server_message_frame.message(), message);
+        assert_eq!(action_frame.message(), message);
         assert_eq!(private_message_frame.message(), message);
+        assert_eq!(reply_frame.message(), message);
+        assert_eq!(reply_mention_frame.message(), message);
+        assert_eq!(reaction_frame.message(), message);
+        assert_eq!(capabilities_frame.message(), message);
+        assert_eq!(typing_start_frame.message(), message);
+        assert_eq!(typing_stop_frame.message(), message);
+        assert_eq!(read_marker_frame.message(), message);
+        assert_eq!(resume_frame.message(), message);
+        assert_eq!(presence_frame.message(), message);
+        assert_eq!(ack_frame.message(), message);
         assert_eq!(error_frame.message(), message);
     }
 
@@ -117,6 +344,14 @@ mod test {
         assert_eq!(frame, Frame::Message(message));
     }
 
+    #[test]
+    fn try_from_prefix_retrieves_mention() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('@', &message).unwrap();
+
+        assert_eq!(frame, Frame::Mention(message));
+    }
+
     #[test]
     fn try_from_prefix_retrieves_server_message() {
         let message = Word().fake::<String>();
@@ -125,6 +360,14 @@ mod test {
         assert_eq!(frame, Frame::ServerMessage(message));
     }
 
+    #[test]
+    fn try_from_prefix_retrieves_action() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('!', &message).unwrap();
+
+        assert_eq!(frame, Frame::Action(message));
+    }
+
     #[test]
     fn try_from_prefix_retrieves_private_message() {
         let message = Word().fake::<String>();
@@ -133,6 +376,96 @@ mod test {
         assert_eq!(frame, Frame::PrivateMessage(message));
     }
 
+    #[test]
+    fn try_from_prefix_retrieves_reply() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('>', &message).unwrap();
+
+        assert_eq!(frame, Frame::Reply(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_reply_mention() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('<', &message).unwrap();
+
+        assert_eq!(frame, Frame::ReplyMention(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_reaction() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix(':', &message).unwrap();
+
+        assert_eq!(frame, Frame::Reaction(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_capabilities() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('%', &message).unwrap();
+
+        assert_eq!(frame, Frame::Capabilities(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_typing_start() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('{', &message).unwrap();
+
+        assert_eq!(frame, Frame::TypingStart(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_typing_stop() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('}', &message).unwrap();
+
+        assert_eq!(frame, Frame::TypingStop(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_read_marker() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('^', &message).unwrap();
+
+        assert_eq!(frame, Frame::ReadMarker(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_resume() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('~', &message).unwrap();
+
+        assert_eq!(frame, Frame::Resume(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_presence() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('*', &message).unwrap();
+
+        assert_eq!(frame, Frame::Presence(message));
+    }
+
+    #[test]
+    fn try_from_prefix_retrieves_ack() {
+        let message = Word().fake::<String>();
+        let frame = Frame::try_from_prefix('#', &message).unwrap();
+
+        assert_eq!(frame, Frame::Ack(message));
+    }
+
+    #[test]
+    fn serializes_to_json_with_type_and_text() {
+        let frame = Frame::ServerMessage("hello".into());
+
+        assert_eq!(
+            serde_json::to_string(&frame).unwrap(),
+            r#"{"type":"server_message","text":"hello"}"#
+        );
+    }
+
     #[test]
     fn try_from_prefix_retrieves_error() {
         let message = Word().fake::<String>();
@@ -1,7 +1,17 @@
+// Allows code generated by the derive macros to refer to this crate by name,
+// both from within the crate and from its dependents.
+extern crate self as realtime_chat;
+
+pub mod args;
+pub mod client;
 pub mod codec;
 pub mod commands;
+pub mod config;
 pub mod domain;
 pub mod errors;
+pub mod filters;
 pub mod frame;
+pub mod scripting;
+pub mod server;
 pub mod traits;
 pub mod utils;
@@ -0,0 +1,566 @@
+use crate::{
+    args::Args,
+    commands::CommandInfo,
+    domain::{Mention, Permission, PluginContext, PluginEvent, Username},
+    errors::CommandError,
+    frame::Frame,
+    traits::Plugin,
+    utils::run_blocking,
+};
+use async_trait::async_trait;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Scope, AST};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, RwLock},
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::{sync::watch, task::JoinHandle};
+
+// How often the script directory is checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+// Maximum number of messages that a script may send from a single call.
+const MAX_ACTIONS: usize = 20;
+
+// Prefix of the functions that define commands, e.g. `command_hello` for `/hello`.
+const COMMAND_PREFIX: &str = "command_";
+
+// Maximum number of distinct command names, usages and descriptions that are
+// kept for commands defined by scripts, as they're never freed.
+const MAX_INTERNED: usize = 4096;
+
+/// Something that a script asked to do, carried out once the script returns.
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    Say(String),
+    Whisper(String, String),
+}
+
+#[derive(Debug)]
+struct Script {
+    ast: Arc<AST>,
+    modified: SystemTime,
+}
+
+impl Script {
+    /// The names of the commands that the script defines.
+    fn commands(&self) -> Vec<String> {
+        self.ast
+            .iter_functions()
+            .filter(|function| function.params.len() == 2)
+            .filter_map(|function| function.name.strip_prefix(COMMAND_PREFIX))
+            .map(str::to_owned)
+            .collect()
+    }
+
+    fn defines(&self, name: &str, params: usize) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|function| function.name == name && function.params.len() == params)
+    }
+}
+
+type Scripts = Arc<RwLock<HashMap<String, Script>>>;
+
+/// Runs the Rhai scripts (`*.rhai`) in a directory as a plugin. Scripts are
+/// reloaded when they change, and can define any of the following functions:
+///
+/// - `command_<name>(sender, args)`: runs `/<name>`, with the arguments as an array.
+/// - `on_message(author, message)`: called when a message is sent to the chat.
+/// - `on_join(username)` and `on_leave(username)`: called when a user joins or leaves.
+///
+/// Scripts can call `say(message)` to send a message to the chat,
+/// `whisper(username, message)` to send a message to a single user, and
+/// `online()` to list the users that are connected. An error thrown by a
+/// command, or a command that runs for too long, is reported to the sender.
+pub struct ScriptPlugin {
+    directory: PathBuf,
+    timeout: Duration,
+    scripts: Scripts,
+}
+
+impl ScriptPlugin {
+    /// Loads the scripts in a directory, each of which may run for up to the
+    /// provided timeout whenever it is called.
+    pub fn new(directory: PathBuf, timeout: Duration) -> Self {
+        let plugin = Self {
+            directory,
+            timeout,
+            scripts: Scripts::default(),
+        };
+
+        plugin.reload();
+        plugin
+    }
+
+    /// Reloads any scripts that have been added, changed or removed.
+    pub fn reload(&self) {
+        reload(&self.directory, &self.scripts);
+    }
+
+    /// Periodically reloads the scripts in the background, until the shutdown
+    /// signal is sent or its sender is dropped.
+    pub fn watch(&self, mut shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        let directory = self.directory.clone();
+        let scripts = self.scripts.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        // Reading and compiling scripts blocks, so it's kept
+                        // off of the async runtime.
+                        let (directory, scripts) = (directory.clone(), scripts.clone());
+                        run_blocking(move || reload(&directory, &scripts)).await;
+                    }
+                    _ = shutdown.changed() => return,
+                }
+            }
+        })
+    }
+
+    /// Finds every script that defines a function.
+    fn find(&self, function: &str, params: usize) -> Vec<(String, Arc<AST>)> {
+        let scripts = self.scripts.read().unwrap();
+
+        scripts
+            .iter()
+            .filter(|(_, script)| script.defines(function, params))
+            .map(|(name, script)| (name.clone(), script.ast.clone()))
+            .collect()
+    }
+
+    /// Calls a function in a script, and then carries out what it asked to do.
+    async fn run(
+        &self,
+        ctx: &PluginContext,
+        script: String,
+        ast: Arc<AST>,
+        function: String,
+        args: Vec<Dynamic>,
+    ) -> Result<(), String> {
+        let online = ctx.online().await;
+        let usernames = online.iter().map(Username::to_string).collect();
+        let timeout = self.timeout;
+        let name = script.clone();
+
+        // Scripts are run synchronously, so keep them off of the async runtime.
+        let actions = tokio::task::spawn_blocking(move || {
+            call(&ast, &name, &function, args, usernames, timeout)
+        })
+        .await
+        .map_err(|_| String::from("script failed unexpectedly"))??;
+
+        for action in actions {
+            match action {
+                Action::Say(message) => {
+                    let frame = Frame::ServerMessage(format!("[{}] {}", script, message));
+                    ctx.broadcast(frame).await;
+                }
+                Action::Whisper(username, message) => {
+                    let username = username.strip_prefix('@').unwrap_or(&username);
+                    let Some(mention) = Mention::parse(username) else {
+                        continue;
+                    };
+
+                    let frame = Frame::ServerMessage(format!("[{}] {}", script, message));
+
+                    for username in online.iter().filter(|user| mention.matches(user)) {
+                        ctx.send_to(username, frame.clone()).await;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Plugin for ScriptPlugin {
+    fn name(&self) -> &str {
+        "scripts"
+    }
+
+    fn commands(&self) -> Vec<CommandInfo> {
+        let scripts = self.scripts.read().unwrap();
+
+        let mut commands: Vec<CommandInfo> = scripts
+            .iter()
+            .flat_map(|(script, definition)| {
+                definition.commands().into_iter().filter_map(move |name| {
+                    Some(CommandInfo {
+                        usage: intern(format!("/{} [args...]", name))?,
+                        about: intern(format!("Provided by the {} script.", script))?,
+                        name: intern(name)?,
+                        aliases: &[],
+                        permission: Permission::User,
+                    })
+                })
+            })
+            .collect();
+
+        commands.sort_by_key(|command| command.name);
+        commands
+    }
+
+    async fn on_command(
+        &self,
+        ctx: &PluginContext,
+        sender: &Username,
+        name: &str,
+        args: Args,
+    ) -> Result<(), CommandError> {
+        let function = format!("{}{}", COMMAND_PREFIX, name);
+
+        let (script, ast) = self
+            .find(&function, 2)
+            .into_iter()
+            .next()
+            .ok_or_else(|| CommandError::UnknownCommand(name.into()))?;
+
+        let values: Array = args.into_values().into_iter().map(Dynamic::from).collect();
+        let args = vec![Dynamic::from(sender.to_string()), Dynamic::from(values)];
+
+        self.run(ctx, script, ast, function, args)
+            .await
+            .map_err(CommandError::ExecutionError)
+    }
+
+    async fn on_event(&self, ctx: &PluginContext, event: &PluginEvent) {
+        let (function, args) = match event {
+            PluginEvent::Message(entry) => (
+                "on_message",
+                vec![
+                    Dynamic::from(entry.author.to_string()),
+                    Dynamic::from(entry.message.clone()),
+                ],
+            ),
+            PluginEvent::Join(username) => ("on_join", vec![Dynamic::from(username.to_string())]),
+            PluginEvent::Leave(username) => ("on_leave", vec![Dynamic::from(username.to_string())]),
+        };
+
+        for (script, ast) in self.find(function, args.len()) {
+            let result = self
+                .run(ctx, script.clone(), ast, function.into(), args.clone())
+                .await;
+
+            if let Err(err) = result {
+                tracing::warn!("Script {} failed in {}: {}", script, function, err);
+            }
+        }
+    }
+}
+
+/// An engine with the limits that every script runs under.
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+
+    engine
+        .set_max_string_size(64 * 1024)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000)
+        .disable_symbol("eval");
+
+    engine
+}
+
+/// Calls a function in a script, returning what the script asked to do.
+fn call(
+    ast: &AST,
+    script: &str,
+    function: &str,
+    args: Vec<Dynamic>,
+    online: Vec<String>,
+    timeout: Duration,
+) -> Result<Vec<Action>, String> {
+    let actions = Arc::new(Mutex::new(vec![]));
+    let mut engine = engine();
+
+    let deadline = Instant::now() + timeout;
+    engine.on_progress(move |_| (Instant::now() >= deadline).then_some(Dynamic::UNIT));
+
+    let name = script.to_owned();
+    engine.on_print(move |text| tracing::info!("[{}] {}", name, text));
+
+    let push = {
+        let actions = actions.clone();
+
+        move |action: Action| -> Result<(), Box<EvalAltResult>> {
+            let mut actions = actions.lock().unwrap();
+
+            if actions.len() >= MAX_ACTIONS {
+                return Err(format!("scripts can't send more than {MAX_ACTIONS} messages").into());
+            }
+
+            actions.push(action);
+
+            Ok(())
+        }
+    };
+
+    let say = push.clone();
+    engine.register_fn("say", move |message: &str| say(Action::Say(message.into())));
+    engine.register_fn("whisper", move |username: &str, message: &str| {
+        push(Action::Whisper(username.into(), message.into()))
+    });
+    engine.register_fn("online", move || {
+        online.iter().cloned().map(Dynamic::from).collect::<Array>()
+    });
+
+    // Only call the function, rather than re-running the whole script. The
+    // value returned by the function is ignored.
+    let options = CallFnOptions::new().eval_ast(false);
+
+    let _: Dynamic = engine
+        .call_fn_with_options(options, &mut Scope::new(), ast, function, args)
+        .map_err(|err| describe(&err, timeout))?;
+
+    let actions = actions.lock().unwrap().drain(..).collect();
+
+    Ok(actions)
+}
+
+/// Describes an error from a script, as it is reported to users.
+fn describe(err: &EvalAltResult, timeout: Duration) -> String {
+    match err {
+        EvalAltResult::ErrorInFunctionCall(_, _, err, _) => describe(err, timeout),
+        EvalAltResult::ErrorTerminated(..) => {
+            format!("script ran for longer than {}ms", timeout.as_millis())
+        }
+        // Errors thrown by the script itself are reported as they are.
+        EvalAltResult::ErrorRuntime(value, _) => value.to_string(),
+        err => err.to_string(),
+    }
+}
+
+/// Loads any scripts that have been added or changed, and forgets those that
+/// have been removed. A script that fails to compile keeps its previous version.
+fn reload(directory: &Path, scripts: &Scripts) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::error!("Failed to read scripts from {}: {}", directory.display(), e);
+            return;
+        }
+    };
+
+    let paths = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "rhai")
+        });
+
+    let mut found = HashSet::new();
+
+    for path in paths {
+        let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        let Ok(modified) = fs::metadata(&path).and_then(|metadata| metadata.modified()) else {
+            continue;
+        };
+
+        found.insert(name.to_owned());
+
+        let previous = match scripts.read().unwrap().get(name) {
+            Some(script) if script.modified == modified => continue,
+            Some(script) => Some(script.ast.clone()),
+            None => None,
+        };
+
+        let ast = match engine().compile_file(path.clone()) {
+            Ok(ast) => {
+                tracing::info!("Loaded script {}", path.display());
+                Arc::new(ast)
+            }
+            Err(e) => {
+                tracing::error!("Failed to load script {}: {}", path.display(), e);
+                previous.unwrap_or_default()
+            }
+        };
+
+        let script = Script { ast, modified };
+        scripts.write().unwrap().insert(name.to_owned(), script);
+    }
+
+    scripts.write().unwrap().retain(|name, _| {
+        let exists = found.contains(name);
+
+        if !exists {
+            tracing::info!("Unloaded script {}", name);
+        }
+
+        exists
+    });
+}
+
+/// Command details are static, so the details of commands defined by scripts
+/// are leaked. Each distinct value is only leaked once, so reloading a script
+/// doesn't leak it again, and at most `MAX_INTERNED` values are leaked in
+/// total. Returns `None` once that many have been leaked.
+fn intern(value: String) -> Option<&'static str> {
+    static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut interned = INTERNED.get_or_init(Default::default).lock().unwrap();
+
+    intern_into(&mut interned, value, MAX_INTERNED)
+}
+
+fn intern_into(
+    interned: &mut HashSet<&'static str>,
+    value: String,
+    limit: usize,
+) -> Option<&'static str> {
+    if let Some(value) = interned.get(value.as_str()) {
+        return Some(value);
+    }
+
+    if interned.len() >= limit {
+        return None;
+    }
+
+    let value: &'static str = Box::leak(value.into_boxed_str());
+    interned.insert(value);
+
+    if interned.len() == limit {
+        tracing::warn!(
+            "Scripts have defined {} distinct command details, so commands with new names will be ignored until the server restarts",
+            limit
+        );
+    }
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::temp_path;
+    use fake::{faker::lorem::en::Word, Fake};
+
+    fn run(source: &str, function: &str, args: Vec<Dynamic>) -> Result<Vec<Action>, String> {
+        let ast = engine().compile(source).unwrap();
+        let online = vec![String::from("some_user!uQ8unuo3Mk")];
+
+        call(
+            &ast,
+            "test",
+            function,
+            args,
+            online,
+            Duration::from_millis(50),
+        )
+    }
+
+    #[test]
+    fn calls_command_with_sender_and_args() {
+        let source = r#"fn command_greet(sender, args) { say(`${sender} greets ${args[0]}`); }"#;
+        let args = vec![
+            Dynamic::from(String::from("alice")),
+            Dynamic::from(vec![Dynamic::from(String::from("bob"))]),
+        ];
+
+        let actions = run(source, "command_greet", args);
+
+        assert_eq!(actions, Ok(vec![Action::Say("alice greets bob".into())]));
+    }
+
+    #[test]
+    fn whispers_to_online_users() {
+        let source = r#"fn on_join(username) { for user in online() { whisper(user, "hi"); } }"#;
+
+        let actions = run(source, "on_join", vec![Dynamic::from(String::new())]);
+
+        let expected = Action::Whisper("some_user!uQ8unuo3Mk".into(), "hi".into());
+        assert_eq!(actions, Ok(vec![expected]));
+    }
+
+    #[test]
+    fn reports_thrown_errors() {
+        let message: String = Word().fake();
+        let source = format!(r#"fn on_join(username) {{ throw "{message}"; }}"#);
+
+        let actions = run(&source, "on_join", vec![Dynamic::from(String::new())]);
+
+        assert_eq!(actions, Err(message));
+    }
+
+    #[test]
+    fn stops_scripts_that_run_too_long() {
+        let source = "fn on_join(username) { loop { } }";
+
+        let actions = run(source, "on_join", vec![Dynamic::from(String::new())]);
+
+        assert_eq!(actions, Err("script ran for longer than 50ms".into()));
+    }
+
+    #[test]
+    fn limits_messages_sent_by_a_single_call() {
+        let source = r#"fn on_join(username) { loop { say("spam"); } }"#;
+
+        let actions = run(source, "on_join", vec![Dynamic::from(String::new())]);
+
+        assert!(actions.is_err());
+    }
+
+    #[test]
+    fn disables_eval() {
+        assert!(engine().compile(r#"fn f() { eval("1") }"#).is_err());
+    }
+
+    #[test]
+    fn loads_commands_from_directory_and_unloads_removed_scripts() {
+        let directory = temp_path("scripts");
+        let path = directory.join("dice.rhai");
+
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(&path, "fn command_roll(sender, args) { say(\"4\"); }").unwrap();
+
+        let plugin = ScriptPlugin::new(directory.clone(), Duration::from_millis(50));
+        let names: Vec<&str> = plugin.commands().iter().map(|c| c.name).collect();
+
+        assert_eq!(names, ["roll"]);
+
+        fs::remove_file(&path).unwrap();
+        plugin.reload();
+
+        assert!(plugin.commands().is_empty());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stops_watching_directory_on_shutdown() {
+        let directory = temp_path("scripts");
+        let plugin = ScriptPlugin::new(directory, Duration::from_millis(50));
+        let (shutdown, signal) = watch::channel(false);
+
+        let watcher = plugin.watch(signal);
+        shutdown.send(true).unwrap();
+
+        assert!(tokio::time::timeout(Duration::from_secs(1), watcher)
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn interns_each_value_once_up_to_limit() {
+        let mut interned = HashSet::new();
+
+        let first = intern_into(&mut interned, "roll".into(), 2).unwrap();
+        let again = intern_into(&mut interned, "roll".into(), 2).unwrap();
+
+        assert!(std::ptr::eq(first, again));
+        assert!(intern_into(&mut interned, "flip".into(), 2).is_some());
+        assert_eq!(intern_into(&mut interned, "draw".into(), 2), None);
+        assert_eq!(intern_into(&mut interned, "roll".into(), 2), Some("roll"));
+    }
+}
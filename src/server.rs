@@ -0,0 +1,379 @@
+use crate::{
+    config::ServerConfig,
+    domain::{
+        Accounts, AliasStore, Connection, PluginContext, PluginEvent, Plugins, Sessions, Shared,
+        State,
+    },
+    errors::ServerError,
+    scripting::ScriptPlugin,
+    traits::Plugin,
+};
+use async_trait::async_trait;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{watch, Mutex},
+    task::{JoinHandle, JoinSet},
+    time::Instant,
+};
+
+/// Builds a `ChatServer`, from a `ServerConfig` and anything else it needs
+/// that can't be described in a config file, such as plugins. For example:
+///
+/// ```no_run
+/// # async fn run() -> Result<(), realtime_chat::errors::ServerError> {
+/// use realtime_chat::server::ChatServer;
+///
+/// let server = ChatServer::builder().bind("127.0.0.1:0").start().await?;
+/// println!("Listening on {}", server.local_addr());
+///
+/// server.shutdown().await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct ChatServerBuilder {
+    addresses: Vec<String>,
+    listeners: Vec<TcpListener>,
+    config: ServerConfig,
+    accounts: Option<Accounts>,
+    plugins: Plugins,
+}
+
+/// A running chat server, which accepts connections in the background until
+/// it's shut down or dropped.
+pub struct ChatServer {
+    state: State,
+    local_addrs: Vec<SocketAddr>,
+    shutdown: watch::Sender<bool>,
+    tasks: Vec<JoinHandle<()>>,
+    accepted: Arc<AtomicUsize>,
+    started: Instant,
+}
+
+/// A snapshot of what's happening on a server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerStats {
+    /// The users in the chat, including those whose sessions are detached.
+    pub online: usize,
+    /// Sessions waiting to be resumed after their connection dropped.
+    pub detached_sessions: usize,
+    /// Every connection accepted, including those that failed the handshake.
+    pub connections: usize,
+    /// Messages sent to the chat, not including commands or whispers.
+    pub messages: u64,
+    pub accounts: usize,
+    pub uptime: Duration,
+}
+
+/// A plugin that calls a function for each event.
+struct Hook<F>(F);
+
+impl ChatServerBuilder {
+    /// Listens on an address once started. Use port 0 to listen on an
+    /// ephemeral port, which can be found with `ChatServer::local_addr`.
+    pub fn bind(mut self, address: impl Into<String>) -> Self {
+        self.addresses.push(address.into());
+        self
+    }
+
+    /// Accepts connections from a listener that's already bound.
+    pub fn listener(mut self, listener: TcpListener) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    pub fn config(mut self, config: ServerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Stores registered accounts in the provided accounts, rather than those
+    /// loaded from the config's accounts path.
+    pub fn accounts(mut self, accounts: Accounts) -> Self {
+        self.accounts = Some(accounts);
+        self
+    }
+
+    pub fn plugin(mut self, plugin: impl Plugin + 'static) -> Self {
+        self.plugins.register(plugin);
+        self
+    }
+
+    /// Calls a function for each message sent, and each user joining or
+    /// leaving the chat. Hooks are run as plugins, so they don't hold up the
+    /// server.
+    pub fn hook(self, hook: impl Fn(&PluginEvent) + Send + Sync + 'static) -> Self {
+        self.plugin(Hook(hook))
+    }
+
+    /// Binds the server's addresses, and starts accepting connections on each
+    /// of them in the background.
+    pub async fn start(self) -> Result<ChatServer, ServerError> {
+        let config = self.config;
+
+        let accounts = match (self.accounts, config.accounts.path) {
+            (Some(accounts), _) => accounts,
+            (None, Some(path)) => Accounts::load(path)?,
+            (None, None) => Accounts::default(),
+        };
+
+        let aliases = match config.aliases.path {
+            Some(path) => AliasStore::load(path)?,
+            None => AliasStore::default(),
+        };
+
+        let mut shared = Shared {
+            permissions: config.permissions,
+            filters: config.filters,
+            accounts,
+            aliases,
+            sessions: Sessions::new(config.sessions.grace_period()),
+            plugins: self.plugins,
+            ..Shared::new()
+        };
+
+        let (shutdown, signal) = watch::channel(false);
+        let mut tasks = vec![];
+
+        if let Some(directory) = &config.scripts.directory {
+            let scripts = ScriptPlugin::new(directory.clone(), config.scripts.timeout());
+            tasks.push(scripts.watch(signal.clone()));
+            shared.plugins.register(scripts);
+        }
+
+        let mut listeners = self.listeners;
+
+        for address in self.addresses {
+            match TcpListener::bind(&address).await {
+                Ok(listener) => listeners.push(listener),
+                Err(e) => return Err(ServerError::BindFailure(address, e)),
+            }
+        }
+
+        if listeners.is_empty() {
+            return Err(ServerError::NoListeners);
+        }
+
+        let state = Arc::new(Mutex::new(shared));
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let mut local_addrs = vec![];
+
+        for listener in listeners {
+            let addr = listener.local_addr()?;
+            tracing::info!("Server listening on {}", addr);
+
+            local_addrs.push(addr);
+            tasks.push(tokio::spawn(accept(
+                listener,
+                state.clone(),
+                signal.clone(),
+                accepted.clone(),
+            )));
+        }
+
+        Ok(ChatServer {
+            state,
+            local_addrs,
+            shutdown,
+            tasks,
+            accepted,
+            started: Instant::now(),
+        })
+    }
+}
+
+impl ChatServer {
+    pub fn builder() -> ChatServerBuilder {
+        ChatServerBuilder::default()
+    }
+
+    /// The address of the first listener, e.g. to find the ephemeral port it
+    /// was bound to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addrs[0]
+    }
+
+    /// The addresses of every listener, in the order they were added.
+    pub fn local_addrs(&self) -> &[SocketAddr] {
+        &self.local_addrs
+    }
+
+    /// The server's shared state, e.g. to inspect it in tests.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub async fn stats(&self) -> ServerStats {
+        let state = self.state.lock().await;
+
+        ServerStats {
+            online: state.peers.len(),
+            detached_sessions: state.sessions.len(),
+            connections: self.accepted.load(Ordering::Relaxed),
+            messages: state.history.latest_id().unwrap_or_default(),
+            accounts: state.accounts.len(),
+            uptime: self.started.elapsed(),
+        }
+    }
+
+    /// Stops accepting connections, disconnects every client and discards the
+    /// sessions of those whose connections dropped, waiting until every task
+    /// running in the background has finished.
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+
+        for task in self.tasks {
+            let _ = task.await;
+        }
+
+        // Sessions are only detached by connections, which have all closed.
+        let mut sessions = self.state.lock().await.sessions.close();
+        while sessions.join_next().await.is_some() {}
+    }
+}
+
+#[async_trait]
+impl<F: Fn(&PluginEvent) + Send + Sync> Plugin for Hook<F> {
+    fn name(&self) -> &str {
+        "hook"
+    }
+
+    async fn on_event(&self, _ctx: &PluginContext, event: &PluginEvent) {
+        (self.0)(event);
+    }
+}
+
+/// Accepts connections from clients until the server is shut down, handling
+/// each client in its own task. Every client is disconnected once the server
+/// is shut down, or its handle is dropped.
+async fn accept(
+    listener: TcpListener,
+    state: State,
+    mut shutdown: watch::Receiver<bool>,
+    accepted: Arc<AtomicUsize>,
+) {
+    let mut connections = JoinSet::new();
+
+    loop {
+        tokio::select! {
+            result = listener.accept() => match result {
+                Ok((socket, addr)) => {
+                    accepted.fetch_add(1, Ordering::Relaxed);
+                    connections.spawn(handle(socket, addr, state.clone()));
+                }
+                Err(e) => tracing::error!("Failed to accept client connection: {:?}", e),
+            },
+            // Clean up after connections that have closed.
+            Some(_) = connections.join_next() => {},
+            _ = shutdown.changed() => break,
+        }
+    }
+
+    connections.shutdown().await;
+}
+
+async fn handle(socket: TcpStream, addr: SocketAddr, state: State) {
+    tracing::info!("New client connection from {:?}", addr);
+
+    match Connection::new(socket, addr, state).await {
+        Ok(conn) => conn.process().await,
+        Err(e) => {
+            tracing::error!("{}", e);
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{codec::MessageCodec, frame::Frame};
+    use futures::{SinkExt, StreamExt};
+    use tokio_util::codec::Framed;
+
+    async fn join(server: &ChatServer, nickname: &str) -> Framed<TcpStream, MessageCodec> {
+        let socket = TcpStream::connect(server.local_addr()).await.unwrap();
+        let mut messages = Framed::new(socket, MessageCodec {});
+
+        messages
+            .send(Frame::Message(format!("{nickname},password123")))
+            .await
+            .unwrap();
+
+        // Wait for the handshake to complete.
+        messages.next().await.unwrap().unwrap();
+
+        messages
+    }
+
+    #[tokio::test]
+    async fn listens_on_ephemeral_port() {
+        let server = ChatServer::builder()
+            .bind("127.0.0.1:0")
+            .start()
+            .await
+            .unwrap();
+
+        assert_ne!(server.local_addr().port(), 0);
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn returns_error_without_listeners() {
+        let result = ChatServer::builder().start().await;
+
+        assert!(matches!(result, Err(ServerError::NoListeners)));
+    }
+
+    #[tokio::test]
+    async fn reports_stats() {
+        let server = ChatServer::builder()
+            .bind("127.0.0.1:0")
+            .start()
+            .await
+            .unwrap();
+
+        let mut alice = join(&server, "alice").await;
+        alice.send(Frame::Message("hi".into())).await.unwrap();
+
+        // Frames are handled in order, so the message has been sent once the
+        // server responds to a later command.
+        alice.send(Frame::Message("/help".into())).await.unwrap();
+        while !matches!(alice.next().await, Some(Ok(Frame::ServerMessage(_)))) {}
+
+        let stats = server.stats().await;
+
+        assert_eq!(stats.online, 1);
+        assert_eq!(stats.connections, 1);
+        assert_eq!(stats.messages, 1);
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn calls_hooks_and_disconnects_clients_on_shutdown() {
+        let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+        let server = ChatServer::builder()
+            .bind("127.0.0.1:0")
+            .hook(move |event| {
+                let _ = sender.send(event.clone());
+            })
+            .start()
+            .await
+            .unwrap();
+
+        let mut alice = join(&server, "alice").await;
+
+        assert!(matches!(events.recv().await, Some(PluginEvent::Join(_))));
+
+        server.shutdown().await;
+
+        while let Some(Ok(_)) = alice.next().await {}
+    }
+}
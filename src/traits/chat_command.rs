@@ -0,0 +1,25 @@
+use super::CommandApply;
+use crate::{args::Args, domain::Permission, errors::CommandError};
+
+/// Derives `ChatCommand` and the parsing of a command's arguments from an
+/// annotated struct. See the `realtime-chat-derive` crate for the attributes.
+pub use realtime_chat_derive::ChatCommand;
+
+/// A command that users can invoke, along with the details displayed by `/help`.
+///
+/// This is usually derived rather than implemented by hand. Commands aren't
+/// registered automatically: to make one available, declare and re-export its
+/// module in `commands/mod.rs`, and add it to the `commands!` registry there,
+/// which generates the `Command` enum that commands are parsed into.
+pub trait ChatCommand: CommandApply + TryFrom<Args, Error = CommandError> {
+    /// The name used to invoke the command, e.g. `whisper` for `/whisper`.
+    const NAME: &'static str;
+    /// A summary of the command's arguments, e.g. `/whisper <username> <message...>`
+    const USAGE: &'static str;
+    /// A short description of what the command does.
+    const ABOUT: &'static str;
+    /// Alternative names that may also be used to invoke the command.
+    const ALIASES: &'static [&'static str];
+    /// The permission level required to run the command.
+    const PERMISSION: Permission;
+}
@@ -1,3 +1,7 @@
+mod chat_command;
 mod command_apply;
+mod plugin;
 
+pub use chat_command::*;
 pub use command_apply::*;
+pub use plugin::*;
@@ -0,0 +1,43 @@
+use crate::{
+    args::Args,
+    commands::CommandInfo,
+    domain::{PluginContext, PluginEvent, Username},
+    errors::CommandError,
+};
+use async_trait::async_trait;
+
+/// A bot or other extension that runs inside the server, registered when the
+/// server is built. Plugins can provide their own commands, observe what
+/// happens in the chat, and send frames to users through their `PluginContext`.
+///
+/// A plugin that panics is logged and otherwise ignored, so it can't take
+/// down the connection that triggered it.
+#[async_trait]
+pub trait Plugin: Send + Sync {
+    /// The name of the plugin, shown on the messages it sends.
+    fn name(&self) -> &str;
+
+    /// The commands provided by the plugin, listed by `/help`. Commands with
+    /// the same name as a built-in command are ignored.
+    fn commands(&self) -> Vec<CommandInfo> {
+        vec![]
+    }
+
+    /// Runs one of the commands provided by the plugin on behalf of a user.
+    async fn on_command(
+        &self,
+        ctx: &PluginContext,
+        sender: &Username,
+        name: &str,
+        args: Args,
+    ) -> Result<(), CommandError> {
+        let _ = (ctx, sender, args);
+
+        Err(CommandError::UnknownCommand(name.into()))
+    }
+
+    /// Observes a message being sent, or a user joining or leaving the chat.
+    async fn on_event(&self, ctx: &PluginContext, event: &PluginEvent) {
+        let _ = (ctx, event);
+    }
+}
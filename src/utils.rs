@@ -1,41 +1,119 @@
-use crate::errors::CommandError;
-use std::slice::Iter;
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
 
-/// Attempts to pop an argument from the provided iterator. Returns a
-/// `CommandError::MissingArgument` error variant if not found.
-pub fn try_pop_arg(args: &mut Iter<&str>, name: &str) -> Result<String, CommandError> {
-    let arg = args
-        .next()
-        .ok_or_else(|| CommandError::MissingArgument(name.into()))?
-        .to_string();
+/// Runs blocking work, such as deliberately slow password hashing, on a thread
+/// where it won't hold up the other tasks running on the same worker.
+pub async fn run_blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    match tokio::task::spawn_blocking(work).await {
+        Ok(value) => value,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
+
+/// Options to open a file with, which create it so that only its owner can
+/// read or write it, for files that hold secrets.
+pub fn private_file() -> OpenOptions {
+    let mut options = OpenOptions::new();
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options
+}
+
+/// Replaces the contents of a file that only its owner can read, such as the
+/// file registered accounts are saved to.
+pub fn save_private_file(path: &Path, contents: String) -> io::Result<()> {
+    // The contents are written to a temporary file first, so that they
+    // aren't lost if the server stops part way through writing them.
+    let temp = path.with_extension("tmp");
+
+    match fs::remove_file(&temp) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let mut file = private_file().write(true).create_new(true).open(&temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+
+    fs::rename(&temp, path)
+}
+
+/// A name that no other test, in this or any other test process, uses at the
+/// same time, for files and environment variables shared between tests.
+#[cfg(test)]
+pub fn unique_name(prefix: &str) -> String {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    format!("{prefix}-{}-{count}", std::process::id())
+}
+
+/// A path in the temporary directory that no other test uses, which may be
+/// left over from an earlier run.
+#[cfg(test)]
+pub fn temp_path(prefix: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(unique_name(prefix))
+}
+
+/// Formats a duration in its largest whole unit, for example: `5 minutes`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    let (value, unit) = match seconds {
+        0..=59 => (seconds, "second"),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86399 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+
+    match value {
+        1 => format!("1 {unit}"),
+        value => format!("{value} {unit}s"),
+    }
+}
 
-    Ok(arg)
+/// Formats how long ago something happened in a human readable form,
+/// for example: `5 minutes ago`.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    match elapsed.as_secs() {
+        0..=9 => String::from("just now"),
+        _ => format!("{} ago", format_duration(elapsed)),
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use fake::{faker::lorem::en::Word, Fake};
 
     #[test]
-    fn successfully_pops_arg() {
-        let name = Word().fake();
-        let args = vec![name];
-        let mut iter = args.iter();
-
-        let result = try_pop_arg(&mut iter, name);
-
-        assert_eq!(result, Ok(name.into()));
+    fn generates_unique_names() {
+        assert_ne!(unique_name("test"), unique_name("test"));
     }
 
     #[test]
-    fn returns_error_if_no_arg() {
-        let args = vec![];
-        let mut iter = args.iter();
+    fn formats_duration_in_largest_unit() {
+        assert_eq!(format_duration(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_duration(Duration::from_secs(300)), "5 minutes");
+    }
 
-        let name = Word().fake();
-        let result = try_pop_arg(&mut iter, name);
+    #[test]
+    fn formats_recent_elapsed_time() {
+        assert_eq!(format_elapsed(Duration::from_secs(3)), "just now");
+    }
 
-        assert_eq!(result, Err(CommandError::MissingArgument(name.into())))
+    #[test]
+    fn formats_elapsed_time_in_largest_unit() {
+        assert_eq!(format_elapsed(Duration::from_secs(42)), "42 seconds ago");
+        assert_eq!(format_elapsed(Duration::from_secs(60)), "1 minute ago");
+        assert_eq!(format_elapsed(Duration::from_secs(7300)), "2 hours ago");
+        assert_eq!(format_elapsed(Duration::from_secs(86400 * 3)), "3 days ago");
     }
 }
@@ -0,0 +1,543 @@
+mod common;
+
+use async_trait::async_trait;
+use common::TestServer;
+use fake::{faker::lorem::en::Sentence, Fake};
+use realtime_chat::{
+    args::Args,
+    client::{ChatMessage, Direction, Event, Whisper},
+    codec::MAX_LENGTH,
+    commands::CommandInfo,
+    config::ServerConfig,
+    domain::{Permission, PluginContext, Username},
+    errors::{ClientError, CommandError, UsernameError},
+    frame::Frame,
+    traits::Plugin,
+};
+use std::{str::FromStr, time::Duration};
+
+/// A plugin with a `/roll` command, which always rolls a 4.
+struct Dice;
+
+#[async_trait]
+impl Plugin for Dice {
+    fn name(&self) -> &str {
+        "dice"
+    }
+
+    fn commands(&self) -> Vec<CommandInfo> {
+        vec![CommandInfo {
+            name: "roll",
+            usage: "",
+            about: "Roll a die.",
+            aliases: &[],
+            permission: Permission::User,
+        }]
+    }
+
+    async fn on_command(
+        &self,
+        ctx: &PluginContext,
+        sender: &Username,
+        _name: &str,
+        _args: Args,
+    ) -> Result<(), CommandError> {
+        ctx.say_to(sender, "You rolled a 4").await;
+
+        Ok(())
+    }
+}
+
+fn message(id: u64, author: &str, text: &str) -> Event {
+    Event::Message(ChatMessage {
+        id,
+        author: author.into(),
+        text: text.into(),
+    })
+}
+
+#[tokio::test]
+async fn notifies_others_when_user_joins() {
+    let server = TestServer::start().await;
+    let mut alice = server.join("alice").await;
+    let mut bob = server.join("bob").await;
+
+    alice.expect(bob.joined_notice()).await;
+    bob.expect_nothing().await;
+}
+
+#[tokio::test]
+async fn notifies_others_when_user_leaves() {
+    let server = TestServer::start().await;
+    let [alice, mut bob] = server.join_all(["alice", "bob"]).await;
+    let notice = alice.left_notice();
+
+    alice.quit();
+
+    bob.expect(notice).await;
+}
+
+#[tokio::test]
+async fn broadcasts_messages_to_everyone_except_sender() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob, mut carol] = server.join_all(["alice", "bob", "carol"]).await;
+    let text: String = Sentence(1..5).fake();
+
+    alice.send(&text).await;
+
+    alice.expect(Event::Ack(Some(1))).await;
+    bob.expect(message(1, &alice.username, &text)).await;
+    carol
+        .expect_within(
+            message(1, &alice.username, &text),
+            Duration::from_millis(500),
+        )
+        .await;
+    alice.expect_nothing().await;
+}
+
+#[tokio::test]
+async fn broadcasts_between_many_clients() {
+    let server = TestServer::start().await;
+    let nicknames = ["alice", "bob", "carol", "dave", "erin"];
+    let mut clients = server.join_all(nicknames).await;
+
+    for (id, sender) in (1..).zip(0..clients.len()) {
+        let text: String = Sentence(1..5).fake();
+        let author = clients[sender].username.clone();
+
+        clients[sender].send(&text).await;
+        clients[sender].expect(Event::Ack(Some(id))).await;
+
+        for (index, client) in clients.iter_mut().enumerate() {
+            if index != sender {
+                client.expect(message(id, &author, &text)).await;
+            }
+        }
+    }
+
+    for client in &mut clients {
+        client.expect_nothing().await;
+    }
+}
+
+#[tokio::test]
+async fn delivers_whispers_to_recipient_only() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob, mut carol] = server.join_all(["alice", "bob", "carol"]).await;
+
+    alice.send(&format!("/whisper {} psst", bob.username)).await;
+
+    bob.expect(Event::Whisper(Whisper {
+        direction: Direction::From,
+        username: alice.username.clone(),
+        text: "psst".into(),
+    }))
+    .await;
+    alice
+        .expect_frame(Frame::PrivateMessage(format!("To {}: psst", bob.username)))
+        .await;
+    alice.expect(Event::Ack(None)).await;
+    carol.expect_nothing().await;
+}
+
+#[tokio::test]
+async fn ignores_whispers_to_self() {
+    let server = TestServer::start().await;
+    let mut alice = server.join("alice").await;
+
+    alice.send(&format!("/w {} hello?", alice.username)).await;
+
+    alice.expect(Event::Ack(None)).await;
+    alice.expect_nothing().await;
+}
+
+#[tokio::test]
+async fn broadcasts_actions_to_everyone_except_sender() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob] = server.join_all(["alice", "bob"]).await;
+
+    alice.send("/me waving").await;
+
+    bob.expect(Event::Action(format!("{} is waving", alice.username)))
+        .await;
+    alice.expect(Event::Ack(None)).await;
+    alice.expect_nothing().await;
+}
+
+#[tokio::test]
+async fn sends_actions_as_server_messages_without_capability() {
+    let server = TestServer::start().await;
+    let mut alice = server.join("alice").await;
+
+    let mut config = server.connect_config("bob");
+    config.capabilities.actions = false;
+    let mut bob = server.connect(&config).await.unwrap();
+    alice.expect(bob.joined_notice()).await;
+
+    alice.send("/me waving").await;
+
+    bob.expect(Event::ServerMessage(format!(
+        "{} is waving",
+        alice.username
+    )))
+    .await;
+}
+
+#[tokio::test]
+async fn returns_errors_to_sender_only() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob] = server.join_all(["alice", "bob"]).await;
+
+    alice.send("/nope").await;
+    alice
+        .expect(Event::Error(
+            CommandError::UnknownCommand("nope".into()).to_string(),
+        ))
+        .await;
+
+    alice.send("/whisper nobody hi").await;
+    alice
+        .expect(Event::Error(
+            CommandError::ExecutionError("No user with username nobody".into()).to_string(),
+        ))
+        .await;
+
+    alice.send("/me").await;
+    alice
+        .expect(Event::Error(
+            CommandError::MissingArgument("message".into()).to_string(),
+        ))
+        .await;
+
+    bob.expect_nothing().await;
+}
+
+#[tokio::test]
+async fn rejects_invalid_nickname() {
+    let server = TestServer::start().await;
+    let config = server.connect_config("not valid");
+
+    let result = server.connect(&config).await;
+
+    assert!(matches!(
+        result,
+        Err(ClientError::Rejected(reason)) if reason == UsernameError::InvalidCharacter(' ').to_string()
+    ));
+}
+
+#[tokio::test]
+async fn cleans_up_after_disconnect() {
+    let server = TestServer::start().await;
+    let [alice, mut bob] = server.join_all(["alice", "bob"]).await;
+    let username = alice.username.clone();
+
+    alice.quit();
+    bob.expect(Event::ServerMessage(format!(
+        "{username} has left the chat"
+    )))
+    .await;
+
+    let stats = server.stats().await;
+    assert_eq!(stats.online, 1);
+    assert_eq!(stats.detached_sessions, 0);
+
+    bob.send(&format!("/whisper {username} hi")).await;
+    bob.expect(Event::Error(
+        CommandError::ExecutionError(format!("No user with username {username}")).to_string(),
+    ))
+    .await;
+
+    // The nickname is free to join with again.
+    let alice = server.join("alice").await;
+    bob.expect(alice.joined_notice()).await;
+}
+
+#[tokio::test]
+async fn keeps_session_of_dropped_connection_during_grace_period() {
+    let server = TestServer::with_config(ServerConfig::default()).await;
+    let [alice, mut bob] = server.join_all(["alice", "bob"]).await;
+
+    alice.quit();
+    bob.expect_nothing().await;
+
+    let stats = server.stats().await;
+    assert_eq!(stats.online, 2);
+    assert_eq!(stats.detached_sessions, 1);
+
+    // Shutting down doesn't wait for the grace period to end.
+    tokio::time::timeout(common::TIMEOUT, server.shutdown())
+        .await
+        .expect("server should shut down");
+}
+
+#[tokio::test]
+async fn disconnects_clients_on_shutdown() {
+    let server = TestServer::start().await;
+    let mut alice = server.join("alice").await;
+
+    server.shutdown().await;
+
+    alice.expect_closed().await;
+}
+
+#[tokio::test]
+async fn rejects_second_connection_for_same_user() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob] = server.join_all(["alice", "bob"]).await;
+
+    let result = server.connect(&server.connect_config("alice")).await;
+
+    assert!(matches!(
+        result,
+        Err(ClientError::Rejected(reason))
+            if reason == UsernameError::AlreadyConnected(alice.username.clone()).to_string()
+    ));
+
+    // The first connection is unaffected.
+    bob.send("hi").await;
+    bob.expect(Event::Ack(Some(1))).await;
+    alice.expect(message(1, &bob.username, "hi")).await;
+}
+
+#[tokio::test]
+async fn filters_chat_text_but_not_commands() {
+    let config = ServerConfig::from_str(
+        r#"
+        [sessions]
+        grace_period_secs = 0
+
+        [filters]
+        words = ["heck"]
+        rules = [{ pattern = "secret", action = "block", reason = "no secrets" }]
+        "#,
+    )
+    .unwrap();
+
+    let server = TestServer::with_config(config).await;
+    let [mut alice, mut bob] = server.join_all(["alice", "bob"]).await;
+
+    alice.send("/register secret-heck").await;
+    alice
+        .expect(Event::ServerMessage(
+            "Registered alice. Connect with this password from now on.".into(),
+        ))
+        .await;
+    alice.expect(Event::Ack(None)).await;
+
+    alice.send("/me says heck").await;
+    alice.expect(Event::Ack(None)).await;
+    bob.expect(Event::Action(format!("{} is says ****", alice.username)))
+        .await;
+
+    alice.send("/me has a secret").await;
+    alice
+        .expect(Event::Error("Your message was blocked: no secrets.".into()))
+        .await;
+    bob.expect_nothing().await;
+
+    // The password was registered as it was written.
+    let mut config = server.connect_config("alice");
+    config.password = "secret-heck".into();
+    let notice = alice.left_notice();
+    alice.quit();
+    bob.expect(notice).await;
+
+    server.connect(&config).await.unwrap();
+}
+
+#[tokio::test]
+async fn rejects_aliases_of_plugin_commands() {
+    let server = TestServer::with_plugin(Dice).await;
+    let mut alice = server.join("alice").await;
+
+    alice.send("/alias roll me rolls a 6").await;
+    alice
+        .expect(Event::Error(
+            CommandError::InvalidArgument("name".into(), "/roll is already a command".into())
+                .to_string(),
+        ))
+        .await;
+
+    // Plugins reply through the user's channel, so the ack arrives first.
+    alice.send("/roll").await;
+    alice.expect(Event::Ack(None)).await;
+    alice
+        .expect(Event::ServerMessage("[dice] You rolled a 4".into()))
+        .await;
+}
+
+#[tokio::test]
+async fn mutes_users_but_not_voiced_users() {
+    let alice = Username::from_credentials("alice", common::PASSWORD).unwrap();
+    let config = ServerConfig::from_str(&format!(
+        r#"
+        [permissions]
+        voice = ["{alice}"]
+
+        [sessions]
+        grace_period_secs = 0
+
+        [filters]
+        rules = [{{ pattern = "buy now", action = "mute", mute_for = "10m", reason = "spam" }}]
+        "#
+    ))
+    .unwrap();
+
+    let server = TestServer::with_config(config).await;
+    let [mut alice, mut bob] = server.join_all(["alice", "bob"]).await;
+
+    alice.send("buy now").await;
+    alice
+        .expect(Event::Error("Your message was blocked: spam.".into()))
+        .await;
+
+    alice.send("hi").await;
+    alice.expect(Event::Ack(Some(1))).await;
+    bob.expect(message(1, &alice.username, "hi")).await;
+
+    bob.send("buy now").await;
+    bob.expect(Event::Error(
+        "Your message was blocked and you have been muted for 10 minutes: spam.".into(),
+    ))
+    .await;
+
+    bob.send("hi").await;
+    assert!(matches!(
+        bob.recv().await,
+        Event::Error(reason) if reason.starts_with("You are muted for another")
+    ));
+    alice.expect_nothing().await;
+}
+
+#[tokio::test]
+async fn sends_copies_of_own_messages_when_asked() {
+    let server = TestServer::start().await;
+    let mut alice = server.join("alice").await;
+
+    let mut config = server.connect_config("bob");
+    config.capabilities.echo = true;
+    let mut bob = server.connect(&config).await.unwrap();
+    alice.expect(bob.joined_notice()).await;
+
+    bob.send("hi").await;
+    bob.expect(message(1, &bob.username, "hi")).await;
+    bob.expect(Event::Ack(Some(1))).await;
+    alice.expect(message(1, &bob.username, "hi")).await;
+
+    // Knowing the ID, bob can reply to their own message.
+    bob.send("/reply 1 hello?").await;
+    let reply = bob.recv().await;
+    bob.expect(Event::Ack(None)).await;
+    assert_eq!(alice.recv().await, reply);
+    assert!(matches!(reply, Event::Reply { .. }));
+}
+
+#[tokio::test]
+async fn highlights_replies_that_mention_users() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob, mut carol] = server.join_all(["alice", "bob", "carol"]).await;
+
+    alice.send("lunch?").await;
+    alice.expect(Event::Ack(Some(1))).await;
+    bob.expect(message(1, &alice.username, "lunch?")).await;
+    carol.expect(message(1, &alice.username, "lunch?")).await;
+
+    bob.send("/reply 1 @alice sure").await;
+    bob.expect(Event::Ack(None)).await;
+
+    let quote = format!("[1] {}: lunch?", alice.username);
+    let author = bob.username.clone();
+    let reply = |mention| Event::Reply {
+        quote: quote.clone(),
+        message: ChatMessage {
+            id: 2,
+            author: author.clone(),
+            text: "@alice sure".into(),
+        },
+        mention,
+    };
+    alice.expect(reply(true)).await;
+    carol.expect(reply(false)).await;
+
+    alice.send("/mentions").await;
+    alice
+        .expect(Event::ServerMessage("Your most recent mention:".into()))
+        .await;
+    alice
+        .expect(Event::ServerMessage(format!(
+            "[2] {}: @alice sure",
+            bob.username
+        )))
+        .await;
+}
+
+#[tokio::test]
+async fn rejects_messages_too_long_to_send_once_formatted() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob] = server.join_all(["alice", "bob"]).await;
+    let too_long = CommandError::MessageTooLong(MAX_LENGTH).to_string();
+
+    // The text fits in a frame, but not once it's prefixed with its ID and
+    // author.
+    alice.send(&"x".repeat(MAX_LENGTH - 8)).await;
+    alice.expect(Event::Error(too_long.clone())).await;
+
+    let prefix = format!("[1] {}: ", alice.username);
+    let text = "x".repeat(MAX_LENGTH - prefix.len());
+    alice.send(&text).await;
+    alice.expect(Event::Ack(Some(1))).await;
+    bob.expect(message(1, &alice.username, &text)).await;
+
+    // Replies also quote the message they reply to.
+    alice
+        .send(&format!("/reply 1 {}", "x".repeat(MAX_LENGTH - 80)))
+        .await;
+    alice.expect(Event::Error(too_long.clone())).await;
+
+    alice
+        .send(&format!("/me {}", "x".repeat(MAX_LENGTH - 8)))
+        .await;
+    alice.expect(Event::Error(too_long)).await;
+
+    // Bob is still connected.
+    alice.send("hi").await;
+    alice.expect(Event::Ack(Some(2))).await;
+    bob.expect(message(2, &alice.username, "hi")).await;
+}
+
+#[tokio::test]
+async fn does_not_mistake_multiline_mentions_for_replies() {
+    let server = TestServer::start().await;
+    let [mut alice, mut bob] = server.join_all(["alice", "bob"]).await;
+    let text = "hi\n[99] admin!xyz: approved @bob";
+
+    alice.send(text).await;
+    alice.expect(Event::Ack(Some(1))).await;
+
+    bob.expect(Event::Mention(ChatMessage {
+        id: 1,
+        author: alice.username.clone(),
+        text: text.into(),
+    }))
+    .await;
+}
+
+#[tokio::test]
+async fn lists_mentions_made_while_offline() {
+    let server = TestServer::start().await;
+    let mut alice = server.join("alice").await;
+
+    alice.send("is @bob around?").await;
+    alice.expect(Event::Ack(Some(1))).await;
+
+    let mut bob = server.join("bob").await;
+    bob.send("/mentions").await;
+    bob.expect(Event::ServerMessage("Your most recent mention:".into()))
+        .await;
+    bob.expect(Event::ServerMessage(format!(
+        "[1] {}: is @bob around?",
+        alice.username
+    )))
+    .await;
+}
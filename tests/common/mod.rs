@@ -0,0 +1,224 @@
+//! A harness for testing the server end-to-end, with real clients connected
+//! to a server running on an ephemeral port.
+
+use futures::StreamExt;
+use realtime_chat::{
+    client::{ChatClient, ConnectConfig, Event},
+    config::ServerConfig,
+    domain::{Capabilities, Username},
+    errors::ClientError,
+    frame::Frame,
+    server::{ChatServer, ChatServerBuilder, ServerStats},
+    traits::Plugin,
+};
+use std::time::Duration;
+use tokio::time::timeout;
+
+// How long a client waits for an event before the test fails.
+pub const TIMEOUT: Duration = Duration::from_secs(2);
+
+// How long a client waits to make sure that it receives nothing.
+pub const QUIET_PERIOD: Duration = Duration::from_millis(200);
+
+pub const PASSWORD: &str = "password123";
+
+/// A server running in the background for the duration of a test.
+pub struct TestServer {
+    server: ChatServer,
+}
+
+/// A client connected to a `TestServer`, which asserts on the events it
+/// receives in order.
+pub struct TestClient {
+    pub username: String,
+    client: ChatClient,
+}
+
+impl TestServer {
+    /// Starts a server where users leave the chat as soon as their connection
+    /// drops, rather than after a grace period.
+    pub async fn start() -> Self {
+        Self::with_config(Self::config()).await
+    }
+
+    pub async fn with_config(config: ServerConfig) -> Self {
+        Self::from_builder(ChatServer::builder().config(config)).await
+    }
+
+    /// Starts the same server as `start`, with a plugin registered.
+    pub async fn with_plugin(plugin: impl Plugin + 'static) -> Self {
+        Self::from_builder(ChatServer::builder().config(Self::config()).plugin(plugin)).await
+    }
+
+    async fn from_builder(builder: ChatServerBuilder) -> Self {
+        let server = builder
+            .bind("127.0.0.1:0")
+            .start()
+            .await
+            .expect("server should start");
+
+        Self { server }
+    }
+
+    fn config() -> ServerConfig {
+        let mut config = ServerConfig::default();
+        config.sessions.grace_period_secs = 0;
+
+        config
+    }
+
+    /// Configures a client to join as a nickname. Typing indicators and
+    /// presence are disabled, so that clients only receive the chat itself,
+    /// and acks are enabled so that clients know when the server has handled
+    /// what they sent. Actions are enabled, as they are in `ConnectConfig`, but
+    /// clients aren't sent copies of their own messages.
+    pub fn connect_config(&self, nickname: &str) -> ConnectConfig {
+        let mut config = ConnectConfig::new(
+            self.server.local_addr().to_string(),
+            nickname.into(),
+            PASSWORD.into(),
+        );
+        config.capabilities = Capabilities {
+            typing: false,
+            presence: false,
+            acks: true,
+            actions: true,
+            echo: false,
+        };
+
+        config
+    }
+
+    pub async fn connect(&self, config: &ConnectConfig) -> Result<TestClient, ClientError> {
+        let client = ChatClient::connect(config).await?;
+
+        // Look up the username the server generated, rather than hashing the
+        // tripcode again, as it's slow in debug builds.
+        let username = self
+            .server
+            .state()
+            .lock()
+            .await
+            .peers
+            .keys()
+            .find(|username| username.nickname() == config.nickname)
+            .map(Username::to_string)
+            .expect("user should be in the chat");
+
+        Ok(TestClient { username, client })
+    }
+
+    /// Joins the chat as a nickname. Users already in the chat are sent a
+    /// notice, which the test is left to assert on.
+    pub async fn join(&self, nickname: &str) -> TestClient {
+        self.connect(&self.connect_config(nickname))
+            .await
+            .unwrap_or_else(|e| panic!("{nickname} should join the chat: {e}"))
+    }
+
+    /// Joins the chat as each nickname in turn, waiting until every user
+    /// already in the chat has received the notice that the next one joined.
+    pub async fn join_all<const N: usize>(&self, nicknames: [&str; N]) -> [TestClient; N] {
+        let mut clients: Vec<TestClient> = vec![];
+
+        for nickname in nicknames {
+            let client = self.join(nickname).await;
+            let notice = client.joined_notice();
+
+            for other in &mut clients {
+                other.expect(notice.clone()).await;
+            }
+
+            clients.push(client);
+        }
+
+        clients
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("a client joined for each nickname"))
+    }
+
+    pub async fn stats(&self) -> ServerStats {
+        self.server.stats().await
+    }
+
+    pub async fn shutdown(self) {
+        self.server.shutdown().await;
+    }
+}
+
+impl TestClient {
+    /// Sends a line to the chat as if it was typed.
+    pub async fn send(&mut self, message: &str) {
+        self.client
+            .send(message)
+            .await
+            .unwrap_or_else(|e| panic!("{} should send {message:?}: {e}", self.username));
+    }
+
+    /// Waits for the next event, failing the test if none arrives in time.
+    pub async fn recv(&mut self) -> Event {
+        self.recv_within(TIMEOUT).await
+    }
+
+    pub async fn recv_within(&mut self, duration: Duration) -> Event {
+        match timeout(duration, self.client.next()).await {
+            Ok(Some(Ok(event))) => event,
+            Ok(Some(Err(e))) => panic!("{} failed to read event: {e}", self.username),
+            Ok(None) => panic!("{} was disconnected", self.username),
+            Err(_) => panic!("{} received nothing within {duration:?}", self.username),
+        }
+    }
+
+    /// Asserts that the next event is the provided one.
+    pub async fn expect(&mut self, expected: Event) {
+        self.expect_within(expected, TIMEOUT).await;
+    }
+
+    /// Asserts that the next event is the provided one, and that it arrives
+    /// within a duration.
+    pub async fn expect_within(&mut self, expected: Event, duration: Duration) {
+        let event = self.recv_within(duration).await;
+
+        assert_eq!(event, expected, "unexpected event for {}", self.username);
+    }
+
+    /// Asserts that the next frame is the provided one.
+    pub async fn expect_frame(&mut self, expected: Frame) {
+        let frame = Frame::from(self.recv().await);
+
+        assert_eq!(frame, expected, "unexpected frame for {}", self.username);
+    }
+
+    /// Asserts that nothing is received for a short while.
+    pub async fn expect_nothing(&mut self) {
+        if let Ok(Some(event)) = timeout(QUIET_PERIOD, self.client.next()).await {
+            panic!("{} unexpectedly received {event:?}", self.username);
+        }
+    }
+
+    /// Asserts that the server has closed the connection.
+    pub async fn expect_closed(&mut self) {
+        loop {
+            match timeout(TIMEOUT, self.client.next()).await {
+                Ok(Some(Ok(_))) => continue,
+                Ok(Some(Err(_)) | None) => return,
+                Err(_) => panic!("{} is still connected", self.username),
+            }
+        }
+    }
+
+    /// The notice other users receive when this user joins the chat.
+    pub fn joined_notice(&self) -> Event {
+        Event::ServerMessage(format!("{} has joined the chat", self.username))
+    }
+
+    /// The notice other users receive when this user leaves the chat.
+    pub fn left_notice(&self) -> Event {
+        Event::ServerMessage(format!("{} has left the chat", self.username))
+    }
+
+    /// Disconnects from the server.
+    pub fn quit(self) {
+        drop(self.client);
+    }
+}
@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use realtime_chat::{
+    args::Args,
+    domain::{Connection, Permission},
+    errors::CommandError,
+    traits::{ChatCommand, CommandApply},
+};
+
+/// A command with every kind of argument, as none of the built-in commands
+/// have flags or options.
+#[derive(Debug, PartialEq, ChatCommand)]
+#[command(
+    name = "kick",
+    aliases = ["k"],
+    permission = Moderator,
+    about = "Remove a user from the chat."
+)]
+struct Kick {
+    username: String,
+    #[arg(optional)]
+    minutes: Option<u32>,
+    #[arg(flag, short = 'q')]
+    quiet: bool,
+    #[arg(flag, short = 'n')]
+    dry_run: bool,
+    #[arg(option, short = 'r')]
+    reason: Option<String>,
+}
+
+#[async_trait]
+impl CommandApply for Kick {
+    async fn apply(&self, _conn: &mut Connection) -> Result<(), CommandError> {
+        Ok(())
+    }
+}
+
+fn kick(args: &str) -> Result<Kick, CommandError> {
+    Kick::try_from(Args::from(args))
+}
+
+#[test]
+fn describes_command() {
+    assert_eq!(Kick::NAME, "kick");
+    assert_eq!(Kick::ALIASES, ["k"]);
+    assert_eq!(Kick::ABOUT, "Remove a user from the chat.");
+    assert_eq!(Kick::PERMISSION, Permission::Moderator);
+}
+
+#[test]
+fn lists_options_after_positional_arguments_in_usage() {
+    assert_eq!(
+        Kick::USAGE,
+        "/kick <username> [minutes] [--quiet|-q] [--dry-run|-n] [--reason|-r <reason>]"
+    );
+}
+
+#[test]
+fn parses_flags_and_options_anywhere() {
+    let expected = Kick {
+        username: "alice".into(),
+        minutes: Some(10),
+        quiet: true,
+        dry_run: false,
+        reason: Some("too loud".into()),
+    };
+
+    assert_eq!(kick("-q alice --reason \"too loud\" 10"), Ok(expected));
+}
+
+#[test]
+fn parses_combined_short_flags_and_inline_option_values() {
+    let expected = Kick {
+        username: "alice".into(),
+        minutes: None,
+        quiet: true,
+        dry_run: true,
+        reason: Some("spam".into()),
+    };
+
+    assert_eq!(kick("-qn alice --reason=spam"), Ok(expected));
+}
+
+#[test]
+fn reads_arguments_after_double_dash_as_positional() {
+    let kicked = kick("-- -q").unwrap();
+
+    assert_eq!(kicked.username, "-q");
+    assert!(!kicked.quiet);
+}
+
+#[test]
+fn returns_errors_for_invalid_options() {
+    assert_eq!(
+        kick("alice --force"),
+        Err(CommandError::UnknownOption("--force".into()))
+    );
+    assert_eq!(
+        kick("alice --reason"),
+        Err(CommandError::MissingArgument("reason".into()))
+    );
+    assert_eq!(
+        kick("alice ten"),
+        Err(CommandError::InvalidArgument(
+            "minutes".into(),
+            "ten is not a valid number".into()
+        ))
+    );
+}
+
+#[test]
+fn rejects_invalid_commands_at_compile_time() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
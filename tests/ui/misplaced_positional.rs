@@ -0,0 +1,11 @@
+use realtime_chat::traits::ChatCommand;
+
+#[derive(ChatCommand)]
+#[command(name = "kick")]
+struct Kick {
+    #[arg(optional)]
+    minutes: Option<u32>,
+    username: String,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use realtime_chat::traits::ChatCommand;
+
+#[derive(ChatCommand)]
+#[command(about = "Has no name.")]
+struct Nameless {}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use realtime_chat::traits::ChatCommand;
+
+#[derive(ChatCommand)]
+#[command(name = "kick")]
+struct Kick {
+    #[arg(short = 'u')]
+    username: String,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use realtime_chat::traits::ChatCommand;
+
+#[derive(ChatCommand)]
+#[command(name = "kick")]
+struct Kick(String);
+
+fn main() {}